@@ -0,0 +1,20 @@
+#ifndef STAGE_INTERFACE_GLSL
+#define STAGE_INTERFACE_GLSL
+
+//One Source File Compiles To Every Stage, These Macros Turn Each Declaration Into What The Current Stage Needs
+
+#if SHADER_FREQUENCY_VERTEX
+#define VERTEX_INPUT(locationId, dataType, name) layout(location = locationId) in dataType name;
+#define VERTEX_TO_FRAGMENT(locationId, dataType, name) layout(location = locationId) out dataType name;
+#define FRAGMENT_OUTPUT(locationId, dataType, name) dataType name;
+#define vert ENTRY_POINT
+#endif
+
+#if SHADER_FREQUENCY_FRAGMENT
+#define VERTEX_INPUT(locationId, dataType, name) dataType name;
+#define VERTEX_TO_FRAGMENT(locationId, dataType, name) layout(location = locationId) in dataType name;
+#define FRAGMENT_OUTPUT(locationId, dataType, name) layout(location = locationId) out dataType name;
+#define frag ENTRY_POINT
+#endif
+
+#endif//STAGE_INTERFACE_GLSL
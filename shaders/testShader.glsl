@@ -1,19 +1,13 @@
 #version 450
 #extension GL_ARB_separate_shader_objects : enable
 
-#if SHADER_FREQUENCY_VERTEX
-#define VERTEX_INPUT(locationId, dataType, name) layout(location = locationId) in dataType name;
-#define VERTEX_TO_FRAGMENT(locationId, dataType, name) layout(location = locationId) out dataType name;
-#define FRAGMENT_OUTPUT(locationId, dataType, name) dataType name;
-#define vert ENTRY_POINT
-#endif
+#include <stage_interface.glsl>
 
-#if SHADER_FREQUENCY_FRAGMENT
-#define VERTEX_INPUT(locationId, dataType, name) dataType name;
-#define VERTEX_TO_FRAGMENT(locationId, dataType, name) layout(location = locationId) in dataType name;
-#define FRAGMENT_OUTPUT(locationId, dataType, name) layout(location = locationId) out dataType name;
-#define frag ENTRY_POINT
-#endif
+layout(set = 0, binding = 0) uniform FrameUniforms {
+    mat4 view_projection;
+    //x: Seconds Since Start, y: Frame Delta, z: Frame Number
+    vec4 time;
+} frame_uniforms;
 
 VERTEX_INPUT(0, vec2, input_Position)
 VERTEX_INPUT(1, vec3, input_Color)
@@ -21,12 +15,16 @@ VERTEX_TO_FRAGMENT(0, vec3, v2f_Color)
 FRAGMENT_OUTPUT(0, vec4, outColor)
 
 #if SHADER_FREQUENCY_VERTEX
+layout(push_constant) uniform DrawConstants {
+    mat4 transform;
+} draw_constants;
+
 out gl_PerVertex {
     vec4 gl_Position;
 };
 void vert()
 {
-    gl_Position = vec4(input_Position, 0, 1);
+    gl_Position = frame_uniforms.view_projection * draw_constants.transform * vec4(input_Position, 0, 1);
     v2f_Color = input_Color;
 }
 #endif//SHADER_FREQUENCY_VERTEX
@@ -34,6 +32,7 @@ void vert()
 #if SHADER_FREQUENCY_FRAGMENT
 void frag()
 {
-    outColor = vec4(v2f_Color, 1.0);
+    float pulse = 0.75 + 0.25 * sin(frame_uniforms.time.x * 2.0);
+    outColor = vec4(v2f_Color * pulse, 1.0);
 }
 #endif//SHADER_FREQUENCY_FRAGMENT
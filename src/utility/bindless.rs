@@ -0,0 +1,482 @@
+use ash::vk;
+
+use crate::utility::{context, constants::MAX_FRAMES_IN_FLIGHT, gpubuffer::GPUBuffer, gpuimage::GPUImage};
+
+//Binding Slots Of The Global Set, Shaders Declare The Same Layout:
+//  layout(set = N, binding = 0) uniform texture2D g_textures[];
+//  layout(set = N, binding = 1, rgba8) uniform image2D g_storage_images[];
+//  layout(set = N, binding = 2) uniform sampler g_samplers[];
+//  layout(set = N, binding = 3) buffer StorageBuffers { uint data[]; } g_buffers[];
+pub const BINDLESS_SAMPLED_IMAGE_BINDING: u32 = 0;
+pub const BINDLESS_STORAGE_IMAGE_BINDING: u32 = 1;
+pub const BINDLESS_SAMPLER_BINDING: u32 = 2;
+pub const BINDLESS_STORAGE_BUFFER_BINDING: u32 = 3;
+
+//Upper Bounds, The Actual Capacity Is Clamped To The Device Update-After-Bind Limits
+const MAX_BINDLESS_SAMPLED_IMAGES: u32 = 16384;
+const MAX_BINDLESS_STORAGE_IMAGES: u32 = 4096;
+const MAX_BINDLESS_SAMPLERS: u32 = 1024;
+const MAX_BINDLESS_STORAGE_BUFFERS: u32 = 16384;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BindlessResourceKind
+{
+    SampledImage,
+    StorageImage,
+    Sampler,
+    StorageBuffer,
+}
+
+impl BindlessResourceKind
+{
+    const ALL: [BindlessResourceKind; 4] = [
+        BindlessResourceKind::SampledImage,
+        BindlessResourceKind::StorageImage,
+        BindlessResourceKind::Sampler,
+        BindlessResourceKind::StorageBuffer,
+    ];
+
+    pub fn binding(&self) -> u32
+    {
+        match self
+        {
+            BindlessResourceKind::SampledImage => BINDLESS_SAMPLED_IMAGE_BINDING,
+            BindlessResourceKind::StorageImage => BINDLESS_STORAGE_IMAGE_BINDING,
+            BindlessResourceKind::Sampler => BINDLESS_SAMPLER_BINDING,
+            BindlessResourceKind::StorageBuffer => BINDLESS_STORAGE_BUFFER_BINDING,
+        }
+    }
+
+    pub fn descriptor_type(&self) -> vk::DescriptorType
+    {
+        match self
+        {
+            BindlessResourceKind::SampledImage => vk::DescriptorType::SAMPLED_IMAGE,
+            BindlessResourceKind::StorageImage => vk::DescriptorType::STORAGE_IMAGE,
+            BindlessResourceKind::Sampler => vk::DescriptorType::SAMPLER,
+            BindlessResourceKind::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
+        }
+    }
+}
+
+//Stable Index Into One Of The Global Arrays, Passed To Shaders As A Plain uint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BindlessHandle
+{
+    pub kind: BindlessResourceKind,
+    pub index: u32,
+}
+
+//Free List Of Array Slots, Released Slots Are Only Reused Once No In-Flight Frame Can Still Index Them
+#[derive(Debug, Clone)]
+struct BindlessSlotAllocator
+{
+    capacity: u32,
+    next_unused: u32,
+    free_slots: Vec<u32>,
+    pending_slots: Vec<(u32, u64)>,
+}
+
+impl BindlessSlotAllocator
+{
+    fn new(capacity: u32) -> BindlessSlotAllocator
+    {
+        BindlessSlotAllocator{
+            capacity,
+            next_unused: 0,
+            free_slots: vec![],
+            pending_slots: vec![],
+        }
+    }
+
+    fn allocate(&mut self) -> Option<u32>
+    {
+        if let Some(slot) = self.free_slots.pop()
+        {
+            return Some(slot);
+        }
+        if self.next_unused < self.capacity
+        {
+            self.next_unused += 1;
+            return Some(self.next_unused - 1);
+        }
+        None
+    }
+
+    fn release(&mut self, slot: u32, frame_number: u64)
+    {
+        self.pending_slots.push((slot, frame_number));
+    }
+
+    fn recycle(&mut self, frame_number: u64)
+    {
+        let free_slots = &mut self.free_slots;
+        self.pending_slots.retain(|&(slot, released_frame)| {
+            let is_safe = released_frame + MAX_FRAMES_IN_FLIGHT as u64 <= frame_number;
+            if is_safe
+            {
+                free_slots.push(slot);
+            }
+            !is_safe
+        });
+    }
+
+    fn used_count(&self) -> u32
+    {
+        self.next_unused - self.free_slots.len() as u32 - self.pending_slots.len() as u32
+    }
+}
+
+//Every Binding Uses ShaderStageFlags::ALL, So Each Array Must Also Fit The Per Stage Limits,
+//And Together They Must Fit maxPerStageUpdateAfterBindResources
+fn bindless_capacities(properties: &vk::PhysicalDeviceDescriptorIndexingProperties) -> [u32; 4]
+{
+    let capacities = [
+        MAX_BINDLESS_SAMPLED_IMAGES
+            .min(properties.max_descriptor_set_update_after_bind_sampled_images)
+            .min(properties.max_per_stage_descriptor_update_after_bind_sampled_images),
+        MAX_BINDLESS_STORAGE_IMAGES
+            .min(properties.max_descriptor_set_update_after_bind_storage_images)
+            .min(properties.max_per_stage_descriptor_update_after_bind_storage_images),
+        MAX_BINDLESS_SAMPLERS
+            .min(properties.max_descriptor_set_update_after_bind_samplers)
+            .min(properties.max_per_stage_descriptor_update_after_bind_samplers),
+        MAX_BINDLESS_STORAGE_BUFFERS
+            .min(properties.max_descriptor_set_update_after_bind_storage_buffers)
+            .min(properties.max_per_stage_descriptor_update_after_bind_storage_buffers),
+    ];
+
+    //Scale Every Array Down By The Same Factor, Rounding Down Keeps The Sum Under The Limit
+    let total: u64 = capacities.iter().map(|&capacity| capacity as u64).sum();
+    let resource_limit = properties.max_per_stage_update_after_bind_resources as u64;
+    if total <= resource_limit
+    {
+        return capacities;
+    }
+    capacities.map(|capacity| (capacity as u64 * resource_limit / total) as u32)
+}
+
+pub struct BindlessHeap
+{
+    pub descriptor_set_layout: vk::DescriptorSetLayout,
+    descriptor_pool: vk::DescriptorPool,
+    pub descriptor_set: vk::DescriptorSet,
+    slot_allocators: Vec<BindlessSlotAllocator>,
+    frame_number: u64,
+}
+
+impl BindlessHeap
+{
+    pub fn is_supported(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> bool
+    {
+        let features = context::query_descriptor_indexing_features(instance, physical_device);
+        features.runtime_descriptor_array == vk::TRUE
+            && features.descriptor_binding_partially_bound == vk::TRUE
+            && features.descriptor_binding_sampled_image_update_after_bind == vk::TRUE
+            && features.descriptor_binding_storage_image_update_after_bind == vk::TRUE
+            && features.descriptor_binding_storage_buffer_update_after_bind == vk::TRUE
+            && features.descriptor_binding_update_unused_while_pending == vk::TRUE
+            && features.shader_sampled_image_array_non_uniform_indexing == vk::TRUE
+    }
+
+    pub fn new(instance: &ash::Instance, physical_device: vk::PhysicalDevice, device: &ash::Device) -> BindlessHeap
+    {
+        let mut descriptor_indexing_properties = vk::PhysicalDeviceDescriptorIndexingProperties::default();
+        {
+            let mut device_properties = vk::PhysicalDeviceProperties2::builder()
+                .push_next(&mut descriptor_indexing_properties);
+            unsafe {
+                instance.get_physical_device_properties2(physical_device, &mut device_properties);
+            }
+        }
+
+        let capacities = bindless_capacities(&descriptor_indexing_properties);
+
+        let layout_bindings: Vec<vk::DescriptorSetLayoutBinding> = BindlessResourceKind::ALL
+            .iter()
+            .zip(capacities.iter())
+            .map(|(kind, &capacity)| vk::DescriptorSetLayoutBinding::builder()
+                .binding(kind.binding())
+                .descriptor_type(kind.descriptor_type())
+                .descriptor_count(capacity)
+                .stage_flags(vk::ShaderStageFlags::ALL)
+                .build())
+            .collect();
+
+        //Slots May Stay Empty, And Are Written While Other Slots Are In Use By In-Flight Frames
+        let binding_flags = [vk::DescriptorBindingFlags::UPDATE_AFTER_BIND
+            | vk::DescriptorBindingFlags::PARTIALLY_BOUND
+            | vk::DescriptorBindingFlags::UPDATE_UNUSED_WHILE_PENDING; 4];
+
+        let mut binding_flags_create_info = vk::DescriptorSetLayoutBindingFlagsCreateInfo::builder()
+            .binding_flags(&binding_flags);
+
+        let layout_create_info = vk::DescriptorSetLayoutCreateInfo::builder()
+            .flags(vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL)
+            .bindings(&layout_bindings)
+            .push_next(&mut binding_flags_create_info);
+
+        let descriptor_set_layout = unsafe{
+            device
+                .create_descriptor_set_layout(&layout_create_info, None)
+                .expect("Failed To Create Bindless Descriptor Set Layout")
+        };
+
+        let pool_sizes: Vec<vk::DescriptorPoolSize> = BindlessResourceKind::ALL
+            .iter()
+            .zip(capacities.iter())
+            .map(|(kind, &capacity)| vk::DescriptorPoolSize{
+                ty: kind.descriptor_type(),
+                descriptor_count: capacity,
+            })
+            .collect();
+
+        let pool_create_info = vk::DescriptorPoolCreateInfo::builder()
+            .flags(vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND)
+            .max_sets(1)
+            .pool_sizes(&pool_sizes);
+
+        let descriptor_pool = unsafe{
+            device
+                .create_descriptor_pool(&pool_create_info, None)
+                .expect("Failed To Create Bindless Descriptor Pool")
+        };
+
+        let set_layouts = [descriptor_set_layout];
+        let allocate_info = vk::DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(descriptor_pool)
+            .set_layouts(&set_layouts);
+
+        let descriptor_set = unsafe{
+            device
+                .allocate_descriptor_sets(&allocate_info)
+                .expect("Failed To Allocate Bindless Descriptor Set")[0]
+        };
+
+        BindlessHeap{
+            descriptor_set_layout,
+            descriptor_pool,
+            descriptor_set,
+            slot_allocators: capacities.iter().map(|&capacity| BindlessSlotAllocator::new(capacity)).collect(),
+            frame_number: 0,
+        }
+    }
+
+    pub fn destroy_heap(&mut self, device: &ash::Device)
+    {
+        unsafe
+        {
+            //Destroying The Pool Frees The Set
+            device.destroy_descriptor_pool(self.descriptor_pool, None);
+            device.destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+        }
+        self.descriptor_pool = vk::DescriptorPool::null();
+        self.descriptor_set_layout = vk::DescriptorSetLayout::null();
+        self.descriptor_set = vk::DescriptorSet::null();
+    }
+
+    //Call Once Per Frame After Waiting Its Fence, Slots Released MAX_FRAMES_IN_FLIGHT Frames Ago Become Reusable
+    pub fn begin_frame(&mut self, frame_number: u64)
+    {
+        self.frame_number = frame_number;
+        for slot_allocator in self.slot_allocators.iter_mut()
+        {
+            slot_allocator.recycle(frame_number);
+        }
+    }
+
+    pub fn register_sampled_image(&mut self, device: &ash::Device, gpu_image: &GPUImage) -> BindlessHandle
+    {
+        self.register_image_view(device, BindlessResourceKind::SampledImage, gpu_image.image_view, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+    }
+
+    pub fn register_storage_image(&mut self, device: &ash::Device, gpu_image: &GPUImage) -> BindlessHandle
+    {
+        self.register_image_view(device, BindlessResourceKind::StorageImage, gpu_image.image_view, vk::ImageLayout::GENERAL)
+    }
+
+    pub fn register_image_view(&mut self, device: &ash::Device, kind: BindlessResourceKind, image_view: vk::ImageView, image_layout: vk::ImageLayout) -> BindlessHandle
+    {
+        let handle = self.allocate_handle(kind);
+        let image_infos = [vk::DescriptorImageInfo{
+            sampler: vk::Sampler::null(),
+            image_view,
+            image_layout,
+        }];
+        self.write_descriptor(device, handle, vk::WriteDescriptorSet::builder().image_info(&image_infos));
+        handle
+    }
+
+    pub fn register_sampler(&mut self, device: &ash::Device, sampler: vk::Sampler) -> BindlessHandle
+    {
+        let handle = self.allocate_handle(BindlessResourceKind::Sampler);
+        let image_infos = [vk::DescriptorImageInfo{
+            sampler,
+            image_view: vk::ImageView::null(),
+            image_layout: vk::ImageLayout::UNDEFINED,
+        }];
+        self.write_descriptor(device, handle, vk::WriteDescriptorSet::builder().image_info(&image_infos));
+        handle
+    }
+
+    pub fn register_storage_buffer(&mut self, device: &ash::Device, gpu_buffer: &GPUBuffer) -> BindlessHandle
+    {
+        let handle = self.allocate_handle(BindlessResourceKind::StorageBuffer);
+        let buffer_infos = [vk::DescriptorBufferInfo{
+            buffer: gpu_buffer.buffer,
+            offset: 0,
+            range: vk::WHOLE_SIZE,
+        }];
+        self.write_descriptor(device, handle, vk::WriteDescriptorSet::builder().buffer_info(&buffer_infos));
+        handle
+    }
+
+    //The Descriptor Is Left In Place, Partially Bound Arrays Tolerate Stale Slots That Are Never Indexed
+    pub fn release(&mut self, handle: BindlessHandle)
+    {
+        self.slot_allocators[handle.kind as usize].release(handle.index, self.frame_number);
+    }
+
+    pub fn used_count(&self, kind: BindlessResourceKind) -> u32
+    {
+        self.slot_allocators[kind as usize].used_count()
+    }
+
+    pub fn capacity(&self, kind: BindlessResourceKind) -> u32
+    {
+        self.slot_allocators[kind as usize].capacity
+    }
+
+    pub unsafe fn cmd_bind(
+        &self
+        ,device: &ash::Device
+        ,command_buffer: vk::CommandBuffer
+        ,pipeline_bind_point: vk::PipelineBindPoint
+        ,pipeline_layout: vk::PipelineLayout
+        ,set_index: u32)
+    {
+        device
+            .cmd_bind_descriptor_sets(
+                command_buffer
+                , pipeline_bind_point
+                , pipeline_layout
+                , set_index
+                , &[self.descriptor_set]
+                , &[]);
+    }
+
+    fn allocate_handle(&mut self, kind: BindlessResourceKind) -> BindlessHandle
+    {
+        let index = self.slot_allocators[kind as usize]
+            .allocate()
+            .unwrap_or_else(|| panic!("Bindless {:?} Array Is Full", kind));
+        BindlessHandle{ kind, index }
+    }
+
+    fn write_descriptor(&self, device: &ash::Device, handle: BindlessHandle, write: vk::WriteDescriptorSetBuilder)
+    {
+        let descriptor_writes = [write
+            .dst_set(self.descriptor_set)
+            .dst_binding(handle.kind.binding())
+            .dst_array_element(handle.index)
+            .descriptor_type(handle.kind.descriptor_type())
+            .build()];
+
+        unsafe
+        {
+            device.update_descriptor_sets(&descriptor_writes, &[]);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn drain(slot_allocator: &mut BindlessSlotAllocator) -> Vec<u32>
+    {
+        std::iter::from_fn(|| slot_allocator.allocate()).collect()
+    }
+
+    #[test]
+    fn slots_run_out_at_capacity()
+    {
+        let mut slot_allocator = BindlessSlotAllocator::new(3);
+        assert_eq!(drain(&mut slot_allocator), vec![0, 1, 2]);
+        assert_eq!(slot_allocator.allocate(), None);
+        assert_eq!(slot_allocator.used_count(), 3);
+
+        let mut empty = BindlessSlotAllocator::new(0);
+        assert_eq!(empty.allocate(), None);
+    }
+
+    #[test]
+    fn released_slots_wait_for_in_flight_frames()
+    {
+        let mut slot_allocator = BindlessSlotAllocator::new(2);
+        drain(&mut slot_allocator);
+        slot_allocator.release(1, 10);
+        assert_eq!(slot_allocator.used_count(), 1);
+
+        //Frames Still In Flight Could Index Slot 1
+        for frame_number in 10..10 + MAX_FRAMES_IN_FLIGHT as u64
+        {
+            slot_allocator.recycle(frame_number);
+            assert_eq!(slot_allocator.allocate(), None, "Frame {}", frame_number);
+        }
+
+        slot_allocator.recycle(10 + MAX_FRAMES_IN_FLIGHT as u64);
+        assert_eq!(slot_allocator.allocate(), Some(1));
+        assert_eq!(slot_allocator.allocate(), None);
+        assert_eq!(slot_allocator.used_count(), 2);
+    }
+
+    #[test]
+    fn slots_retire_independently()
+    {
+        let mut slot_allocator = BindlessSlotAllocator::new(4);
+        drain(&mut slot_allocator);
+        slot_allocator.release(0, 5);
+        slot_allocator.release(2, 7);
+
+        slot_allocator.recycle(5 + MAX_FRAMES_IN_FLIGHT as u64);
+        assert_eq!(drain(&mut slot_allocator), vec![0]);
+
+        slot_allocator.recycle(7 + MAX_FRAMES_IN_FLIGHT as u64);
+        assert_eq!(drain(&mut slot_allocator), vec![2]);
+        assert_eq!(slot_allocator.used_count(), 4);
+    }
+
+    fn indexing_properties(set_limit: u32, per_stage_limit: u32, per_stage_resources: u32) -> vk::PhysicalDeviceDescriptorIndexingProperties
+    {
+        vk::PhysicalDeviceDescriptorIndexingProperties{
+            max_per_stage_update_after_bind_resources: per_stage_resources,
+            max_per_stage_descriptor_update_after_bind_sampled_images: per_stage_limit,
+            max_per_stage_descriptor_update_after_bind_storage_images: per_stage_limit,
+            max_per_stage_descriptor_update_after_bind_samplers: per_stage_limit,
+            max_per_stage_descriptor_update_after_bind_storage_buffers: per_stage_limit,
+            max_descriptor_set_update_after_bind_sampled_images: set_limit,
+            max_descriptor_set_update_after_bind_storage_images: set_limit,
+            max_descriptor_set_update_after_bind_samplers: set_limit,
+            max_descriptor_set_update_after_bind_storage_buffers: set_limit,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn capacities_fit_the_device_limits()
+    {
+        let generous = indexing_properties(u32::MAX, u32::MAX, u32::MAX);
+        assert_eq!(bindless_capacities(&generous), [MAX_BINDLESS_SAMPLED_IMAGES, MAX_BINDLESS_STORAGE_IMAGES, MAX_BINDLESS_SAMPLERS, MAX_BINDLESS_STORAGE_BUFFERS]);
+
+        //Per Stage Limits Lower Than The Per Set Ones Win
+        assert_eq!(bindless_capacities(&indexing_properties(u32::MAX, 2048, u32::MAX)), [2048, 2048, 1024, 2048]);
+        assert_eq!(bindless_capacities(&indexing_properties(512, 2048, u32::MAX)), [512, 512, 512, 512]);
+
+        let resource_limited = bindless_capacities(&indexing_properties(u32::MAX, u32::MAX, 8192));
+        assert!(resource_limited.iter().map(|&capacity| capacity as u64).sum::<u64>() <= 8192);
+        assert_eq!(resource_limited, [3542, 885, 221, 3542]);
+    }
+}
@@ -1,9 +1,10 @@
 use ash::vk;
 use ash::vk::{CommandPoolResetFlags, CommandPool};
-use crate::utility::{structs};
+use crate::utility::{structs, descriptors::DescriptorAllocator};
 
 //We Can Binding One CommandGroup With One Frame, When The Frame Is Finished, So The CommandGroup
-#[derive(Debug, Clone)]
+//Descriptor Sets Allocated Through The Group Share Its Lifetime And Are Reset Together With The Command Pool
+#[derive(Debug)]
 pub struct FrameBoundCommandGroup
 {
     command_pool: vk::CommandPool,
@@ -11,6 +12,7 @@ pub struct FrameBoundCommandGroup
     buffer_id: u32,
     level: vk::CommandBufferLevel,
     frame_id: u64,
+    descriptor_allocator: DescriptorAllocator,
 }
 
 impl FrameBoundCommandGroup
@@ -23,6 +25,7 @@ impl FrameBoundCommandGroup
             buffer_id: 0,
             level,
             frame_id,
+            descriptor_allocator: DescriptorAllocator::new(),
         };
         let command_pool_create_info = vk::CommandPoolCreateInfo::builder()
         .queue_family_index(queue_family.graphics_family.unwrap());
@@ -73,6 +76,11 @@ impl FrameBoundCommandGroup
         result
     }
 
+    pub fn allocate_descriptor_set(& mut self, device: &ash::Device, layout: vk::DescriptorSetLayout) -> vk::DescriptorSet
+    {
+        self.descriptor_allocator.allocate(device, layout)
+    }
+
     pub fn reset_command_group(& mut self, device: &ash::Device)
     {
         unsafe
@@ -81,6 +89,7 @@ impl FrameBoundCommandGroup
             .reset_command_pool(self.command_pool, CommandPoolResetFlags::empty())
             .expect("Failed To Reset Command Pool!");
         }
+        self.descriptor_allocator.reset_pools(device);
         self.buffer_id = 0;
     }
 
@@ -95,6 +104,7 @@ impl FrameBoundCommandGroup
             device
             .destroy_command_pool(self.command_pool, None);
         }
+        self.descriptor_allocator.destroy_allocator(device);
         self.command_buffers.clear();
         self.command_pool = CommandPool::null();
         self.buffer_id = 0;
@@ -0,0 +1,818 @@
+use std::fmt;
+
+use ash::vk;
+use bytemuck::{Pod, Zeroable};
+use gpu_allocator::vulkan;
+
+use crate::utility::{
+    constants::VALIDATION,
+    context,
+    debug::{self, ValidationInfo},
+    descriptors::{DescriptorAllocator, DescriptorLayoutCache, DescriptorWriter},
+    file_system::CompiledShader,
+    gpubuffer::*,
+    memory_report::TrackedAllocator,
+    shader_reflection::*,
+};
+
+#[derive(Debug)]
+pub enum ComputeError
+{
+    Reflection(ShaderReflectionError),
+    NotComputeShader{ path: String, stage: vk::ShaderStageFlags },
+    //LocalSizeId Or Specialization Constants, Group Counts Have To Be Given Directly
+    UnknownWorkgroupSize(String),
+    InvalidDispatch(String),
+    InvalidBinding(String),
+    InvalidPushConstants(String),
+    Buffer(GPUBufferError),
+    EntryLoad(ash::LoadingError),
+    NoComputeDevice,
+    Vulkan(vk::Result),
+}
+
+impl From<ShaderReflectionError> for ComputeError
+{
+    fn from(error: ShaderReflectionError) -> Self
+    {
+        ComputeError::Reflection(error)
+    }
+}
+
+impl From<GPUBufferError> for ComputeError
+{
+    fn from(error: GPUBufferError) -> Self
+    {
+        ComputeError::Buffer(error)
+    }
+}
+
+impl fmt::Display for ComputeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            ComputeError::Reflection(error) => write!(f, "{}", error),
+            ComputeError::NotComputeShader{ path, stage } => write!(f, "{} Is A {:?} Shader, Not A Compute Shader", path, stage),
+            ComputeError::UnknownWorkgroupSize(path) => write!(f, "Workgroup Size Of {} Is Not A Literal, Pass Group Counts Directly", path),
+            ComputeError::InvalidDispatch(message) => write!(f, "Invalid Dispatch: {}", message),
+            ComputeError::InvalidBinding(message) => write!(f, "Invalid Compute Binding: {}", message),
+            ComputeError::InvalidPushConstants(message) => write!(f, "Invalid Push Constants: {}", message),
+            ComputeError::Buffer(error) => write!(f, "{}", error),
+            ComputeError::EntryLoad(error) => write!(f, "Failed To Load Vulkan: {}", error),
+            ComputeError::NoComputeDevice => write!(f, "No Vulkan Device With A Compute Queue Found"),
+            ComputeError::Vulkan(error) => write!(f, "Vulkan Error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ComputeError {}
+
+//Matches VkDispatchIndirectCommand, Written By The CPU Or By A Previous Kernel
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Pod, Zeroable)]
+pub struct DispatchIndirectArgs
+{
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+pub struct ComputePipeline
+{
+    pub pipeline: vk::Pipeline,
+    pub layout: vk::PipelineLayout,
+    //Owned By The DescriptorLayoutCache, Indexed By Set Number
+    pub set_layouts: Vec<vk::DescriptorSetLayout>,
+    pub reflection: ShaderReflection,
+    pub name: String,
+}
+
+impl ComputePipeline
+{
+    //The Layout Comes Entirely From Reflection, So The Shader Is The Only Description Needed
+    pub fn new(
+        device: &ash::Device
+        , pipeline_cache: vk::PipelineCache
+        , descriptor_layout_cache: &mut DescriptorLayoutCache
+        , shader: &CompiledShader) -> Result<ComputePipeline, ComputeError>
+    {
+        let reflection = shader.reflect()?;
+        if reflection.stage != vk::ShaderStageFlags::COMPUTE
+        {
+            return Err(ComputeError::NotComputeShader{ path: shader.path.clone(), stage: reflection.stage });
+        }
+
+        let layout_desc = PipelineLayoutDesc::from_reflections(&[&reflection])?;
+        let (layout, set_layouts) = layout_desc.create_pipeline_layout(device, descriptor_layout_cache);
+
+        let shader_module_create_info = vk::ShaderModuleCreateInfo::builder().code(&shader.spirv);
+        let shader_module = match unsafe { device.create_shader_module(&shader_module_create_info, None) }
+        {
+            Ok(shader_module) => shader_module,
+            Err(error) =>
+            {
+                unsafe { device.destroy_pipeline_layout(layout, None) };
+                return Err(ComputeError::Vulkan(error));
+            }
+        };
+
+        let pipeline_create_info = vk::ComputePipelineCreateInfo::builder()
+            .stage(vk::PipelineShaderStageCreateInfo::builder()
+                .module(shader_module)
+                .name(&shader.entry_point)
+                .stage(vk::ShaderStageFlags::COMPUTE).build())
+            .layout(layout);
+
+        let pipeline_result = unsafe
+        {
+            device.create_compute_pipelines(pipeline_cache, std::slice::from_ref(&pipeline_create_info), None)
+        };
+        unsafe
+        {
+            device.destroy_shader_module(shader_module, None);
+        }
+        let pipeline = match pipeline_result
+        {
+            Ok(pipelines) => pipelines[0],
+            Err((_, error)) =>
+            {
+                unsafe { device.destroy_pipeline_layout(layout, None) };
+                return Err(ComputeError::Vulkan(error));
+            }
+        };
+
+        Ok(ComputePipeline{
+            pipeline,
+            layout,
+            set_layouts,
+            reflection,
+            name: shader.path.clone(),
+        })
+    }
+
+    pub fn workgroup_size(&self) -> Option<[u32; 3]>
+    {
+        self.reflection.workgroup_size
+    }
+
+    //Enough Groups To Cover element_counts Invocations, The Kernel Must Bounds Check The Tail
+    pub fn group_count_for(&self, element_counts: [u32; 3]) -> Result<[u32; 3], ComputeError>
+    {
+        let workgroup_size = self.workgroup_size().ok_or_else(|| ComputeError::UnknownWorkgroupSize(self.name.clone()))?;
+        let mut group_count = [0u32; 3];
+        for axis in 0..3
+        {
+            let size = workgroup_size[axis].max(1) as u64;
+            group_count[axis] = ((element_counts[axis] as u64 + size - 1) / size) as u32;
+        }
+        Ok(group_count)
+    }
+
+    pub unsafe fn cmd_bind(&self, device: &ash::Device, command_buffer: vk::CommandBuffer)
+    {
+        device.cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::COMPUTE, self.pipeline);
+    }
+
+    pub unsafe fn cmd_bind_descriptor_sets(&self, device: &ash::Device, command_buffer: vk::CommandBuffer, first_set: u32, descriptor_sets: &[vk::DescriptorSet])
+    {
+        device.cmd_bind_descriptor_sets(command_buffer, vk::PipelineBindPoint::COMPUTE, self.layout, first_set, descriptor_sets, &[]);
+    }
+
+    //data Is Written At The Start Of The Reflected Block And May Not Run Past Its End
+    pub unsafe fn cmd_push_constants(&self, device: &ash::Device, command_buffer: vk::CommandBuffer, data: &[u8]) -> Result<(), ComputeError>
+    {
+        if data.is_empty()
+        {
+            return Ok(());
+        }
+        let block = self.reflection.push_constant_block
+            .as_ref()
+            .ok_or_else(|| ComputeError::InvalidPushConstants(format!("{} Declares No Push Constant Block", self.name)))?;
+        if data.len() % 4 != 0 || data.len() as u32 > block.size
+        {
+            return Err(ComputeError::InvalidPushConstants(format!(
+                "{} Bytes Given For Block {} Of {} Bytes, Size Must Be A Multiple Of 4 And Fit", data.len(), block.name, block.size)));
+        }
+        device.cmd_push_constants(command_buffer, self.layout, vk::ShaderStageFlags::COMPUTE, block.offset, data);
+        Ok(())
+    }
+
+    pub unsafe fn cmd_dispatch(&self, device: &ash::Device, command_buffer: vk::CommandBuffer, device_limits: &vk::PhysicalDeviceLimits, group_count: [u32; 3]) -> Result<(), ComputeError>
+    {
+        for axis in 0..3
+        {
+            if group_count[axis] > device_limits.max_compute_work_group_count[axis]
+            {
+                return Err(ComputeError::InvalidDispatch(format!(
+                    "Group Count {:?} Exceeds The Device Limit {:?}", group_count, device_limits.max_compute_work_group_count)));
+            }
+        }
+        device.cmd_dispatch(command_buffer, group_count[0], group_count[1], group_count[2]);
+        Ok(())
+    }
+
+    //Group Counts Are Read From A DispatchIndirectArgs At offset, The Driver Does Not Check Them Against The Limits
+    pub unsafe fn cmd_dispatch_indirect(&self, device: &ash::Device, command_buffer: vk::CommandBuffer, args_buffer: &GPUBuffer, offset: u64) -> Result<(), ComputeError>
+    {
+        if !args_buffer.usage.contains(vk::BufferUsageFlags::INDIRECT_BUFFER)
+        {
+            return Err(ComputeError::InvalidDispatch("Indirect Arguments Buffer Was Not Created With INDIRECT_BUFFER Usage".to_owned()));
+        }
+        let args_size = std::mem::size_of::<DispatchIndirectArgs>() as u64;
+        if offset % 4 != 0
+        {
+            return Err(ComputeError::Buffer(GPUBufferError::Misaligned{ offset, size: args_size, alignment: 4 }));
+        }
+        if offset.checked_add(args_size).map_or(true, |end| end > args_buffer.size)
+        {
+            return Err(ComputeError::Buffer(GPUBufferError::RegionOutOfBounds{ offset, size: args_size, buffer_size: args_buffer.size }));
+        }
+        device.cmd_dispatch_indirect(command_buffer, args_buffer.buffer, offset);
+        Ok(())
+    }
+
+    //The Set Layouts Stay With The Cache
+    pub fn destroy_pipeline(&mut self, device: &ash::Device)
+    {
+        unsafe
+        {
+            device.destroy_pipeline(self.pipeline, None);
+            device.destroy_pipeline_layout(self.layout, None);
+        }
+        self.pipeline = vk::Pipeline::null();
+        self.layout = vk::PipelineLayout::null();
+        self.set_layouts.clear();
+    }
+}
+
+//Makes Compute Shader Writes Visible To Later Kernels, Indirect Dispatches, Draws, Copies And Host Reads
+pub unsafe fn cmd_compute_write_barrier(device: &ash::Device, command_buffer: vk::CommandBuffer)
+{
+    let memory_barriers = [vk::MemoryBarrier::builder()
+        .src_access_mask(vk::AccessFlags::SHADER_WRITE)
+        .dst_access_mask(vk::AccessFlags::SHADER_READ
+            | vk::AccessFlags::SHADER_WRITE
+            | vk::AccessFlags::INDIRECT_COMMAND_READ
+            | vk::AccessFlags::VERTEX_ATTRIBUTE_READ
+            | vk::AccessFlags::TRANSFER_READ
+            | vk::AccessFlags::HOST_READ)
+        .build()];
+    device.cmd_pipeline_barrier(
+        command_buffer
+        , vk::PipelineStageFlags::COMPUTE_SHADER
+        , vk::PipelineStageFlags::COMPUTE_SHADER
+            | vk::PipelineStageFlags::DRAW_INDIRECT
+            | vk::PipelineStageFlags::VERTEX_INPUT
+            | vk::PipelineStageFlags::VERTEX_SHADER
+            | vk::PipelineStageFlags::FRAGMENT_SHADER
+            | vk::PipelineStageFlags::TRANSFER
+            | vk::PipelineStageFlags::HOST
+        , vk::DependencyFlags::empty()
+        , &memory_barriers
+        , &[]
+        , &[]);
+}
+
+//One Buffer Descriptor, The Descriptor Type Comes From The Kernel's Reflection
+#[derive(Debug, Clone, Copy)]
+pub struct ComputeBinding
+{
+    pub set: u32,
+    pub binding: u32,
+    pub buffer: vk::Buffer,
+    pub offset: u64,
+    pub range: u64,
+}
+
+impl ComputeBinding
+{
+    pub fn whole(set: u32, binding: u32, gpu_buffer: &GPUBuffer) -> ComputeBinding
+    {
+        ComputeBinding{
+            set,
+            binding,
+            buffer: gpu_buffer.buffer,
+            offset: 0,
+            range: vk::WHOLE_SIZE,
+        }
+    }
+}
+
+//Records, Submits And Waits, For Kernels Whose Results Are Needed Right Away Rather Than Next Frame
+pub struct ComputeKernelRunner
+{
+    command_pool: vk::CommandPool,
+    command_buffer: vk::CommandBuffer,
+    fence: vk::Fence,
+    descriptor_allocator: DescriptorAllocator,
+    queue: vk::Queue,
+}
+
+impl ComputeKernelRunner
+{
+    //queue Must Come From queue_family_index, Which Must Support Compute
+    pub fn new(device: &ash::Device, queue_family_index: u32, queue: vk::Queue) -> ComputeKernelRunner
+    {
+        let command_pool_create_info = vk::CommandPoolCreateInfo::builder()
+            .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+            .queue_family_index(queue_family_index);
+
+        unsafe
+        {
+            let command_pool = device
+                .create_command_pool(&command_pool_create_info, None)
+                .expect("Failed To Create Compute Command Pool!");
+
+            let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::builder()
+                .command_buffer_count(1)
+                .command_pool(command_pool)
+                .level(vk::CommandBufferLevel::PRIMARY);
+            let command_buffer = device
+                .allocate_command_buffers(&command_buffer_allocate_info)
+                .expect("Failed To Allocate Compute Command Buffer!")[0];
+
+            let fence = device
+                .create_fence(&vk::FenceCreateInfo::builder(), None)
+                .expect("Failed To Create Compute Fence!");
+
+            ComputeKernelRunner{
+                command_pool,
+                command_buffer,
+                fence,
+                descriptor_allocator: DescriptorAllocator::with_pool_ratios(&[
+                    (vk::DescriptorType::STORAGE_BUFFER, 4.0),
+                    (vk::DescriptorType::UNIFORM_BUFFER, 1.0),
+                ]),
+                queue,
+            }
+        }
+    }
+
+    //Nothing Recorded Before An Error Is Submitted, Descriptor Sets From The Submission Are Recycled Afterwards
+    pub fn submit<F>(&mut self, device: &ash::Device, record: F) -> Result<(), ComputeError>
+        where F: FnOnce(vk::CommandBuffer, &mut DescriptorAllocator) -> Result<(), ComputeError>
+    {
+        let command_buffer_begin_info = vk::CommandBufferBeginInfo::builder()
+            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+        let record_result = unsafe
+        {
+            device
+                .begin_command_buffer(self.command_buffer, &command_buffer_begin_info)
+                .map_err(ComputeError::Vulkan)?;
+            let record_result = record(self.command_buffer, &mut self.descriptor_allocator);
+            device
+                .end_command_buffer(self.command_buffer)
+                .map_err(ComputeError::Vulkan)?;
+            record_result
+        };
+
+        let submit_result = match record_result
+        {
+            Ok(()) => unsafe
+            {
+                let submit_infos = [vk::SubmitInfo::builder()
+                    .command_buffers(std::slice::from_ref(&self.command_buffer)).build()];
+                device
+                    .queue_submit(self.queue, &submit_infos, self.fence)
+                    .and_then(|_| device.wait_for_fences(&[self.fence], true, std::u64::MAX))
+                    .and_then(|_| device.reset_fences(&[self.fence]))
+                    .map_err(ComputeError::Vulkan)
+            },
+            Err(error) => Err(error),
+        };
+
+        unsafe
+        {
+            device
+                .reset_command_pool(self.command_pool, vk::CommandPoolResetFlags::empty())
+                .expect("Failed To Reset Compute Command Pool!");
+        }
+        self.descriptor_allocator.reset_pools(device);
+        submit_result
+    }
+
+    //Every Binding The Kernel Declares Must Be Given, And Nothing It Does Not Declare
+    pub fn run(
+        &mut self
+        , device: &ash::Device
+        , device_limits: &vk::PhysicalDeviceLimits
+        , pipeline: &ComputePipeline
+        , bindings: &[ComputeBinding]
+        , push_constants: &[u8]
+        , group_count: [u32; 3]) -> Result<(), ComputeError>
+    {
+        self.submit(device, |command_buffer, descriptor_allocator| unsafe {
+            ComputeKernelRunner::cmd_run(device, command_buffer, descriptor_allocator, pipeline, bindings, push_constants)?;
+            pipeline.cmd_dispatch(device, command_buffer, device_limits, group_count)?;
+            cmd_compute_write_barrier(device, command_buffer);
+            Ok(())
+        })
+    }
+
+    pub fn run_indirect(
+        &mut self
+        , device: &ash::Device
+        , pipeline: &ComputePipeline
+        , bindings: &[ComputeBinding]
+        , push_constants: &[u8]
+        , args_buffer: &GPUBuffer
+        , args_offset: u64) -> Result<(), ComputeError>
+    {
+        self.submit(device, |command_buffer, descriptor_allocator| unsafe {
+            ComputeKernelRunner::cmd_run(device, command_buffer, descriptor_allocator, pipeline, bindings, push_constants)?;
+            pipeline.cmd_dispatch_indirect(device, command_buffer, args_buffer, args_offset)?;
+            cmd_compute_write_barrier(device, command_buffer);
+            Ok(())
+        })
+    }
+
+    //Binds The Pipeline, Its Descriptor Sets And Push Constants, Leaving The Dispatch To The Caller
+    pub unsafe fn cmd_run(
+        device: &ash::Device
+        , command_buffer: vk::CommandBuffer
+        , descriptor_allocator: &mut DescriptorAllocator
+        , pipeline: &ComputePipeline
+        , bindings: &[ComputeBinding]
+        , push_constants: &[u8]) -> Result<(), ComputeError>
+    {
+        for reflected_binding in pipeline.reflection.descriptor_bindings.iter()
+        {
+            if !bindings.iter().any(|binding| binding.set == reflected_binding.set && binding.binding == reflected_binding.binding)
+            {
+                return Err(ComputeError::InvalidBinding(format!(
+                    "{} Needs Set {} Binding {} ({})", pipeline.name, reflected_binding.set, reflected_binding.binding, reflected_binding.name)));
+            }
+        }
+
+        let mut writers: Vec<DescriptorWriter> = pipeline.set_layouts.iter().map(|_| DescriptorWriter::new()).collect();
+        for (index, binding) in bindings.iter().enumerate()
+        {
+            if bindings[..index].iter().any(|earlier| earlier.set == binding.set && earlier.binding == binding.binding)
+            {
+                return Err(ComputeError::InvalidBinding(format!("Set {} Binding {} Is Given Twice", binding.set, binding.binding)));
+            }
+            let reflected_binding = pipeline.reflection.descriptor_bindings
+                .iter()
+                .find(|reflected_binding| reflected_binding.set == binding.set && reflected_binding.binding == binding.binding)
+                .ok_or_else(|| ComputeError::InvalidBinding(format!("{} Has No Set {} Binding {}", pipeline.name, binding.set, binding.binding)))?;
+            if reflected_binding.descriptor_type != vk::DescriptorType::STORAGE_BUFFER && reflected_binding.descriptor_type != vk::DescriptorType::UNIFORM_BUFFER
+            {
+                return Err(ComputeError::InvalidBinding(format!(
+                    "Set {} Binding {} ({}) Is {:?}, Only Buffers Can Be Bound", binding.set, binding.binding, reflected_binding.name, reflected_binding.descriptor_type)));
+            }
+            writers[binding.set as usize].write_buffer(binding.binding, reflected_binding.descriptor_type, binding.buffer, binding.offset, binding.range);
+        }
+
+        let descriptor_sets: Vec<vk::DescriptorSet> = pipeline.set_layouts
+            .iter()
+            .zip(writers.iter())
+            .map(|(&set_layout, writer)| {
+                let descriptor_set = descriptor_allocator.allocate(device, set_layout);
+                writer.update_set(device, descriptor_set);
+                descriptor_set
+            })
+            .collect();
+
+        pipeline.cmd_bind(device, command_buffer);
+        if !descriptor_sets.is_empty()
+        {
+            pipeline.cmd_bind_descriptor_sets(device, command_buffer, 0, &descriptor_sets);
+        }
+        pipeline.cmd_push_constants(device, command_buffer, push_constants)
+    }
+
+    //A Device Local Buffer Filled Through A Staging Copy, usage Gains TRANSFER_DST
+    pub fn upload_buffer<T: Pod>(
+        &mut self
+        , device: &ash::Device
+        , allocator: &mut TrackedAllocator
+        , name: &str
+        , usage: vk::BufferUsageFlags
+        , data: &[T]) -> Result<GPUBuffer, ComputeError>
+    {
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+        let size = (bytes.len() as u64).max(4);
+        let mut staging_buffer = GPUBuffer::create_gpu_buffer(device, allocator, &GPUBufferDesc::staging_buffer(name, size));
+        let mut gpu_buffer = GPUBuffer::create_gpu_buffer(device, allocator, &GPUBufferDesc::new(
+            name, size, usage | vk::BufferUsageFlags::TRANSFER_DST, gpu_allocator::MemoryLocation::GpuOnly));
+
+        let result = match staging_buffer.allocation.mapped_slice_mut()
+        {
+            Some(mapped_memory) =>
+            {
+                mapped_memory[..bytes.len()].copy_from_slice(bytes);
+                //Empty Data Still Gets A Buffer, Just Nothing To Copy Into It
+                let copy_regions: Vec<vk::BufferCopy> = if bytes.is_empty() { vec![] } else { vec![vk::BufferCopy{ src_offset: 0, dst_offset: 0, size: bytes.len() as u64 }] };
+                self.submit(device, |command_buffer, _| unsafe {
+                    GPUBuffer::cmd_copy_buffer_regions(device, command_buffer, &staging_buffer, &gpu_buffer, &copy_regions)?;
+                    cmd_transfer_write_barrier(device, command_buffer);
+                    Ok(())
+                })
+            },
+            None => Err(ComputeError::Buffer(GPUBufferError::NotHostVisible)),
+        };
+
+        GPUBuffer::destroy_gpu_buffer(&mut staging_buffer, device, allocator);
+        match result
+        {
+            Ok(()) => Ok(gpu_buffer),
+            Err(error) =>
+            {
+                GPUBuffer::destroy_gpu_buffer(&mut gpu_buffer, device, allocator);
+                Err(error)
+            }
+        }
+    }
+
+    //Copies The Whole Of gpu_buffer Back Through A Temporary Readback Buffer, Which Needs TRANSFER_SRC Usage
+    pub fn read_buffer<T: Pod>(
+        &mut self
+        , device: &ash::Device
+        , allocator: &mut TrackedAllocator
+        , gpu_buffer: &GPUBuffer) -> Result<Vec<T>, ComputeError>
+    {
+        if !gpu_buffer.usage.contains(vk::BufferUsageFlags::TRANSFER_SRC)
+        {
+            return Err(ComputeError::InvalidBinding("Read Back Buffer Was Not Created With TRANSFER_SRC Usage".to_owned()));
+        }
+        let element_size = std::mem::size_of::<T>() as u64;
+        let element_count = if element_size == 0 { 0 } else { gpu_buffer.size / element_size };
+
+        let mut readback_buffer = GPUBuffer::create_gpu_buffer(device, allocator, &GPUBufferDesc::readback_buffer("Compute Readback", gpu_buffer.size));
+        let result = self
+            .submit(device, |command_buffer, _| unsafe {
+                GPUBuffer::cmd_copy_buffer(device, command_buffer, gpu_buffer, &readback_buffer)?;
+                cmd_transfer_write_barrier(device, command_buffer);
+                Ok(())
+            })
+            .and_then(|_| {
+                let mapped_memory = readback_buffer.allocation
+                    .mapped_slice()
+                    .ok_or(ComputeError::Buffer(GPUBufferError::NotHostVisible))?;
+                //Copied Out Rather Than Cast, Mapped Memory Makes No Promise About T's Alignment
+                let mut elements = vec![T::zeroed(); element_count as usize];
+                bytemuck::cast_slice_mut::<T, u8>(&mut elements).copy_from_slice(&mapped_memory[..(element_count * element_size) as usize]);
+                Ok(elements)
+            });
+
+        GPUBuffer::destroy_gpu_buffer(&mut readback_buffer, device, allocator);
+        result
+    }
+
+    pub fn destroy_runner(&mut self, device: &ash::Device)
+    {
+        self.descriptor_allocator.destroy_allocator(device);
+        unsafe
+        {
+            device.destroy_command_pool(self.command_pool, None);
+            device.destroy_fence(self.fence, None);
+        }
+        self.command_pool = vk::CommandPool::null();
+        self.command_buffer = vk::CommandBuffer::null();
+        self.fence = vk::Fence::null();
+    }
+}
+
+//Makes Transfer Writes Visible To Shaders, Indirect Reads, Further Copies And The Host
+unsafe fn cmd_transfer_write_barrier(device: &ash::Device, command_buffer: vk::CommandBuffer)
+{
+    let memory_barriers = [vk::MemoryBarrier::builder()
+        .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+        .dst_access_mask(vk::AccessFlags::SHADER_READ
+            | vk::AccessFlags::SHADER_WRITE
+            | vk::AccessFlags::INDIRECT_COMMAND_READ
+            | vk::AccessFlags::TRANSFER_READ
+            | vk::AccessFlags::HOST_READ)
+        .build()];
+    device.cmd_pipeline_barrier(
+        command_buffer
+        , vk::PipelineStageFlags::TRANSFER
+        , vk::PipelineStageFlags::COMPUTE_SHADER
+            | vk::PipelineStageFlags::DRAW_INDIRECT
+            | vk::PipelineStageFlags::TRANSFER
+            | vk::PipelineStageFlags::HOST
+        , vk::DependencyFlags::empty()
+        , &memory_barriers
+        , &[]
+        , &[]);
+}
+
+//A Device, Queue And Allocator Without A Window, For Tests And Tools, Runs On Software Drivers Such As lavapipe
+//Pipelines And Buffers Made Through It Must Be Destroyed Before It Is Dropped
+pub struct HeadlessCompute
+{
+    _entry: ash::Entry,
+    instance: ash::Instance,
+    debug_utils_loader: ash::extensions::ext::DebugUtils,
+    debug_messenger: vk::DebugUtilsMessengerEXT,
+    validation_info: ValidationInfo,
+    pub physical_device: vk::PhysicalDevice,
+    pub device: ash::Device,
+    pub queue_family_index: u32,
+    pub queue: vk::Queue,
+    pub device_limits: vk::PhysicalDeviceLimits,
+    pub allocator: std::mem::ManuallyDrop<TrackedAllocator>,
+    pub descriptor_layout_cache: DescriptorLayoutCache,
+    //In Memory Only, Headless Runs Are Usually Too Short Lived To Benefit From Saving It
+    pub pipeline_cache: vk::PipelineCache,
+    pub runner: ComputeKernelRunner,
+}
+
+impl HeadlessCompute
+{
+    //Validation Is Skipped With A Warning When The Layer Is Not Installed, Headless Runs Often Happen On Bare CI Machines
+    pub fn new(application_name: &str, enable_validation: bool) -> Result<HeadlessCompute, ComputeError>
+    {
+        let entry = unsafe{
+            ash::Entry::load().map_err(ComputeError::EntryLoad)?
+        };
+        let validation_info = ValidationInfo{
+            enabled: enable_validation && debug::check_validation_layer_support(&entry),
+            required_validation_layers: VALIDATION.required_validation_layers,
+        };
+        if enable_validation && !validation_info.enabled
+        {
+            println!("Validation Layers Not Available, {} Runs Without Validation", application_name);
+        }
+        let instance = context::create_headless_instance(&entry, application_name, &validation_info);
+        let (debug_utils_loader, debug_messenger) = debug::setup_debug_utils(&entry, &instance, &validation_info);
+
+        let (physical_device, queue_family_index) = match context::pick_compute_physical_device(&instance)
+        {
+            Some(found) => found,
+            None =>
+            {
+                unsafe
+                {
+                    if validation_info.enabled
+                    {
+                        debug_utils_loader.destroy_debug_utils_messenger(debug_messenger, None);
+                    }
+                    instance.destroy_instance(None);
+                }
+                return Err(ComputeError::NoComputeDevice);
+            }
+        };
+        let device_limits = unsafe {
+            instance.get_physical_device_properties(physical_device).limits
+        };
+
+        let (device, buffer_device_address) = context::create_compute_device(&instance, physical_device, queue_family_index, &validation_info);
+        let queue = unsafe {
+            device.get_device_queue(queue_family_index, 0)
+        };
+
+        let allocator = TrackedAllocator::new(vulkan::Allocator::new(
+            &vulkan::AllocatorCreateDesc{
+                instance: instance.clone(),
+                device: device.clone(),
+                physical_device,
+                debug_settings: Default::default(),
+                buffer_device_address,
+            }
+        ).expect("Failed To Create GPU Memory Allocator!"));
+
+        let pipeline_cache = unsafe {
+            device
+                .create_pipeline_cache(&vk::PipelineCacheCreateInfo::builder(), None)
+                .expect("Failed To Create Pipeline Cache!")
+        };
+        let runner = ComputeKernelRunner::new(&device, queue_family_index, queue);
+
+        Ok(HeadlessCompute{
+            _entry: entry,
+            instance,
+            debug_utils_loader,
+            debug_messenger,
+            validation_info,
+            physical_device,
+            device,
+            queue_family_index,
+            queue,
+            device_limits,
+            allocator: std::mem::ManuallyDrop::new(allocator),
+            descriptor_layout_cache: DescriptorLayoutCache::new(),
+            pipeline_cache,
+            runner,
+        })
+    }
+
+    pub fn create_pipeline(&mut self, shader: &CompiledShader) -> Result<ComputePipeline, ComputeError>
+    {
+        ComputePipeline::new(&self.device, self.pipeline_cache, &mut self.descriptor_layout_cache, shader)
+    }
+
+    //Storage Buffer Holding data, Readable With read_buffer
+    pub fn upload_buffer<T: Pod>(&mut self, name: &str, data: &[T]) -> Result<GPUBuffer, ComputeError>
+    {
+        self.runner.upload_buffer(&self.device, &mut self.allocator, name, vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::TRANSFER_SRC, data)
+    }
+
+    //Uninitialised Output Storage Buffer
+    pub fn create_storage_buffer(&mut self, name: &str, size: u64) -> GPUBuffer
+    {
+        GPUBuffer::create_gpu_buffer(&self.device, &mut self.allocator, &GPUBufferDesc::storage_buffer(name, size))
+    }
+
+    pub fn read_buffer<T: Pod>(&mut self, gpu_buffer: &GPUBuffer) -> Result<Vec<T>, ComputeError>
+    {
+        self.runner.read_buffer(&self.device, &mut self.allocator, gpu_buffer)
+    }
+
+    pub fn run(&mut self, pipeline: &ComputePipeline, bindings: &[ComputeBinding], push_constants: &[u8], group_count: [u32; 3]) -> Result<(), ComputeError>
+    {
+        self.runner.run(&self.device, &self.device_limits, pipeline, bindings, push_constants, group_count)
+    }
+
+    //Dispatches Enough Groups To Cover element_count Invocations Along X
+    pub fn run_elements(&mut self, pipeline: &ComputePipeline, bindings: &[ComputeBinding], push_constants: &[u8], element_count: u32) -> Result<(), ComputeError>
+    {
+        let group_count = pipeline.group_count_for([element_count, 1, 1])?;
+        self.run(pipeline, bindings, push_constants, group_count)
+    }
+
+    pub fn destroy_buffer(&mut self, gpu_buffer: &mut GPUBuffer)
+    {
+        GPUBuffer::destroy_gpu_buffer(gpu_buffer, &self.device, &mut self.allocator);
+    }
+}
+
+impl Drop for HeadlessCompute
+{
+    fn drop(&mut self)
+    {
+        unsafe
+        {
+            self.device
+                .device_wait_idle()
+                .expect("Failed To Wait Device Idle!");
+            self.runner.destroy_runner(&self.device);
+            self.descriptor_layout_cache.destroy_cache(&self.device);
+            self.device.destroy_pipeline_cache(self.pipeline_cache, None);
+            std::mem::ManuallyDrop::drop(&mut self.allocator);
+            self.device.destroy_device(None);
+            if self.validation_info.enabled
+            {
+                self.debug_utils_loader
+                    .destroy_debug_utils_messenger(self.debug_messenger, None);
+            }
+            self.instance.destroy_instance(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::utility::{file_system::{compile_shader_file, ShaderType}, shader_include::ShaderIncludeResolver};
+
+    const SCALE_KERNEL: &str = r#"
+        #version 450
+        layout(local_size_x = 64) in;
+        layout(set = 0, binding = 0) readonly buffer Inputs { uint inputs[]; };
+        layout(set = 0, binding = 1) writeonly buffer Outputs { uint outputs[]; };
+        layout(push_constant) uniform Params { uint scale; uint count; } params;
+        void main()
+        {
+            uint index = gl_GlobalInvocationID.x;
+            if (index < params.count)
+            {
+                outputs[index] = inputs[index] * params.scale;
+            }
+        }
+    "#;
+
+    //Needs A Vulkan Driver, Run With cargo test -- --ignored
+    #[test]
+    #[ignore]
+    fn scale_kernel_runs_headless()
+    {
+        let mut resolver = ShaderIncludeResolver::new();
+        resolver.add_virtual_file("vfs/scale.comp", SCALE_KERNEL);
+        let shader = compile_shader_file("vfs/scale.comp", ShaderType::Compute, &resolver, None).expect("Failed To Compile Scale Kernel!");
+
+        let mut compute = HeadlessCompute::new("Compute Test", true).expect("Failed To Create Headless Compute!");
+        let mut pipeline = compute.create_pipeline(&shader).unwrap();
+        assert_eq!(pipeline.workgroup_size(), Some([64, 1, 1]));
+
+        let inputs: Vec<u32> = (0..1000).collect();
+        let mut input_buffer = compute.upload_buffer("Scale Inputs", &inputs).unwrap();
+        let mut output_buffer = compute.create_storage_buffer("Scale Outputs", std::mem::size_of_val(inputs.as_slice()) as u64);
+        let params = [3u32, inputs.len() as u32];
+        compute.run_elements(
+            &pipeline
+            , &[ComputeBinding::whole(0, 0, &input_buffer), ComputeBinding::whole(0, 1, &output_buffer)]
+            , bytemuck::cast_slice(&params)
+            , inputs.len() as u32).unwrap();
+
+        let outputs: Vec<u32> = compute.read_buffer(&output_buffer).unwrap();
+        assert_eq!(outputs, inputs.iter().map(|input| input * 3).collect::<Vec<u32>>());
+
+        compute.destroy_buffer(&mut input_buffer);
+        compute.destroy_buffer(&mut output_buffer);
+        pipeline.destroy_pipeline(&compute.device);
+    }
+}
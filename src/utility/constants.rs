@@ -10,7 +10,17 @@ pub const WINDOW_WIDTH: u32 = 800;
 pub const WINDOW_HEIGHT: u32 = 600;
 
 pub const MAX_FRAMES_IN_FLIGHT: usize = 2;
+//Shows The FPS And Frame Time In The Window Title
 pub const IS_PAINT_FPS_COUNTER: bool = false;
+//Scopes Past This Many In A Frame Are Dropped From The Profile
+pub const GPU_PROFILER_MAX_SCOPES: u32 = 64;
+pub const GPU_PROFILER_PIPELINE_STATISTICS: bool = true;
+
+//Both Are Part Of The SPIR-V Cache Key, Changing Either Invalidates Cached Shaders
+pub const SHADER_TARGET_ENV_VERSION: shaderc::EnvVersion = shaderc::EnvVersion::Vulkan1_0;
+pub const SHADER_OPTIMIZATION_LEVEL: shaderc::OptimizationLevel = shaderc::OptimizationLevel::Zero;
+pub const SHADER_CACHE_DIRECTORY: &str = "shader_cache";
+pub const PIPELINE_CACHE_PATH: &str = "pipeline_cache.bin";
 
 pub const VALIDATION: ValidationInfo = ValidationInfo
 {
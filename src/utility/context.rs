@@ -1,11 +1,25 @@
 use ash::vk;
-use crate::utility::{tools, debug, constants::*, structs, platforms, self};
+use crate::utility::{tools, debug, constants::*, structs, platforms, memory_report, self};
 
 use std::{ffi::{CString, c_char, CStr}, collections::HashSet};
 
 
 
 pub fn create_instance(entry: &ash::Entry, window_title: &str, validation_info: &debug::ValidationInfo) -> ash::Instance
+{
+    // Get Required Extension Names
+    let extension_names = platforms::required_extension_names();
+    create_instance_with_extensions(entry, window_title, validation_info, &extension_names)
+}
+
+//No Surface Extensions, For Compute Work That Never Opens A Window
+pub fn create_headless_instance(entry: &ash::Entry, application_name: &str, validation_info: &debug::ValidationInfo) -> ash::Instance
+{
+    let extension_names = [ash::extensions::ext::DebugUtils::name().as_ptr()];
+    create_instance_with_extensions(entry, application_name, validation_info, &extension_names)
+}
+
+fn create_instance_with_extensions(entry: &ash::Entry, window_title: &str, validation_info: &debug::ValidationInfo, extension_names: &[*const c_char]) -> ash::Instance
 {
     if validation_info.enabled && debug::check_validation_layer_support(entry) == false
     {
@@ -22,13 +36,10 @@ pub fn create_instance(entry: &ash::Entry, window_title: &str, validation_info:
         .api_version(API_VERSION)
         .engine_version(ENGINE_VERSION);
 
-    // Get Required Extension Names
-    let extension_names = platforms::required_extension_names();
-
     let mut create_info = vk::InstanceCreateInfo::builder()
         .flags(vk::InstanceCreateFlags::empty())
         .application_info(&app_info)
-        .enabled_extension_names(&extension_names);
+        .enabled_extension_names(extension_names);
 
 
 
@@ -86,15 +97,38 @@ pub fn create_logical_device(instance: &ash::Instance, physical_device: vk::Phys
     let indices = find_queue_family_indices(instance, physical_device, surface_context);
 
     let queue_priorities = [1.0f32];
-    let queue_create_info = vk::DeviceQueueCreateInfo::builder()
+    let mut queue_create_infos = vec![vk::DeviceQueueCreateInfo::builder()
         .flags(vk::DeviceQueueCreateFlags::empty())
         .queue_family_index(indices.graphics_family.unwrap())
-        .queue_priorities(&queue_priorities);
+        .queue_priorities(&queue_priorities)
+        .build()];
+    if let Some(transfer_family) = indices.transfer_family
+    {
+        queue_create_infos.push(vk::DeviceQueueCreateInfo::builder()
+            .flags(vk::DeviceQueueCreateFlags::empty())
+            .queue_family_index(transfer_family)
+            .queue_priorities(&queue_priorities)
+            .build());
+    }
     
+    let supported_features = unsafe {
+        instance.get_physical_device_features(physical_device)
+    };
+    let enabled_features = vk::PhysicalDeviceFeatures::builder()
+        .sampler_anisotropy(supported_features.sampler_anisotropy == vk::TRUE)
+        //Only Used By The GPU Profiler, Which Checks For It Again
+        .pipeline_statistics_query(supported_features.pipeline_statistics_query == vk::TRUE)
+        .build();
+
+    //Every Supported Descriptor Indexing Feature Is Enabled, The Bindless Heap Checks The Ones It Needs
+    let mut descriptor_indexing_feature = query_descriptor_indexing_features(instance, physical_device);
+
     let mut buffer_device_address_feature = vk::PhysicalDeviceBufferDeviceAddressFeatures::builder()
         .buffer_device_address(true);
     let mut physical_device_features = vk::PhysicalDeviceFeatures2::builder()
-        .push_next(&mut buffer_device_address_feature);
+        .features(enabled_features)
+        .push_next(&mut buffer_device_address_feature)
+        .push_next(&mut descriptor_indexing_feature);
 
     let enabled_layer_names_c: Vec<CString> = validation_info
         .required_validation_layers
@@ -107,11 +141,16 @@ pub fn create_logical_device(instance: &ash::Instance, physical_device: vk::Phys
         .map(|layer_name_c| layer_name_c.as_ptr())
         .collect();
 
-    let enabled_extension_names = get_required_device_extension_names();
+    let mut enabled_extension_names = get_required_device_extension_names();
+    //Optional, Only Feeds The Memory Budget Report
+    if memory_report::is_memory_budget_supported(instance, physical_device)
+    {
+        enabled_extension_names.push(vk::ExtMemoryBudgetFn::name().as_ptr());
+    }
 
     let mut device_create_info = vk::DeviceCreateInfo::builder()
         .flags(vk::DeviceCreateFlags::empty())
-        .queue_create_infos(std::slice::from_ref(&queue_create_info))
+        .queue_create_infos(&queue_create_infos)
         .enabled_extension_names(&enabled_extension_names)
         .push_next(&mut physical_device_features);
 
@@ -129,6 +168,123 @@ pub fn create_logical_device(instance: &ash::Instance, physical_device: vk::Phys
     (device, indices)
 }
 
+//Any Device With A Compute Queue, Scored Like pick_physical_device So CPU Drivers Such As lavapipe Still Qualify
+pub fn pick_compute_physical_device(instance: &ash::Instance) -> Option<(vk::PhysicalDevice, u32)>
+{
+    let physical_devices = unsafe{
+        instance
+            .enumerate_physical_devices()
+            .expect("Vulkan Failed To Enumerate Physical Devices!")
+    };
+
+    let mut result = None;
+    let mut device_score: u8 = 0;
+    for &physical_device in physical_devices.iter()
+    {
+        let device_properties = unsafe {
+            instance.get_physical_device_properties(physical_device)
+        };
+        let device_type_score = match device_properties.device_type
+        {
+            vk::PhysicalDeviceType::DISCRETE_GPU => 4,
+            vk::PhysicalDeviceType::INTEGRATED_GPU => 3,
+            vk::PhysicalDeviceType::VIRTUAL_GPU => 2,
+            vk::PhysicalDeviceType::CPU => 1,
+            _ => 0,
+        };
+        if device_type_score <= device_score
+        {
+            continue;
+        }
+
+        //The First Compute Family Is Normally The Universal One, Which Also Supports Timestamps
+        let queue_families = unsafe {
+            instance.get_physical_device_queue_family_properties(physical_device)
+        };
+        let compute_family = queue_families
+            .iter()
+            .position(|queue_family_prop| queue_family_prop.queue_count > 0 && queue_family_prop.queue_flags.contains(vk::QueueFlags::COMPUTE));
+        if let Some(compute_family) = compute_family
+        {
+            println!("\tCompute Device Name: {}, Queue Family: {}", tools::char_array_to_string(&device_properties.device_name), compute_family);
+            result = Some((physical_device, compute_family as u32));
+            device_score = device_type_score;
+        }
+    }
+
+    result
+}
+
+//One Queue From compute_family And No Swapchain, Returns Whether Buffer Device Address Was Enabled
+pub fn create_compute_device(instance: &ash::Instance, physical_device: vk::PhysicalDevice, compute_family: u32, validation_info: &debug::ValidationInfo) -> (ash::Device, bool)
+{
+    let queue_priorities = [1.0f32];
+    let queue_create_infos = [vk::DeviceQueueCreateInfo::builder()
+        .flags(vk::DeviceQueueCreateFlags::empty())
+        .queue_family_index(compute_family)
+        .queue_priorities(&queue_priorities)
+        .build()];
+
+    let mut supported_buffer_device_address = vk::PhysicalDeviceBufferDeviceAddressFeatures::default();
+    {
+        let mut physical_device_features = vk::PhysicalDeviceFeatures2::builder()
+            .push_next(&mut supported_buffer_device_address);
+        unsafe {
+            instance.get_physical_device_features2(physical_device, &mut physical_device_features);
+        }
+    }
+    let buffer_device_address = supported_buffer_device_address.buffer_device_address == vk::TRUE;
+
+    let mut buffer_device_address_feature = vk::PhysicalDeviceBufferDeviceAddressFeatures::builder()
+        .buffer_device_address(buffer_device_address);
+    let mut physical_device_features = vk::PhysicalDeviceFeatures2::builder()
+        .push_next(&mut buffer_device_address_feature);
+
+    let enabled_layer_names_c: Vec<CString> = validation_info
+        .required_validation_layers
+        .iter()
+        .map(|layer_name_c| CString::new(*layer_name_c).unwrap())
+        .collect();
+
+    let enabled_layer_names:Vec<*const c_char> = enabled_layer_names_c
+        .iter()
+        .map(|layer_name_c| layer_name_c.as_ptr())
+        .collect();
+
+    let mut device_create_info = vk::DeviceCreateInfo::builder()
+        .flags(vk::DeviceCreateFlags::empty())
+        .queue_create_infos(&queue_create_infos)
+        .push_next(&mut physical_device_features);
+
+    if validation_info.enabled
+    {
+        device_create_info = device_create_info.enabled_layer_names(&enabled_layer_names);
+    }
+
+    let device: ash::Device = unsafe {
+        instance
+            .create_device(physical_device, &device_create_info, None)
+            .expect("Vulkan Failed To Create Compute Device!")
+    };
+
+    (device, buffer_device_address)
+}
+
+pub fn query_descriptor_indexing_features(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> vk::PhysicalDeviceDescriptorIndexingFeatures
+{
+    let mut descriptor_indexing_feature = vk::PhysicalDeviceDescriptorIndexingFeatures::default();
+    {
+        let mut physical_device_features = vk::PhysicalDeviceFeatures2::builder()
+            .push_next(&mut descriptor_indexing_feature);
+        unsafe {
+            instance.get_physical_device_features2(physical_device, &mut physical_device_features);
+        }
+    }
+    //Detach From The Query Chain So The Struct Can Be Chained Again
+    descriptor_indexing_feature.p_next = std::ptr::null_mut();
+    descriptor_indexing_feature
+}
+
 pub fn create_surface(entry: &ash::Entry, instance: &ash::Instance, window: &winit::window::Window) -> structs::SurfaceContext
 {
     let surface = unsafe {
@@ -177,6 +333,16 @@ fn find_queue_family_indices(instance: &ash::Instance, current_device: vk::Physi
         index += 1;
     }
 
+    //Prefer A Transfer Only Family (Usually A DMA Engine), Then Any Non Graphics Family That Can Transfer
+    let find_transfer_family = |excluded_flags: vk::QueueFlags| queue_families
+        .iter()
+        .position(|queue_family_prop| queue_family_prop.queue_count > 0
+            && queue_family_prop.queue_flags.contains(vk::QueueFlags::TRANSFER)
+            && !queue_family_prop.queue_flags.intersects(excluded_flags))
+        .map(|family_index| family_index as u32);
+    queue_family_indicecs.transfer_family = find_transfer_family(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE)
+        .or_else(|| find_transfer_family(vk::QueueFlags::GRAPHICS));
+
     queue_family_indicecs
 }
 
@@ -262,45 +428,66 @@ fn is_device_support_extensions(instance: &ash::Instance, physical_device: vk::P
     return support;
 }
 
-pub fn create_image_views_2d(
+pub fn create_image_view(
     logical_device: &ash::Device,
+    image: vk::Image,
     image_format: vk::Format,
-    images: &Vec<vk::Image>,
-) -> Vec<vk::ImageView>
+    view_type: vk::ImageViewType,
+    subresource_range: vk::ImageSubresourceRange,
+) -> vk::ImageView
 {
-    let mut result_image_views = vec![];
-
-    for &image in images.iter()
-    {
-        let image_view_create_info = vk::ImageViewCreateInfo::builder()
-            .flags(vk::ImageViewCreateFlags::empty())
-            .view_type(vk::ImageViewType::TYPE_2D)
-            .format(image_format)
-            .components(vk::ComponentMapping{
-                r: vk::ComponentSwizzle::IDENTITY,
-                g: vk::ComponentSwizzle::IDENTITY,
-                b: vk::ComponentSwizzle::IDENTITY,
-                a: vk::ComponentSwizzle::IDENTITY,
-            })
-            .subresource_range(vk::ImageSubresourceRange{
-                aspect_mask: vk::ImageAspectFlags::COLOR,
-                base_mip_level: 0,
-                level_count: 1,
-                base_array_layer: 0,
-                layer_count: 1,
-            })
-            .image(image);
-
-        let image_view = unsafe{
-            logical_device
-                .create_image_view(&image_view_create_info, None)
-                .expect("Vulkan Failed To Create Image View")
-        };
+    let image_view_create_info = vk::ImageViewCreateInfo::builder()
+        .flags(vk::ImageViewCreateFlags::empty())
+        .view_type(view_type)
+        .format(image_format)
+        .components(vk::ComponentMapping{
+            r: vk::ComponentSwizzle::IDENTITY,
+            g: vk::ComponentSwizzle::IDENTITY,
+            b: vk::ComponentSwizzle::IDENTITY,
+            a: vk::ComponentSwizzle::IDENTITY,
+        })
+        .subresource_range(subresource_range)
+        .image(image);
 
-        result_image_views.push(image_view);
+    unsafe{
+        logical_device
+            .create_image_view(&image_view_create_info, None)
+            .expect("Vulkan Failed To Create Image View")
     }
+}
+
+pub fn create_image_views(
+    logical_device: &ash::Device,
+    image_format: vk::Format,
+    images: &[vk::Image],
+    view_type: vk::ImageViewType,
+    subresource_range: vk::ImageSubresourceRange,
+) -> Vec<vk::ImageView>
+{
+    images
+        .iter()
+        .map(|&image| create_image_view(logical_device, image, image_format, view_type, subresource_range))
+        .collect()
+}
 
-    result_image_views
+pub fn create_image_views_2d(
+    logical_device: &ash::Device,
+    image_format: vk::Format,
+    images: &Vec<vk::Image>,
+) -> Vec<vk::ImageView>
+{
+    create_image_views(
+        logical_device,
+        image_format,
+        images,
+        vk::ImageViewType::TYPE_2D,
+        vk::ImageSubresourceRange{
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            base_mip_level: 0,
+            level_count: 1,
+            base_array_layer: 0,
+            layer_count: 1,
+        })
 }
 
 pub fn create_framebuffer(device: &ash::Device
@@ -322,3 +509,130 @@ pub fn create_framebuffer(device: &ash::Device
     }
 }
 
+
+//Every Sample Count Usable For A Color Plus Depth Target, TYPE_1 Is Always Included
+pub fn get_usable_sample_counts(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> vk::SampleCountFlags
+{
+    let device_properties = unsafe {
+        instance.get_physical_device_properties(physical_device)
+    };
+
+    //Color And Depth Targets Are Multisampled Together, So Only Counts Supported By Both Are Usable
+    device_properties.limits.framebuffer_color_sample_counts
+        & device_properties.limits.framebuffer_depth_sample_counts
+        | vk::SampleCountFlags::TYPE_1
+}
+
+//Largest Supported Count Not Above requested, Counts Need Not Be Contiguous
+pub fn snap_sample_count(supported: vk::SampleCountFlags, requested: vk::SampleCountFlags) -> vk::SampleCountFlags
+{
+    let mut sample_count = vk::SampleCountFlags::TYPE_1;
+    for bit in 0..vk::SampleCountFlags::TYPE_64.as_raw().trailing_zeros() + 1
+    {
+        let candidate = vk::SampleCountFlags::from_raw(1 << bit);
+        if candidate.as_raw() <= requested.as_raw() && supported.contains(candidate)
+        {
+            sample_count = candidate;
+        }
+    }
+    sample_count
+}
+
+//Next Supported Count Above current, Wrapping Back To TYPE_1 After The Largest
+pub fn next_sample_count(supported: vk::SampleCountFlags, current: vk::SampleCountFlags) -> vk::SampleCountFlags
+{
+    let mut candidate = current.as_raw() << 1;
+    while candidate <= vk::SampleCountFlags::TYPE_64.as_raw()
+    {
+        if supported.contains(vk::SampleCountFlags::from_raw(candidate))
+        {
+            return vk::SampleCountFlags::from_raw(candidate);
+        }
+        candidate <<= 1;
+    }
+    vk::SampleCountFlags::TYPE_1
+}
+
+pub fn max_sample_count(supported: vk::SampleCountFlags) -> vk::SampleCountFlags
+{
+    snap_sample_count(supported, vk::SampleCountFlags::TYPE_64)
+}
+
+pub fn find_supported_format(
+    instance: &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+    candidates: &[vk::Format],
+    tiling: vk::ImageTiling,
+    features: vk::FormatFeatureFlags,
+) -> Option<vk::Format>
+{
+    for &format in candidates.iter()
+    {
+        let format_properties = unsafe {
+            instance.get_physical_device_format_properties(physical_device, format)
+        };
+
+        let supported_features = match tiling
+        {
+            vk::ImageTiling::LINEAR => format_properties.linear_tiling_features,
+            vk::ImageTiling::OPTIMAL => format_properties.optimal_tiling_features,
+            _ => vk::FormatFeatureFlags::empty(),
+        };
+
+        if supported_features.contains(features)
+        {
+            return Some(format);
+        }
+    }
+
+    None
+}
+
+pub fn find_depth_format(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> vk::Format
+{
+    find_supported_format(
+        instance,
+        physical_device,
+        &[vk::Format::D32_SFLOAT, vk::Format::D32_SFLOAT_S8_UINT, vk::Format::D24_UNORM_S8_UINT],
+        vk::ImageTiling::OPTIMAL,
+        vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+        .expect("Failed To Find Supported Depth Format")
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const TYPE_1: vk::SampleCountFlags = vk::SampleCountFlags::TYPE_1;
+    const TYPE_2: vk::SampleCountFlags = vk::SampleCountFlags::TYPE_2;
+    const TYPE_4: vk::SampleCountFlags = vk::SampleCountFlags::TYPE_4;
+    const TYPE_8: vk::SampleCountFlags = vk::SampleCountFlags::TYPE_8;
+    const TYPE_16: vk::SampleCountFlags = vk::SampleCountFlags::TYPE_16;
+
+    #[test]
+    fn sample_counts_snap_down_to_a_supported_one()
+    {
+        //No 2x, Like Some Tilers Report
+        let supported = TYPE_1 | TYPE_4 | TYPE_8;
+        assert_eq!(snap_sample_count(supported, TYPE_1), TYPE_1);
+        assert_eq!(snap_sample_count(supported, TYPE_2), TYPE_1);
+        assert_eq!(snap_sample_count(supported, TYPE_4), TYPE_4);
+        assert_eq!(snap_sample_count(supported, TYPE_16), TYPE_8);
+        assert_eq!(snap_sample_count(supported, vk::SampleCountFlags::empty()), TYPE_1);
+        assert_eq!(max_sample_count(supported), TYPE_8);
+        assert_eq!(max_sample_count(TYPE_1), TYPE_1);
+    }
+
+    #[test]
+    fn cycling_skips_unsupported_sample_counts()
+    {
+        let supported = TYPE_1 | TYPE_4 | TYPE_16;
+        assert_eq!(next_sample_count(supported, TYPE_1), TYPE_4);
+        assert_eq!(next_sample_count(supported, TYPE_4), TYPE_16);
+        assert_eq!(next_sample_count(supported, TYPE_16), TYPE_1);
+        assert_eq!(next_sample_count(TYPE_1, TYPE_1), TYPE_1);
+        assert_eq!(next_sample_count(supported | vk::SampleCountFlags::TYPE_64, vk::SampleCountFlags::TYPE_64), TYPE_1);
+    }
+}
@@ -112,11 +112,12 @@ pub fn check_validation_layer_support(entry: &ash::Entry) -> bool
 pub fn setup_debug_utils(
     entry: &ash::Entry,
     instance: &ash::Instance,
+    validation_info: &ValidationInfo,
 ) -> (ash::extensions::ext::DebugUtils, vk::DebugUtilsMessengerEXT)
 {
     let debug_utils_loader = ash::extensions::ext::DebugUtils::new(entry, instance);
 
-    if VALIDATION.enabled == false
+    if validation_info.enabled == false
     {
         return (debug_utils_loader, ash::vk::DebugUtilsMessengerEXT::null());
     }
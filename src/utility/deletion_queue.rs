@@ -0,0 +1,326 @@
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use ash::vk;
+use gpu_allocator::vulkan;
+
+use crate::utility::{constants::*, gpubuffer::GPUBuffer, gpuimage::GPUImage, memory_report::TrackedAllocator};
+
+//A Vulkan Object Whose Destruction Has Been Put Off Until The GPU Is Done With It
+pub enum DeferredResource
+{
+    Buffer{ buffer: vk::Buffer, allocation: vulkan::Allocation },
+    //The View Is Destroyed Before The Image It Looks At
+    Image{ image: vk::Image, image_view: vk::ImageView, allocation: vulkan::Allocation },
+    ImageView(vk::ImageView),
+    Sampler(vk::Sampler),
+    Framebuffer(vk::Framebuffer),
+    Pipeline(vk::Pipeline),
+    PipelineLayout(vk::PipelineLayout),
+}
+
+impl DeferredResource
+{
+    //Takes The Handles And Allocation Out, gpu_buffer Is Left Null
+    pub fn from_buffer(gpu_buffer: &mut GPUBuffer) -> DeferredResource
+    {
+        DeferredResource::Buffer{
+            buffer: std::mem::replace(&mut gpu_buffer.buffer, vk::Buffer::null()),
+            allocation: std::mem::take(&mut gpu_buffer.allocation),
+        }
+    }
+
+    //Takes The Handles And Allocation Out, gpu_image Is Left Null
+    pub fn from_image(gpu_image: &mut GPUImage) -> DeferredResource
+    {
+        DeferredResource::Image{
+            image: std::mem::replace(&mut gpu_image.image, vk::Image::null()),
+            image_view: std::mem::replace(&mut gpu_image.image_view, vk::ImageView::null()),
+            allocation: std::mem::take(&mut gpu_image.allocation),
+        }
+    }
+
+    fn destroy(self, device: &ash::Device, allocator: &mut TrackedAllocator)
+    {
+        unsafe
+        {
+            match self
+            {
+                DeferredResource::Buffer{ buffer, allocation } =>
+                {
+                    device.destroy_buffer(buffer, None);
+                    allocator
+                        .free(allocation)
+                        .expect("Failed To Free Deferred Buffer Allocation!");
+                }
+                DeferredResource::Image{ image, image_view, allocation } =>
+                {
+                    device.destroy_image_view(image_view, None);
+                    device.destroy_image(image, None);
+                    allocator
+                        .free(allocation)
+                        .expect("Failed To Free Deferred Image Allocation!");
+                }
+                DeferredResource::ImageView(image_view) => device.destroy_image_view(image_view, None),
+                DeferredResource::Sampler(sampler) => device.destroy_sampler(sampler, None),
+                DeferredResource::Framebuffer(framebuffer) => device.destroy_framebuffer(framebuffer, None),
+                DeferredResource::Pipeline(pipeline) => device.destroy_pipeline(pipeline, None),
+                DeferredResource::PipelineLayout(pipeline_layout) => device.destroy_pipeline_layout(pipeline_layout, None),
+            }
+        }
+    }
+}
+
+struct DeletionQueueState
+{
+    frame_number: u64,
+    //(Resource, Frame Number It Was Released On), Kept In Release Order
+    pending: Vec<(DeferredResource, u64)>,
+}
+
+//Cheap To Clone, Every Clone Feeds The Same Queue
+//Resources Released On Frame N Are Destroyed Once Frame N + MAX_FRAMES_IN_FLIGHT Begins,
+//By Then The In Flight Fence Of Every Frame That Could Have Used Them Has Signaled
+#[derive(Clone)]
+pub struct DeletionQueue
+{
+    state: Arc<Mutex<DeletionQueueState>>,
+}
+
+impl DeletionQueue
+{
+    pub fn new() -> DeletionQueue
+    {
+        DeletionQueue{
+            state: Arc::new(Mutex::new(DeletionQueueState{
+                frame_number: 0,
+                pending: vec![],
+            })),
+        }
+    }
+
+    pub fn defer(&self, resource: DeferredResource)
+    {
+        let mut state = self.state.lock().expect("Deletion Queue Poisoned!");
+        let frame_number = state.frame_number;
+        state.pending.push((resource, frame_number));
+    }
+
+    //Call After Waiting On This Frame's In Flight Fence, frame_number Counts Every Rendered Frame
+    pub fn begin_frame(&self, device: &ash::Device, allocator: &mut TrackedAllocator, frame_number: u64)
+    {
+        self.begin_frame_with(frame_number, |resource| resource.destroy(device, allocator));
+    }
+
+    //Hands Every Retired Resource To destroy, Newest Release First
+    pub fn begin_frame_with<F>(&self, frame_number: u64, mut destroy: F)
+        where F: FnMut(DeferredResource)
+    {
+        let retired = {
+            let mut state = self.state.lock().expect("Deletion Queue Poisoned!");
+            state.frame_number = frame_number;
+            let pending = std::mem::take(&mut state.pending);
+            let (retired, still_pending): (Vec<_>, Vec<_>) = pending
+                .into_iter()
+                .partition(|&(_, released_frame)| released_frame + MAX_FRAMES_IN_FLIGHT as u64 <= frame_number);
+            state.pending = still_pending;
+            retired
+        };
+
+        //Destroyed Outside The Lock, So Dropping A Handle From Another Thread Never Waits On The Driver
+        for (resource, _) in retired.into_iter().rev()
+        {
+            destroy(resource);
+        }
+    }
+
+    //Destroys Everything Regardless Of Frame, Only Call After vkDeviceWaitIdle
+    pub fn flush_all(&self, device: &ash::Device, allocator: &mut TrackedAllocator)
+    {
+        self.flush_all_with(|resource| resource.destroy(device, allocator));
+    }
+
+    pub fn flush_all_with<F>(&self, mut destroy: F)
+        where F: FnMut(DeferredResource)
+    {
+        let pending = std::mem::take(&mut self.state.lock().expect("Deletion Queue Poisoned!").pending);
+        for (resource, _) in pending.into_iter().rev()
+        {
+            destroy(resource);
+        }
+    }
+
+    pub fn pending_count(&self) -> usize
+    {
+        self.state.lock().expect("Deletion Queue Poisoned!").pending.len()
+    }
+}
+
+impl Default for DeletionQueue
+{
+    fn default() -> Self
+    {
+        DeletionQueue::new()
+    }
+}
+
+//Owns A GPUBuffer And Hands It To The Deletion Queue When The Last BufferHandle Drops
+pub struct OwnedBuffer
+{
+    gpu_buffer: GPUBuffer,
+    deletion_queue: DeletionQueue,
+}
+
+pub type BufferHandle = Arc<OwnedBuffer>;
+
+impl OwnedBuffer
+{
+    pub fn new(gpu_buffer: GPUBuffer, deletion_queue: &DeletionQueue) -> BufferHandle
+    {
+        Arc::new(OwnedBuffer{
+            gpu_buffer,
+            deletion_queue: deletion_queue.clone(),
+        })
+    }
+}
+
+impl Deref for OwnedBuffer
+{
+    type Target = GPUBuffer;
+
+    fn deref(&self) -> &GPUBuffer
+    {
+        &self.gpu_buffer
+    }
+}
+
+impl Drop for OwnedBuffer
+{
+    fn drop(&mut self)
+    {
+        self.deletion_queue.defer(DeferredResource::from_buffer(&mut self.gpu_buffer));
+    }
+}
+
+//Owns A GPUImage And Hands It To The Deletion Queue When The Last ImageHandle Drops
+pub struct OwnedImage
+{
+    gpu_image: GPUImage,
+    deletion_queue: DeletionQueue,
+}
+
+pub type ImageHandle = Arc<OwnedImage>;
+
+impl OwnedImage
+{
+    pub fn new(gpu_image: GPUImage, deletion_queue: &DeletionQueue) -> ImageHandle
+    {
+        Arc::new(OwnedImage{
+            gpu_image,
+            deletion_queue: deletion_queue.clone(),
+        })
+    }
+}
+
+impl Deref for OwnedImage
+{
+    type Target = GPUImage;
+
+    fn deref(&self) -> &GPUImage
+    {
+        &self.gpu_image
+    }
+}
+
+impl Drop for OwnedImage
+{
+    fn drop(&mut self)
+    {
+        self.deletion_queue.defer(DeferredResource::from_image(&mut self.gpu_image));
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use ash::vk::Handle;
+
+    fn sampler(raw: u64) -> DeferredResource
+    {
+        DeferredResource::Sampler(vk::Sampler::from_raw(raw))
+    }
+
+    //Raw Handles In The Order They Were Destroyed
+    fn recorder(destroyed: &mut Vec<u64>) -> impl FnMut(DeferredResource) + '_
+    {
+        move |resource| match resource
+        {
+            DeferredResource::Sampler(sampler) => destroyed.push(sampler.as_raw()),
+            DeferredResource::Buffer{ buffer, .. } => destroyed.push(buffer.as_raw()),
+            _ => panic!("Unexpected Resource"),
+        }
+    }
+
+    #[test]
+    fn retired_resources_are_destroyed_newest_first()
+    {
+        let deletion_queue = DeletionQueue::new();
+        deletion_queue.defer(sampler(1));
+        deletion_queue.defer(sampler(2));
+        deletion_queue.defer(sampler(3));
+
+        let mut destroyed = vec![];
+        deletion_queue.begin_frame_with(MAX_FRAMES_IN_FLIGHT as u64, recorder(&mut destroyed));
+        assert_eq!(destroyed, vec![3, 2, 1]);
+        assert_eq!(deletion_queue.pending_count(), 0);
+    }
+
+    #[test]
+    fn resources_wait_until_their_frame_fence_has_signaled()
+    {
+        let deletion_queue = DeletionQueue::new();
+        let mut destroyed = vec![];
+
+        deletion_queue.begin_frame_with(10, recorder(&mut destroyed));
+        deletion_queue.defer(sampler(10));
+        deletion_queue.begin_frame_with(11, recorder(&mut destroyed));
+        deletion_queue.defer(sampler(11));
+        assert!(destroyed.is_empty());
+
+        //Frame 10 Used The Same Frame Slot As 10 + MAX_FRAMES_IN_FLIGHT, Its Fence Was Just Waited On
+        deletion_queue.begin_frame_with(10 + MAX_FRAMES_IN_FLIGHT as u64 - 1, recorder(&mut destroyed));
+        assert!(destroyed.is_empty());
+        deletion_queue.begin_frame_with(10 + MAX_FRAMES_IN_FLIGHT as u64, recorder(&mut destroyed));
+        assert_eq!(destroyed, vec![10]);
+        deletion_queue.begin_frame_with(11 + MAX_FRAMES_IN_FLIGHT as u64, recorder(&mut destroyed));
+        assert_eq!(destroyed, vec![10, 11]);
+    }
+
+    #[test]
+    fn dropped_handles_are_drained_by_the_final_flush()
+    {
+        let deletion_queue = DeletionQueue::new();
+        let buffer_handle = OwnedBuffer::new(
+            GPUBuffer{
+                buffer: vk::Buffer::from_raw(7),
+                allocation: vulkan::Allocation::default(),
+                size: 16,
+                usage: vk::BufferUsageFlags::UNIFORM_BUFFER,
+                device_address: None,
+            }
+            , &deletion_queue);
+        let shared_handle = buffer_handle.clone();
+
+        drop(buffer_handle);
+        assert_eq!(deletion_queue.pending_count(), 0);
+        drop(shared_handle);
+        assert_eq!(deletion_queue.pending_count(), 1);
+
+        deletion_queue.defer(sampler(8));
+        let mut destroyed = vec![];
+        deletion_queue.flush_all_with(recorder(&mut destroyed));
+        assert_eq!(destroyed, vec![8, 7]);
+        assert_eq!(deletion_queue.pending_count(), 0);
+    }
+}
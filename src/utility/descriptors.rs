@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+use ash::vk;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DescriptorBindingDesc
+{
+    pub binding: u32,
+    pub descriptor_type: vk::DescriptorType,
+    pub descriptor_count: u32,
+    pub stage_flags: vk::ShaderStageFlags,
+}
+
+impl DescriptorBindingDesc
+{
+    pub fn new(binding: u32, descriptor_type: vk::DescriptorType, stage_flags: vk::ShaderStageFlags) -> DescriptorBindingDesc
+    {
+        DescriptorBindingDesc{
+            binding,
+            descriptor_type,
+            descriptor_count: 1,
+            stage_flags,
+        }
+    }
+}
+
+//Layouts Are Keyed By Their Bindings, So Pipelines Declaring The Same Set Share One Layout
+#[derive(Debug, Default)]
+pub struct DescriptorLayoutCache
+{
+    layouts: HashMap<Vec<DescriptorBindingDesc>, vk::DescriptorSetLayout>,
+}
+
+impl DescriptorLayoutCache
+{
+    pub fn new() -> DescriptorLayoutCache
+    {
+        DescriptorLayoutCache{
+            layouts: HashMap::new(),
+        }
+    }
+
+    pub fn get_layout(&mut self, device: &ash::Device, bindings: &[DescriptorBindingDesc]) -> vk::DescriptorSetLayout
+    {
+        //Binding Order Does Not Matter To Vulkan, Normalize It For The Key
+        let mut layout_key = bindings.to_vec();
+        layout_key.sort();
+
+        if let Some(&layout) = self.layouts.get(&layout_key)
+        {
+            return layout;
+        }
+
+        let layout_bindings: Vec<vk::DescriptorSetLayoutBinding> = layout_key
+            .iter()
+            .map(|binding| vk::DescriptorSetLayoutBinding::builder()
+                .binding(binding.binding)
+                .descriptor_type(binding.descriptor_type)
+                .descriptor_count(binding.descriptor_count)
+                .stage_flags(binding.stage_flags)
+                .build())
+            .collect();
+
+        let layout_create_info = vk::DescriptorSetLayoutCreateInfo::builder()
+            .bindings(&layout_bindings);
+
+        let layout = unsafe{
+            device
+                .create_descriptor_set_layout(&layout_create_info, None)
+                .expect("Failed To Create Descriptor Set Layout")
+        };
+
+        self.layouts.insert(layout_key, layout);
+        layout
+    }
+
+    pub fn destroy_cache(&mut self, device: &ash::Device)
+    {
+        unsafe
+        {
+            for &layout in self.layouts.values()
+            {
+                device.destroy_descriptor_set_layout(layout, None);
+            }
+        }
+        self.layouts.clear();
+    }
+}
+
+//Descriptor Count Per Set Allocated From A Pool, For Each Descriptor Type
+const DEFAULT_POOL_RATIOS: [(vk::DescriptorType, f32); 7] = [
+    (vk::DescriptorType::UNIFORM_BUFFER, 2.0),
+    (vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC, 1.0),
+    (vk::DescriptorType::STORAGE_BUFFER, 1.0),
+    (vk::DescriptorType::COMBINED_IMAGE_SAMPLER, 4.0),
+    (vk::DescriptorType::SAMPLED_IMAGE, 2.0),
+    (vk::DescriptorType::STORAGE_IMAGE, 1.0),
+    (vk::DescriptorType::SAMPLER, 1.0),
+];
+
+const INITIAL_SETS_PER_POOL: u32 = 64;
+const MAX_SETS_PER_POOL: u32 = 4096;
+
+//Grows By Adding Pools, Every Set Is Released Together When The Allocator Is Reset
+//Not Clone, Two Copies Would Reset And Destroy The Same Pools
+#[derive(Debug)]
+pub struct DescriptorAllocator
+{
+    pool_ratios: Vec<(vk::DescriptorType, f32)>,
+    sets_per_pool: u32,
+    used_pools: Vec<vk::DescriptorPool>,
+    free_pools: Vec<vk::DescriptorPool>,
+}
+
+impl DescriptorAllocator
+{
+    pub fn new() -> DescriptorAllocator
+    {
+        DescriptorAllocator::with_pool_ratios(&DEFAULT_POOL_RATIOS)
+    }
+
+    pub fn with_pool_ratios(pool_ratios: &[(vk::DescriptorType, f32)]) -> DescriptorAllocator
+    {
+        DescriptorAllocator{
+            pool_ratios: pool_ratios.to_vec(),
+            sets_per_pool: INITIAL_SETS_PER_POOL,
+            used_pools: vec![],
+            free_pools: vec![],
+        }
+    }
+
+    pub fn allocate(&mut self, device: &ash::Device, layout: vk::DescriptorSetLayout) -> vk::DescriptorSet
+    {
+        if self.used_pools.is_empty()
+        {
+            let pool = self.grab_pool(device);
+            self.used_pools.push(pool);
+        }
+
+        let set_layouts = [layout];
+        let current_pool = *self.used_pools.last().unwrap();
+        match Self::try_allocate(device, current_pool, &set_layouts)
+        {
+            Ok(descriptor_set) => descriptor_set,
+            //Current Pool Is Exhausted, Retry Once On A Fresh One
+            Err(vk::Result::ERROR_OUT_OF_POOL_MEMORY) | Err(vk::Result::ERROR_FRAGMENTED_POOL) =>
+            {
+                let pool = self.grab_pool(device);
+                self.used_pools.push(pool);
+                Self::try_allocate(device, pool, &set_layouts)
+                    .expect("Failed To Allocate Descriptor Set From New Pool")
+            },
+            Err(error) => panic!("Failed To Allocate Descriptor Set: {:?}", error),
+        }
+    }
+
+    pub fn reset_pools(&mut self, device: &ash::Device)
+    {
+        for &pool in self.used_pools.iter()
+        {
+            unsafe
+            {
+                device
+                    .reset_descriptor_pool(pool, vk::DescriptorPoolResetFlags::empty())
+                    .expect("Failed To Reset Descriptor Pool!");
+            }
+        }
+        self.free_pools.append(&mut self.used_pools);
+    }
+
+    pub fn destroy_allocator(&mut self, device: &ash::Device)
+    {
+        unsafe
+        {
+            for &pool in self.used_pools.iter().chain(self.free_pools.iter())
+            {
+                device.destroy_descriptor_pool(pool, None);
+            }
+        }
+        self.used_pools.clear();
+        self.free_pools.clear();
+        self.sets_per_pool = INITIAL_SETS_PER_POOL;
+    }
+
+    fn try_allocate(device: &ash::Device, pool: vk::DescriptorPool, set_layouts: &[vk::DescriptorSetLayout]) -> Result<vk::DescriptorSet, vk::Result>
+    {
+        let allocate_info = vk::DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(pool)
+            .set_layouts(set_layouts);
+
+        unsafe
+        {
+            device
+                .allocate_descriptor_sets(&allocate_info)
+                .map(|descriptor_sets| descriptor_sets[0])
+        }
+    }
+
+    fn grab_pool(&mut self, device: &ash::Device) -> vk::DescriptorPool
+    {
+        if let Some(pool) = self.free_pools.pop()
+        {
+            return pool;
+        }
+
+        let pool_sizes: Vec<vk::DescriptorPoolSize> = self.pool_ratios
+            .iter()
+            .map(|&(descriptor_type, ratio)| vk::DescriptorPoolSize{
+                ty: descriptor_type,
+                descriptor_count: ((ratio * self.sets_per_pool as f32) as u32).max(1),
+            })
+            .collect();
+
+        let pool_create_info = vk::DescriptorPoolCreateInfo::builder()
+            .max_sets(self.sets_per_pool)
+            .pool_sizes(&pool_sizes);
+
+        let pool = unsafe{
+            device
+                .create_descriptor_pool(&pool_create_info, None)
+                .expect("Failed To Create Descriptor Pool")
+        };
+
+        //Each New Pool Is Bigger, So A Busy Frame Settles On Few Pools
+        self.sets_per_pool = (self.sets_per_pool + self.sets_per_pool / 2).min(MAX_SETS_PER_POOL);
+
+        pool
+    }
+}
+
+impl Default for DescriptorAllocator
+{
+    fn default() -> Self
+    {
+        DescriptorAllocator::new()
+    }
+}
+
+enum DescriptorWriteInfo
+{
+    Buffer(usize),
+    Image(usize),
+}
+
+//Collects Writes, Then Applies Them To A Set In One vkUpdateDescriptorSets Call
+#[derive(Default)]
+pub struct DescriptorWriter
+{
+    buffer_infos: Vec<vk::DescriptorBufferInfo>,
+    image_infos: Vec<vk::DescriptorImageInfo>,
+    writes: Vec<(u32, vk::DescriptorType, DescriptorWriteInfo)>,
+}
+
+impl DescriptorWriter
+{
+    pub fn new() -> DescriptorWriter
+    {
+        DescriptorWriter::default()
+    }
+
+    pub fn write_buffer(&mut self, binding: u32, descriptor_type: vk::DescriptorType, buffer: vk::Buffer, offset: u64, range: u64) -> &mut Self
+    {
+        self.buffer_infos.push(vk::DescriptorBufferInfo{
+            buffer,
+            offset,
+            range,
+        });
+        self.writes.push((binding, descriptor_type, DescriptorWriteInfo::Buffer(self.buffer_infos.len() - 1)));
+        self
+    }
+
+    pub fn write_image(&mut self, binding: u32, descriptor_type: vk::DescriptorType, image_view: vk::ImageView, image_layout: vk::ImageLayout, sampler: vk::Sampler) -> &mut Self
+    {
+        self.image_infos.push(vk::DescriptorImageInfo{
+            sampler,
+            image_view,
+            image_layout,
+        });
+        self.writes.push((binding, descriptor_type, DescriptorWriteInfo::Image(self.image_infos.len() - 1)));
+        self
+    }
+
+    pub fn write_sampled_image(&mut self, binding: u32, image_view: vk::ImageView, sampler: vk::Sampler) -> &mut Self
+    {
+        self.write_image(binding, vk::DescriptorType::COMBINED_IMAGE_SAMPLER, image_view, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL, sampler)
+    }
+
+    pub fn write_sampler(&mut self, binding: u32, sampler: vk::Sampler) -> &mut Self
+    {
+        self.write_image(binding, vk::DescriptorType::SAMPLER, vk::ImageView::null(), vk::ImageLayout::UNDEFINED, sampler)
+    }
+
+    pub fn clear(&mut self)
+    {
+        self.buffer_infos.clear();
+        self.image_infos.clear();
+        self.writes.clear();
+    }
+
+    pub fn update_set(&self, device: &ash::Device, descriptor_set: vk::DescriptorSet)
+    {
+        let descriptor_writes: Vec<vk::WriteDescriptorSet> = self.writes
+            .iter()
+            .map(|(binding, descriptor_type, write_info)| {
+                let write = vk::WriteDescriptorSet::builder()
+                    .dst_set(descriptor_set)
+                    .dst_binding(*binding)
+                    .dst_array_element(0)
+                    .descriptor_type(*descriptor_type);
+                match write_info
+                {
+                    DescriptorWriteInfo::Buffer(index) => write.buffer_info(std::slice::from_ref(&self.buffer_infos[*index])).build(),
+                    DescriptorWriteInfo::Image(index) => write.image_info(std::slice::from_ref(&self.image_infos[*index])).build(),
+                }
+            })
+            .collect();
+
+        unsafe
+        {
+            device.update_descriptor_sets(&descriptor_writes, &[]);
+        }
+    }
+}
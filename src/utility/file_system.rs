@@ -3,20 +3,387 @@ use std::io;
 use std::io::Read;
 use std::ffi::CString;
 
+use ash::vk;
+
 use shaderc::CompilationArtifact;
 use shaderc::CompileOptions;
 
+use crate::utility::{constants::*, shader_reflection::*, shader_include::ShaderIncludeResolver, shader_error::*, shader_cache::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ShaderType
 {
     Vertex,
     Fragment,
     Compute,
+    Geometry,
+    TessellationControl,
+    TessellationEvaluation,
+    Task,
+    Mesh,
+    RayGeneration,
+    AnyHit,
+    ClosestHit,
+    Miss,
+    Intersection,
+    Callable,
+}
+
+impl ShaderType
+{
+    pub fn shader_kind(&self) -> shaderc::ShaderKind
+    {
+        match self
+        {
+            ShaderType::Vertex => shaderc::ShaderKind::Vertex,
+            ShaderType::Fragment => shaderc::ShaderKind::Fragment,
+            ShaderType::Compute => shaderc::ShaderKind::Compute,
+            ShaderType::Geometry => shaderc::ShaderKind::Geometry,
+            ShaderType::TessellationControl => shaderc::ShaderKind::TessControl,
+            ShaderType::TessellationEvaluation => shaderc::ShaderKind::TessEvaluation,
+            ShaderType::Task => shaderc::ShaderKind::Task,
+            ShaderType::Mesh => shaderc::ShaderKind::Mesh,
+            ShaderType::RayGeneration => shaderc::ShaderKind::RayGeneration,
+            ShaderType::AnyHit => shaderc::ShaderKind::AnyHit,
+            ShaderType::ClosestHit => shaderc::ShaderKind::ClosestHit,
+            ShaderType::Miss => shaderc::ShaderKind::Miss,
+            ShaderType::Intersection => shaderc::ShaderKind::Intersection,
+            ShaderType::Callable => shaderc::ShaderKind::Callable,
+        }
+    }
+
+    pub fn stage_flags(&self) -> vk::ShaderStageFlags
+    {
+        match self
+        {
+            ShaderType::Vertex => vk::ShaderStageFlags::VERTEX,
+            ShaderType::Fragment => vk::ShaderStageFlags::FRAGMENT,
+            ShaderType::Compute => vk::ShaderStageFlags::COMPUTE,
+            ShaderType::Geometry => vk::ShaderStageFlags::GEOMETRY,
+            ShaderType::TessellationControl => vk::ShaderStageFlags::TESSELLATION_CONTROL,
+            ShaderType::TessellationEvaluation => vk::ShaderStageFlags::TESSELLATION_EVALUATION,
+            ShaderType::Task => vk::ShaderStageFlags::TASK_EXT,
+            ShaderType::Mesh => vk::ShaderStageFlags::MESH_EXT,
+            ShaderType::RayGeneration => vk::ShaderStageFlags::RAYGEN_KHR,
+            ShaderType::AnyHit => vk::ShaderStageFlags::ANY_HIT_KHR,
+            ShaderType::ClosestHit => vk::ShaderStageFlags::CLOSEST_HIT_KHR,
+            ShaderType::Miss => vk::ShaderStageFlags::MISS_KHR,
+            ShaderType::Intersection => vk::ShaderStageFlags::INTERSECTION_KHR,
+            ShaderType::Callable => vk::ShaderStageFlags::CALLABLE_KHR,
+        }
+    }
+
+    //Defined To 1 While Compiling For This Stage, So One Source Can Hold Several Stages
+    pub fn frequency_macro(&self) -> &'static str
+    {
+        match self
+        {
+            ShaderType::Vertex => "SHADER_FREQUENCY_VERTEX",
+            ShaderType::Fragment => "SHADER_FREQUENCY_FRAGMENT",
+            ShaderType::Compute => "SHADER_FREQUENCY_COMPUTE",
+            ShaderType::Geometry => "SHADER_FREQUENCY_GEOMETRY",
+            ShaderType::TessellationControl => "SHADER_FREQUENCY_TESSELLATION_CONTROL",
+            ShaderType::TessellationEvaluation => "SHADER_FREQUENCY_TESSELLATION_EVALUATION",
+            ShaderType::Task => "SHADER_FREQUENCY_TASK",
+            ShaderType::Mesh => "SHADER_FREQUENCY_MESH",
+            ShaderType::RayGeneration => "SHADER_FREQUENCY_RAY_GENERATION",
+            ShaderType::AnyHit => "SHADER_FREQUENCY_ANY_HIT",
+            ShaderType::ClosestHit => "SHADER_FREQUENCY_CLOSEST_HIT",
+            ShaderType::Miss => "SHADER_FREQUENCY_MISS",
+            ShaderType::Intersection => "SHADER_FREQUENCY_INTERSECTION",
+            ShaderType::Callable => "SHADER_FREQUENCY_CALLABLE",
+        }
+    }
+
+    //HLSL Sources Keep Every Stage In One File, Each Under Its Own Entry Point
+    pub fn hlsl_entry_point(&self) -> &'static str
+    {
+        match self
+        {
+            ShaderType::Vertex => "vert",
+            ShaderType::Fragment => "frag",
+            ShaderType::Compute => "comp",
+            ShaderType::Geometry => "geom",
+            ShaderType::TessellationControl => "tesc",
+            ShaderType::TessellationEvaluation => "tese",
+            ShaderType::Task => "task",
+            ShaderType::Mesh => "mesh",
+            ShaderType::RayGeneration => "rgen",
+            ShaderType::AnyHit => "rahit",
+            ShaderType::ClosestHit => "rchit",
+            ShaderType::Miss => "rmiss",
+            ShaderType::Intersection => "rint",
+            ShaderType::Callable => "rcall",
+        }
+    }
+
+    //Ray Tracing And Mesh Shading Need SPIR-V 1.4, Which Needs A Vulkan 1.2 Target
+    pub fn minimum_target_env_version(&self) -> shaderc::EnvVersion
+    {
+        match self
+        {
+            ShaderType::Task | ShaderType::Mesh
+            | ShaderType::RayGeneration | ShaderType::AnyHit | ShaderType::ClosestHit
+            | ShaderType::Miss | ShaderType::Intersection | ShaderType::Callable => shaderc::EnvVersion::Vulkan1_2,
+            _ => shaderc::EnvVersion::Vulkan1_0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShaderLanguage
+{
+    Glsl,
+    Hlsl,
+    //Precompiled Binary, Loaded As Is
+    SpirV,
+}
+
+impl ShaderLanguage
+{
+    //.hlsl And .spv Are Recognized, Everything Else Is Treated As GLSL
+    pub fn from_path(path: &str) -> ShaderLanguage
+    {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref()
+        {
+            Some("hlsl") => ShaderLanguage::Hlsl,
+            Some("spv") => ShaderLanguage::SpirV,
+            _ => ShaderLanguage::Glsl,
+        }
+    }
+
+    pub fn default_entry_point(&self, shader_type: ShaderType) -> &'static str
+    {
+        match self
+        {
+            ShaderLanguage::Hlsl => shader_type.hlsl_entry_point(),
+            ShaderLanguage::Glsl | ShaderLanguage::SpirV => "main",
+        }
+    }
+}
+
+//Everything That Decides What SPIR-V Comes Out Of A Shader File, Besides The Source Itself
+#[derive(Debug, Clone)]
+pub struct ShaderDesc
+{
+    pub path: String,
+    pub shader_type: ShaderType,
+    //None Infers The Language From The Extension
+    pub language: Option<ShaderLanguage>,
+    //None Uses The Language's Default For The Stage
+    pub entry_point: Option<String>,
+    //Defined After The Built In SHADER_FREQUENCY_* And ENTRY_POINT Macros
+    pub macros: Vec<(String, String)>,
+    pub optimization_level: shaderc::OptimizationLevel,
+    pub target_env_version: shaderc::EnvVersion,
+    //None Lets The Target Environment Pick
+    pub target_spirv_version: Option<shaderc::SpirvVersion>,
+}
+
+impl ShaderDesc
+{
+    pub fn new(path: &str, shader_type: ShaderType) -> ShaderDesc
+    {
+        let target_env_version = if (shader_type.minimum_target_env_version() as u32) > (SHADER_TARGET_ENV_VERSION as u32)
+        {
+            shader_type.minimum_target_env_version()
+        }
+        else
+        {
+            SHADER_TARGET_ENV_VERSION
+        };
+        ShaderDesc{
+            path: path.to_owned(),
+            shader_type,
+            language: None,
+            entry_point: None,
+            macros: vec![],
+            optimization_level: SHADER_OPTIMIZATION_LEVEL,
+            target_env_version,
+            target_spirv_version: None,
+        }
+    }
+
+    pub fn with_macros(path: &str, shader_type: ShaderType, macros: &[(String, String)]) -> ShaderDesc
+    {
+        ShaderDesc{
+            macros: macros.to_vec(),
+            ..ShaderDesc::new(path, shader_type)
+        }
+    }
+
+    //A Binary From Another Toolchain, Only The Stage And Entry Point Matter
+    pub fn precompiled(path: &str, shader_type: ShaderType, entry_point: &str) -> ShaderDesc
+    {
+        ShaderDesc{
+            language: Some(ShaderLanguage::SpirV),
+            entry_point: Some(entry_point.to_owned()),
+            ..ShaderDesc::new(path, shader_type)
+        }
+    }
+
+    pub fn resolved_language(&self) -> ShaderLanguage
+    {
+        self.language.unwrap_or_else(|| ShaderLanguage::from_path(&self.path))
+    }
+
+    pub fn resolved_entry_point(&self) -> &str
+    {
+        match &self.entry_point
+        {
+            Some(entry_point) => entry_point,
+            None => self.resolved_language().default_entry_point(self.shader_type),
+        }
+    }
+
+    //Built In Macros First, Then The Caller's
+    fn all_macros(&self) -> Vec<(String, String)>
+    {
+        let mut macros = vec![];
+        if self.resolved_language() == ShaderLanguage::Glsl
+        {
+            macros.push(("ENTRY_POINT".to_owned(), self.resolved_entry_point().to_owned()));
+        }
+        macros.push((self.shader_type.frequency_macro().to_owned(), "1".to_owned()));
+        macros.extend(self.macros.iter().cloned());
+        macros
+    }
 }
 
 pub struct ShaderSourceData
 {
     pub compiledData : CompilationArtifact,
-    pub entryPoint : CString
+    pub entryPoint : CString,
+    //Compiled Fine, But The Compiler Had Something To Say
+    pub warnings : Vec<ShaderDiagnostic>,
+}
+
+impl ShaderSourceData
+{
+    pub fn reflect(&self) -> Result<ShaderReflection, ShaderReflectionError>
+    {
+        reflect_spirv(self.compiledData.as_binary(), &self.entryPoint.to_string_lossy())
+    }
+}
+
+//Owned SPIR-V Words, Unlike ShaderSourceData It Can Be Sent Across Threads And Kept Around
+pub struct CompiledShader
+{
+    pub path: String,
+    pub shader_type: ShaderType,
+    pub spirv: Vec<u32>,
+    pub entry_point: CString,
+    pub warnings: Vec<ShaderDiagnostic>,
+}
+
+impl CompiledShader
+{
+    pub fn from_source_data(path: &str, shader_type: ShaderType, source_data: ShaderSourceData) -> CompiledShader
+    {
+        CompiledShader{
+            path: path.to_owned(),
+            shader_type,
+            spirv: source_data.compiledData.as_binary().to_vec(),
+            entry_point: source_data.entryPoint,
+            warnings: source_data.warnings,
+        }
+    }
+
+    pub fn stage_flags(&self) -> vk::ShaderStageFlags
+    {
+        self.shader_type.stage_flags()
+    }
+
+    pub fn reflect(&self) -> Result<ShaderReflection, ShaderReflectionError>
+    {
+        reflect_spirv(&self.spirv, &self.entry_point.to_string_lossy())
+    }
+}
+
+pub fn compile_shader_file(path: &str, shader_type: ShaderType, include_resolver: &ShaderIncludeResolver, shader_cache: Option<&ShaderCache>) -> Result<CompiledShader, ShaderError>
+{
+    compile_shader(&ShaderDesc::new(path, shader_type), include_resolver, shader_cache)
+}
+
+//Extra Macros Take Part In The Cache Key, So Every Variant Gets Its Own Entry
+pub fn compile_shader_file_with_macros(
+    path: &str
+    , shader_type: ShaderType
+    , extra_macros: &[(String, String)]
+    , include_resolver: &ShaderIncludeResolver
+    , shader_cache: Option<&ShaderCache>) -> Result<CompiledShader, ShaderError>
+{
+    compile_shader(&ShaderDesc::with_macros(path, shader_type, extra_macros), include_resolver, shader_cache)
+}
+
+//One Entry Point For GLSL, HLSL And Precompiled SPIR-V
+//With A Cache, Sources Are Preprocessed First And The SPIR-V Is Reused When Nothing That Affects It Changed
+pub fn compile_shader(desc: &ShaderDesc, include_resolver: &ShaderIncludeResolver, shader_cache: Option<&ShaderCache>) -> Result<CompiledShader, ShaderError>
+{
+    let path = desc.path.as_str();
+    if desc.resolved_language() == ShaderLanguage::SpirV
+    {
+        return load_spirv_file(desc);
+    }
+    let minimum_target_env_version = desc.shader_type.minimum_target_env_version();
+    if (desc.target_env_version as u32) < (minimum_target_env_version as u32)
+    {
+        return Err(ShaderError::InvalidDesc{
+            path: path.to_owned(),
+            message: format!("{:?} Shaders Need At Least {:?}, Got {:?}", desc.shader_type, minimum_target_env_version, desc.target_env_version),
+        });
+    }
+
+    let shader_source = include_resolver
+        .load_source(path)
+        .map_err(|error| ShaderError::Io{ path: path.to_owned(), error })?;
+    let (compiler, options) = create_compiler_and_options(desc, include_resolver)?;
+
+    let cache_entry = match shader_cache
+    {
+        Some(shader_cache) =>
+        {
+            let preprocessed = compiler
+                .preprocess(&shader_source, path, desc.resolved_entry_point(), Some(&options))
+                .map_err(|error| compile_error_to_shader_error(error, path, include_resolver))?;
+            let cache_key = shader_cache_key(desc, preprocessed.as_text().as_bytes());
+            if let Some(spirv) = shader_cache.load(&cache_key)
+            {
+                //Warnings Are Only Reported When The Shader Is Actually Compiled
+                return Ok(CompiledShader{
+                    path: path.to_owned(),
+                    shader_type: desc.shader_type,
+                    spirv,
+                    entry_point: entry_point_cstring(path, desc.resolved_entry_point())?,
+                    warnings: vec![],
+                });
+            }
+            Some((shader_cache, cache_key))
+        }
+        None => None,
+    };
+
+    let source_data = compile_with_diagnostics(&compiler, &shader_source, desc.shader_type.shader_kind(), path, desc.resolved_entry_point(), &options, include_resolver)?;
+    let mut compiled_shader = CompiledShader::from_source_data(path, desc.shader_type, source_data);
+    //The SPIR-V Is Still Good, So A Failed Store Is Reported With The Compiler Warnings Instead Of Failing The Compile
+    if let Some((shader_cache, cache_key)) = cache_entry
+    {
+        if let Err(error) = shader_cache.store(&cache_key, &compiled_shader.spirv)
+        {
+            compiled_shader.warnings.push(ShaderDiagnostic{
+                severity: DiagnosticSeverity::Warning,
+                file: path.to_owned(),
+                line: None,
+                column: None,
+                message: format!("Failed To Cache SPIR-V In {}: {}", shader_cache.directory().display(), error),
+                source_line: None,
+            });
+        }
+    }
+    Ok(compiled_shader)
 }
 
 pub fn load_shader_src(path: &str) -> io::Result<String>{
@@ -27,79 +394,299 @@ pub fn load_shader_src(path: &str) -> io::Result<String>{
     Ok(contents)
 }
 
-pub fn load_and_compile_shader_src(path: &str, shader_type: ShaderType) -> ShaderSourceData{
+//The Entry Point Must Exist And Run At The Requested Stage, Otherwise Pipeline Creation Would Be The First To Notice
+fn load_spirv_file(desc: &ShaderDesc) -> Result<CompiledShader, ShaderError>
+{
+    let path = desc.path.as_str();
+    let invalid_binary = |message: String| ShaderError::InvalidBinary{ path: path.to_owned(), message };
 
-    let shader_source = load_shader_src(&path).unwrap();
-    let compiler = shaderc::Compiler::new().unwrap();
-    let mut options = shaderc::CompileOptions::new().unwrap();
-    options.add_macro_definition("ENTRY_POINT", Some("main"));
+    let bytes = fs::read(path).map_err(|error| ShaderError::Io{ path: path.to_owned(), error })?;
+    if bytes.len() % 4 != 0
+    {
+        return Err(invalid_binary(format!("Size {} Is Not A Multiple Of 4", bytes.len())));
+    }
+    let spirv: Vec<u32> = bytes
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        .collect();
+    if spirv.first() != Some(&SPIRV_MAGIC_NUMBER)
+    {
+        return Err(invalid_binary("Missing SPIR-V Magic Number".to_owned()));
+    }
 
-    let mut shaderc_shader_kind = shaderc::ShaderKind::Vertex;
-    match shader_type
+    let compiled_shader = CompiledShader{
+        path: path.to_owned(),
+        shader_type: desc.shader_type,
+        spirv,
+        entry_point: entry_point_cstring(path, desc.resolved_entry_point())?,
+        warnings: vec![],
+    };
+    let reflection = compiled_shader.reflect().map_err(|error| invalid_binary(error.to_string()))?;
+    if reflection.stage != desc.shader_type.stage_flags()
     {
-        ShaderType::Vertex => {
-            options.add_macro_definition("SHADER_FREQUENCY_VERTEX", Some("1"));
-            shaderc_shader_kind = shaderc::ShaderKind::Vertex;
-        },
-        ShaderType::Fragment => {
-            options.add_macro_definition("SHADER_FREQUENCY_FRAGMENT", Some("1"));
-            shaderc_shader_kind = shaderc::ShaderKind::Fragment;
+        return Err(invalid_binary(format!("Entry Point {} Is A {:?} Shader, Expected {:?}", reflection.entry_point, reflection.stage, desc.shader_type.stage_flags())));
+    }
+    Ok(compiled_shader)
+}
 
-        },
-        ShaderType::Compute => {
-            options.add_macro_definition("SHADER_FREQUENCY_COMPUTE", Some("1"));
-            shaderc_shader_kind = shaderc::ShaderKind::Compute;
-        },
+fn entry_point_cstring(path: &str, entry_point: &str) -> Result<CString, ShaderError>
+{
+    CString::new(entry_point).map_err(|_| ShaderError::InvalidDesc{
+        path: path.to_owned(),
+        message: format!("Entry Point {:?} Contains A NUL Byte", entry_point),
+    })
+}
+
+fn create_compiler_and_options<'a>(desc: &ShaderDesc, include_resolver: &'a ShaderIncludeResolver) -> Result<(shaderc::Compiler, CompileOptions<'a>), ShaderError>
+{
+    let path = desc.path.as_str();
+    let compiler = shaderc::Compiler::new()
+        .ok_or_else(|| ShaderError::CompilerUnavailable(format!("Failed To Create Compiler For {}", path)))?;
+    let mut options = shaderc::CompileOptions::new()
+        .ok_or_else(|| ShaderError::CompilerUnavailable(format!("Failed To Create Compile Options For {}", path)))?;
+
+    include_resolver.install(&mut options, path);
+    ShaderCompileSettings::from_desc(desc).apply(&mut options);
+    Ok((compiler, options))
+}
+
+//Everything Set On CompileOptions Besides The Include Callback, Applied And Hashed From One Place
+//So A New Option Cannot Reach The Compiler Without Also Reaching The Cache Key
+#[derive(Debug, Clone, PartialEq)]
+struct ShaderCompileSettings
+{
+    source_language: shaderc::SourceLanguage,
+    target_env: shaderc::TargetEnv,
+    target_env_version: u32,
+    target_spirv_version: Option<shaderc::SpirvVersion>,
+    optimization_level: shaderc::OptimizationLevel,
+    macros: Vec<(String, String)>,
+}
+
+impl ShaderCompileSettings
+{
+    fn from_desc(desc: &ShaderDesc) -> ShaderCompileSettings
+    {
+        let source_language = match desc.resolved_language()
+        {
+            ShaderLanguage::Hlsl => shaderc::SourceLanguage::HLSL,
+            _ => shaderc::SourceLanguage::GLSL,
+        };
+        ShaderCompileSettings{
+            source_language,
+            target_env: shaderc::TargetEnv::Vulkan,
+            target_env_version: desc.target_env_version as u32,
+            target_spirv_version: desc.target_spirv_version,
+            optimization_level: desc.optimization_level,
+            macros: desc.all_macros(),
+        }
     }
-    let binary_result = compiler.compile_into_spirv(&shader_source
-        , shaderc_shader_kind
-        , path
-        , "main"
-        , Some(&options)).unwrap();
 
-    ShaderSourceData{
-        compiledData : binary_result,
-        entryPoint : CString::new("main").unwrap()
+    fn apply(&self, options: &mut CompileOptions)
+    {
+        options.set_source_language(self.source_language);
+        options.set_target_env(self.target_env, self.target_env_version);
+        if let Some(target_spirv_version) = self.target_spirv_version
+        {
+            options.set_target_spirv(target_spirv_version);
+        }
+        options.set_optimization_level(self.optimization_level);
+        for (name, value) in self.macros.iter()
+        {
+            options.add_macro_definition(name, Some(value));
+        }
     }
-    
 }
 
-pub fn load_and_compile_hlsl_shader_src(path: &str, shader_type: ShaderType) -> ShaderSourceData{
+//shaderc Has No Version Query Beyond The SPIR-V Version, So A Tiny Shader Is Compiled Once Instead
+//Its Output Carries The Generator Version In The Header And Changes With Any Codegen Change
+fn shaderc_compiler_fingerprint() -> &'static [u8]
+{
+    static COMPILER_FINGERPRINT: std::sync::OnceLock<Vec<u8>> = std::sync::OnceLock::new();
+    COMPILER_FINGERPRINT.get_or_init(|| {
+        shaderc::Compiler::new()
+            .and_then(|compiler| compiler
+                .compile_into_spirv("#version 450\nvoid main() {}\n", shaderc::ShaderKind::Vertex, "fingerprint.vert", "main", None)
+                .ok())
+            .map(|artifact| artifact.as_binary_u8().to_vec())
+            .unwrap_or_default()
+    })
+}
 
-    let shader_source = load_shader_src(&path).unwrap();
-    let compiler = shaderc::Compiler::new().unwrap();
-    let mut options = shaderc::CompileOptions::new().unwrap();
-    //options.add_macro_definition("ENTRY_POINT", Some("main"));
-    options.set_source_language(shaderc::SourceLanguage::HLSL);
+//Preprocessed Source Already Has Includes And Macros Expanded, The Rest Is Listed So Any Change Still Misses
+fn shader_cache_key(desc: &ShaderDesc, preprocessed_source: &[u8]) -> ShaderCacheKey
+{
+    let key_parts: Vec<Vec<u8>> = vec![
+        preprocessed_source.to_vec(),
+        format!("{:?}", desc.shader_type.shader_kind()).into_bytes(),
+        desc.resolved_entry_point().as_bytes().to_vec(),
+        format!("{:?}", ShaderCompileSettings::from_desc(desc)).into_bytes(),
+        format!("{:?}", shaderc::get_spirv_version()).into_bytes(),
+        shaderc_compiler_fingerprint().to_vec(),
+    ];
+    ShaderCacheKey::new(&key_parts)
+}
 
-    let mut shaderc_shader_kind = shaderc::ShaderKind::Vertex;
-    let mut shader_entry_point = "vert";
-    match shader_type
+fn compile_error_to_shader_error(error: shaderc::Error, path: &str, include_resolver: &ShaderIncludeResolver) -> ShaderError
+{
+    match error
     {
-        ShaderType::Vertex => {
-            options.add_macro_definition("SHADER_FREQUENCY_VERTEX", Some("1"));
-            shaderc_shader_kind = shaderc::ShaderKind::Vertex;
-            shader_entry_point = "vert";
+        shaderc::Error::CompilationError(_, messages) => ShaderError::Compilation{
+            path: path.to_owned(),
+            diagnostics: parse_shaderc_messages(&messages, path, |file: &str| include_resolver.load_source(file).ok()),
         },
-        ShaderType::Fragment => {
-            options.add_macro_definition("SHADER_FREQUENCY_FRAGMENT", Some("1"));
-            shaderc_shader_kind = shaderc::ShaderKind::Fragment;
-            shader_entry_point = "frag";
-        },
-        ShaderType::Compute => {
-            options.add_macro_definition("SHADER_FREQUENCY_COMPUTE", Some("1"));
-            shaderc_shader_kind = shaderc::ShaderKind::Compute;
-            shader_entry_point = "comp";
+        error => ShaderError::Internal{
+            path: path.to_owned(),
+            message: error.to_string(),
         },
     }
-    let binary_result = compiler.compile_into_spirv(&shader_source
-        , shaderc_shader_kind
-        , path
-        , shader_entry_point
-        , Some(&options)).unwrap();
+}
 
-    ShaderSourceData{
+fn compile_with_diagnostics(
+    compiler: &shaderc::Compiler
+    , shader_source: &str
+    , shader_kind: shaderc::ShaderKind
+    , path: &str
+    , entry_point: &str
+    , options: &CompileOptions
+    , include_resolver: &ShaderIncludeResolver) -> Result<ShaderSourceData, ShaderError>
+{
+    let binary_result = compiler
+        .compile_into_spirv(shader_source, shader_kind, path, entry_point, Some(options))
+        .map_err(|error| compile_error_to_shader_error(error, path, include_resolver))?;
+
+    let warnings = parse_shaderc_messages(&binary_result.get_warning_messages(), path, |file: &str| include_resolver.load_source(file).ok());
+    Ok(ShaderSourceData{
         compiledData : binary_result,
-        entryPoint : CString::new(shader_entry_point).unwrap()
+        entryPoint : entry_point_cstring(path, entry_point)?,
+        warnings,
+    })
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const PASSTHROUGH_FRAGMENT_SHADER: &str = "#version 450\nlayout(location = 0) out vec4 out_color;\nvoid main() { out_color = vec4(1.0); }\n";
+
+    fn fragment_spirv() -> Vec<u8>
+    {
+        let compiler = shaderc::Compiler::new().expect("Failed To Create Compiler!");
+        let artifact = compiler
+            .compile_into_spirv(PASSTHROUGH_FRAGMENT_SHADER, shaderc::ShaderKind::Fragment, "passthrough.frag", "main", None)
+            .expect("Failed To Compile Test Shader!");
+        artifact.as_binary_u8().to_vec()
+    }
+
+    #[test]
+    fn language_follows_the_extension()
+    {
+        assert_eq!(ShaderLanguage::from_path("shaders/testShader.glsl"), ShaderLanguage::Glsl);
+        assert_eq!(ShaderLanguage::from_path("shaders/testShader.frag"), ShaderLanguage::Glsl);
+        assert_eq!(ShaderLanguage::from_path("shaders/testShader.hlsl"), ShaderLanguage::Hlsl);
+        assert_eq!(ShaderLanguage::from_path("shaders/TESTSHADER.HLSL"), ShaderLanguage::Hlsl);
+        assert_eq!(ShaderLanguage::from_path("shaders/testShader.frag.spv"), ShaderLanguage::SpirV);
+        assert_eq!(ShaderLanguage::from_path("shaders/testShader"), ShaderLanguage::Glsl);
+        assert_eq!(ShaderLanguage::from_path("shaders.hlsl/testShader"), ShaderLanguage::Glsl);
+    }
+
+    #[test]
+    fn desc_defaults_follow_the_stage_and_language()
+    {
+        let vertex = ShaderDesc::new("shaders/testShader.glsl", ShaderType::Vertex);
+        assert_eq!(vertex.resolved_language(), ShaderLanguage::Glsl);
+        assert_eq!(vertex.resolved_entry_point(), "main");
+        assert_eq!(vertex.target_env_version as u32, SHADER_TARGET_ENV_VERSION as u32);
+        assert_eq!(vertex.optimization_level as u32, SHADER_OPTIMIZATION_LEVEL as u32);
+        assert_eq!(vertex.target_spirv_version, None);
+        assert_eq!(vertex.all_macros()[0], ("ENTRY_POINT".to_string(), "main".to_string()));
+
+        let hlsl_fragment = ShaderDesc::new("shaders/testShader.hlsl", ShaderType::Fragment);
+        assert_eq!(hlsl_fragment.resolved_entry_point(), ShaderType::Fragment.hlsl_entry_point());
+        assert!(hlsl_fragment.all_macros().iter().all(|(name, _)| name != "ENTRY_POINT"));
+
+        //Ray Tracing Stages Are Raised To Their Minimum Target
+        let ray_generation = ShaderDesc::new("shaders/raygen.glsl", ShaderType::RayGeneration);
+        assert_eq!(ray_generation.target_env_version as u32, shaderc::EnvVersion::Vulkan1_2 as u32);
+
+        let with_macros = ShaderDesc::with_macros("shaders/testShader.glsl", ShaderType::Vertex, &[("USE_FOG".to_string(), "1".to_string())]);
+        assert_eq!(with_macros.all_macros().last(), Some(&("USE_FOG".to_string(), "1".to_string())));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn invalid_descs_are_rejected_before_compiling()
+    {
+        let include_resolver = ShaderIncludeResolver::new();
+
+        let mut below_minimum = ShaderDesc::new("shaders/raygen.glsl", ShaderType::RayGeneration);
+        below_minimum.target_env_version = shaderc::EnvVersion::Vulkan1_0;
+        assert!(matches!(compile_shader(&below_minimum, &include_resolver, None), Err(ShaderError::InvalidDesc{ .. })));
+
+        let directory = std::env::temp_dir().join(format!("file_system_test_nul_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let spirv_path = directory.join("passthrough.frag.spv");
+        fs::write(&spirv_path, fragment_spirv()).unwrap();
+        let nul_entry_point = ShaderDesc::precompiled(&spirv_path.to_string_lossy(), ShaderType::Fragment, "ma\0in");
+        assert!(matches!(compile_shader(&nul_entry_point, &include_resolver, None), Err(ShaderError::InvalidDesc{ .. })));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn spirv_binaries_load_as_is()
+    {
+        let include_resolver = ShaderIncludeResolver::new();
+        let directory = std::env::temp_dir().join(format!("file_system_test_spv_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path_of = |name: &str| directory.join(name).to_string_lossy().into_owned();
+
+        let spirv = fragment_spirv();
+        fs::write(path_of("passthrough.frag.spv"), &spirv).unwrap();
+        let compiled_shader = compile_shader(&ShaderDesc::new(&path_of("passthrough.frag.spv"), ShaderType::Fragment), &include_resolver, None)
+            .expect("SPIR-V Should Load");
+        assert_eq!(bytemuck::cast_slice::<u32, u8>(&compiled_shader.spirv), &spirv[..]);
+        assert_eq!(compiled_shader.entry_point.to_str(), Ok("main"));
+
+        //Wrong Stage, Missing Entry Point, Truncated Words And Non SPIR-V Data
+        let wrong_stage = compile_shader(&ShaderDesc::new(&path_of("passthrough.frag.spv"), ShaderType::Vertex), &include_resolver, None);
+        let missing_entry_point = compile_shader(&ShaderDesc::precompiled(&path_of("passthrough.frag.spv"), ShaderType::Fragment, "frag"), &include_resolver, None);
+        fs::write(path_of("truncated.spv"), &spirv[..spirv.len() - 1]).unwrap();
+        fs::write(path_of("text.spv"), b"void main() {}\n\n").unwrap();
+        let truncated = compile_shader(&ShaderDesc::new(&path_of("truncated.spv"), ShaderType::Fragment), &include_resolver, None);
+        let not_spirv = compile_shader(&ShaderDesc::new(&path_of("text.spv"), ShaderType::Fragment), &include_resolver, None);
+        for result in [wrong_stage, missing_entry_point, truncated, not_spirv]
+        {
+            assert!(matches!(result, Err(ShaderError::InvalidBinary{ .. })));
+        }
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn cache_keys_follow_the_compile_settings()
+    {
+        let source = PASSTHROUGH_FRAGMENT_SHADER.as_bytes();
+        let desc = ShaderDesc::new("shaders/testShader.frag", ShaderType::Fragment);
+        assert_eq!(shader_cache_key(&desc, source), shader_cache_key(&desc.clone(), source));
+
+        let mut optimized = desc.clone();
+        optimized.optimization_level = shaderc::OptimizationLevel::Size;
+        let mut newer_target = desc.clone();
+        newer_target.target_spirv_version = Some(shaderc::SpirvVersion::V1_5);
+        let with_macros = ShaderDesc::with_macros("shaders/testShader.frag", ShaderType::Fragment, &[("USE_FOG".to_string(), "1".to_string())]);
+        let hlsl = ShaderDesc::new("shaders/testShader.hlsl", ShaderType::Fragment);
+        for changed in [optimized, newer_target, with_macros, hlsl]
+        {
+            assert_ne!(shader_cache_key(&desc, source), shader_cache_key(&changed, source), "{:?}", changed);
+        }
+    }
+
+    #[test]
+    fn compiler_fingerprint_is_spirv()
+    {
+        let fingerprint = shaderc_compiler_fingerprint();
+        assert_eq!(fingerprint.first_chunk::<4>().map(|magic| u32::from_le_bytes(*magic)), Some(0x0723_0203));
+        assert_eq!(fingerprint, shaderc_compiler_fingerprint());
+    }
+}
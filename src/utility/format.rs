@@ -0,0 +1,88 @@
+use ash::vk;
+
+//(Block Width, Block Height, Bytes Per Block), Uncompressed Formats Use 1x1 Blocks
+pub fn format_block_info(format: vk::Format) -> Option<(u32, u32, u32)>
+{
+    match format
+    {
+        vk::Format::R8_UNORM | vk::Format::R8_SRGB => Some((1, 1, 1)),
+        vk::Format::R8G8_UNORM | vk::Format::R16_SFLOAT => Some((1, 1, 2)),
+        vk::Format::R8G8B8A8_UNORM | vk::Format::R8G8B8A8_SRGB
+        | vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB
+        | vk::Format::A2B10G10R10_UNORM_PACK32 | vk::Format::R16G16_SFLOAT
+        | vk::Format::R32_SFLOAT => Some((1, 1, 4)),
+        vk::Format::R16G16B16A16_SFLOAT | vk::Format::R32G32_SFLOAT => Some((1, 1, 8)),
+        vk::Format::R32G32B32A32_SFLOAT => Some((1, 1, 16)),
+        vk::Format::BC1_RGB_UNORM_BLOCK | vk::Format::BC1_RGB_SRGB_BLOCK
+        | vk::Format::BC1_RGBA_UNORM_BLOCK | vk::Format::BC1_RGBA_SRGB_BLOCK
+        | vk::Format::BC4_UNORM_BLOCK | vk::Format::BC4_SNORM_BLOCK => Some((4, 4, 8)),
+        vk::Format::BC2_UNORM_BLOCK | vk::Format::BC2_SRGB_BLOCK
+        | vk::Format::BC3_UNORM_BLOCK | vk::Format::BC3_SRGB_BLOCK
+        | vk::Format::BC5_UNORM_BLOCK | vk::Format::BC5_SNORM_BLOCK
+        | vk::Format::BC6H_UFLOAT_BLOCK | vk::Format::BC6H_SFLOAT_BLOCK
+        | vk::Format::BC7_UNORM_BLOCK | vk::Format::BC7_SRGB_BLOCK => Some((4, 4, 16)),
+        _ => None,
+    }
+}
+
+//Partial Blocks At The Edge Still Take A Whole Block
+pub fn blocks_covering(texels: u32, block_dimension: u32) -> u32
+{
+    texels.div_ceil(block_dimension)
+}
+
+pub fn format_mip_size(format: vk::Format, extent: vk::Extent3D) -> Option<u64>
+{
+    let (block_width, block_height, block_size) = format_block_info(format)?;
+    let blocks_x = blocks_covering(extent.width, block_width) as u64;
+    let blocks_y = blocks_covering(extent.height, block_height) as u64;
+    blocks_x
+        .checked_mul(blocks_y)?
+        .checked_mul(extent.depth as u64)?
+        .checked_mul(block_size as u64)
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extent(width: u32, height: u32, depth: u32) -> vk::Extent3D
+    {
+        vk::Extent3D{ width, height, depth }
+    }
+
+    #[test]
+    fn mip_sizes_cover_whole_blocks()
+    {
+        let cases = [
+            (vk::Format::R8G8B8A8_UNORM, extent(4, 4, 1), Some(64)),
+            (vk::Format::R8G8B8A8_UNORM, extent(3, 5, 2), Some(3 * 5 * 2 * 4)),
+            (vk::Format::R8_UNORM, extent(1, 1, 1), Some(1)),
+            (vk::Format::R16G16B16A16_SFLOAT, extent(2, 2, 1), Some(32)),
+            (vk::Format::BC1_RGBA_UNORM_BLOCK, extent(8, 8, 1), Some(4 * 8)),
+            (vk::Format::BC3_UNORM_BLOCK, extent(8, 8, 1), Some(4 * 16)),
+            (vk::Format::BC7_SRGB_BLOCK, extent(6, 5, 1), Some(4 * 16)),
+            //Mip Tails Smaller Than A Block Still Take A Whole Block
+            (vk::Format::BC1_RGB_UNORM_BLOCK, extent(1, 1, 1), Some(8)),
+            (vk::Format::BC1_RGBA_SRGB_BLOCK, extent(2, 2, 1), Some(8)),
+            (vk::Format::BC4_UNORM_BLOCK, extent(1, 2, 1), Some(8)),
+            (vk::Format::BC5_UNORM_BLOCK, extent(1, 1, 1), Some(16)),
+            (vk::Format::BC6H_UFLOAT_BLOCK, extent(2, 1, 1), Some(16)),
+            (vk::Format::UNDEFINED, extent(4, 4, 1), None),
+            (vk::Format::ASTC_4X4_UNORM_BLOCK, extent(4, 4, 1), None),
+        ];
+        for (format, extent, expected) in cases
+        {
+            assert_eq!(format_mip_size(format, extent), expected, "{:?} {:?}", format, extent);
+        }
+    }
+
+    #[test]
+    fn mip_sizes_do_not_overflow()
+    {
+        assert_eq!(format_mip_size(vk::Format::R32G32B32A32_SFLOAT, extent(u32::MAX, u32::MAX, u32::MAX)), None);
+        assert_eq!(format_mip_size(vk::Format::R8_UNORM, extent(u32::MAX, u32::MAX, 1)), Some(u32::MAX as u64 * u32::MAX as u64));
+    }
+}
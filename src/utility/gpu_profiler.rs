@@ -0,0 +1,613 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use ash::vk;
+
+//How Often fps() And frame_milliseconds() Change, Averaging Over The Interval Keeps Them Readable
+pub const FPS_UPDATE_INTERVAL: Duration = Duration::from_millis(500);
+
+//Results Come Back In Ascending Bit Order, Which Is The Field Order Of PipelineStatistics
+const PIPELINE_STATISTICS_FLAGS: vk::QueryPipelineStatisticFlags = vk::QueryPipelineStatisticFlags::from_raw(
+    vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES.as_raw()
+    | vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES.as_raw()
+    | vk::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS.as_raw()
+    | vk::QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS.as_raw()
+    | vk::QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES.as_raw()
+    | vk::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS.as_raw()
+    | vk::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS.as_raw());
+const PIPELINE_STATISTICS_COUNT: usize = 7;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineStatistics
+{
+    pub input_assembly_vertices: u64,
+    pub input_assembly_primitives: u64,
+    pub vertex_shader_invocations: u64,
+    pub clipping_invocations: u64,
+    pub clipping_primitives: u64,
+    pub fragment_shader_invocations: u64,
+    pub compute_shader_invocations: u64,
+}
+
+impl PipelineStatistics
+{
+    fn from_results(results: &[u64; PIPELINE_STATISTICS_COUNT]) -> PipelineStatistics
+    {
+        PipelineStatistics{
+            input_assembly_vertices: results[0],
+            input_assembly_primitives: results[1],
+            vertex_shader_invocations: results[2],
+            clipping_invocations: results[3],
+            clipping_primitives: results[4],
+            fragment_shader_invocations: results[5],
+            compute_shader_invocations: results[6],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuScopeTiming
+{
+    pub name: String,
+    //0 For Scopes Opened With No Other Scope Open
+    pub depth: u32,
+    pub gpu_milliseconds: f64,
+    //Only Top Level Scopes, Queries Of One Type Cannot Be Active Together
+    pub statistics: Option<PipelineStatistics>,
+}
+
+//Everything Measured For One Frame, Available Once Its Fence Has Signalled
+#[derive(Debug, Clone)]
+pub struct FrameProfile
+{
+    pub frame_number: u64,
+    //Interval Between This Frame's begin_frame And The Next One
+    pub cpu_milliseconds: f64,
+    //In The Order The Scopes Were Opened, So Children Follow Their Parent
+    pub scopes: Vec<GpuScopeTiming>,
+    //Opened After The Query Pool Ran Out, Or Never Closed
+    pub dropped_scopes: u32,
+}
+
+impl FrameProfile
+{
+    //Sum Of The Top Level Scopes
+    pub fn gpu_milliseconds(&self) -> f64
+    {
+        self.scopes
+            .iter()
+            .filter(|scope| scope.depth == 0)
+            .map(|scope| scope.gpu_milliseconds)
+            .sum()
+    }
+}
+
+impl fmt::Display for FrameProfile
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        writeln!(f, "Frame {}: CPU {:.3} ms, GPU {:.3} ms", self.frame_number, self.cpu_milliseconds, self.gpu_milliseconds())?;
+        for scope in self.scopes.iter()
+        {
+            write!(f, "{}{}: {:.3} ms", "    ".repeat(scope.depth as usize + 1), scope.name, scope.gpu_milliseconds)?;
+            if let Some(statistics) = &scope.statistics
+            {
+                write!(f, " (Vertices {}, Primitives {}, VS {}, Clipped {}/{}, FS {}, CS {})"
+                    , statistics.input_assembly_vertices
+                    , statistics.input_assembly_primitives
+                    , statistics.vertex_shader_invocations
+                    , statistics.clipping_primitives
+                    , statistics.clipping_invocations
+                    , statistics.fragment_shader_invocations
+                    , statistics.compute_shader_invocations)?;
+            }
+            writeln!(f)?;
+        }
+        if self.dropped_scopes > 0
+        {
+            writeln!(f, "    {} Scopes Dropped", self.dropped_scopes)?;
+        }
+        Ok(())
+    }
+}
+
+//Frames Per Second Averaged Over FPS_UPDATE_INTERVAL
+pub struct FpsCounter
+{
+    last_update: Instant,
+    frames_since_update: u32,
+    fps: f64,
+    frame_milliseconds: f64,
+}
+
+impl FpsCounter
+{
+    pub fn new() -> FpsCounter
+    {
+        FpsCounter{
+            last_update: Instant::now(),
+            frames_since_update: 0,
+            fps: 0.0,
+            frame_milliseconds: 0.0,
+        }
+    }
+
+    //True When The Averages Were Refreshed By This Frame
+    pub fn tick(&mut self, now: Instant) -> bool
+    {
+        self.frames_since_update += 1;
+        let elapsed = now.saturating_duration_since(self.last_update);
+        if elapsed < FPS_UPDATE_INTERVAL
+        {
+            return false;
+        }
+        self.fps = self.frames_since_update as f64 / elapsed.as_secs_f64();
+        self.frame_milliseconds = elapsed.as_secs_f64() * 1000.0 / self.frames_since_update as f64;
+        self.frames_since_update = 0;
+        self.last_update = now;
+        true
+    }
+
+    pub fn fps(&self) -> f64
+    {
+        self.fps
+    }
+
+    pub fn frame_milliseconds(&self) -> f64
+    {
+        self.frame_milliseconds
+    }
+}
+
+impl Default for FpsCounter
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+struct ScopeRecord
+{
+    name: String,
+    depth: u32,
+    begin_query: u32,
+    end_query: Option<u32>,
+    statistics_query: Option<u32>,
+}
+
+//Queries Written By One Frame In Flight, Reused Once That Frame's Fence Has Signalled
+struct ProfilerFrame
+{
+    timestamp_pool: vk::QueryPool,
+    //Null When Pipeline Statistics Are Off Or Unsupported
+    statistics_pool: vk::QueryPool,
+    scopes: Vec<ScopeRecord>,
+    open_scopes: Vec<usize>,
+    next_timestamp_query: u32,
+    next_statistics_query: u32,
+    dropped_scopes: u32,
+    frame_number: u64,
+    frame_start: Instant,
+    //Filled In When The Next Frame Begins
+    cpu_milliseconds: f64,
+    //Set Once cmd_begin_frame Has Reset The Pools, Nothing Can Be Read Back Before That
+    recorded: bool,
+}
+
+//Named, Nestable GPU Timing Scopes Recorded Into Command Buffers, Plus CPU Frame Timing
+//Without Timestamp Support On The Queue The Scopes Record Nothing But CPU Timing Still Works
+pub struct GpuProfiler
+{
+    frames: Vec<ProfilerFrame>,
+    current_frame: usize,
+    max_scopes: u32,
+    timestamp_supported: bool,
+    //Nanoseconds Per Timestamp Tick
+    timestamp_period: f64,
+    timestamp_mask: u64,
+    //Frame In Flight That Called begin_frame Last, Its CPU Time Ends When The Next One Begins
+    previous_frame: Option<usize>,
+    latest_profile: Option<FrameProfile>,
+    fps_counter: FpsCounter,
+    fps_updated: bool,
+}
+
+impl GpuProfiler
+{
+    //queue_family_index Is The Family The Profiled Command Buffers Are Submitted To
+    //Pipeline Statistics Also Need The pipelineStatisticsQuery Feature Enabled On The Device
+    pub fn new(
+        instance: &ash::Instance
+        , physical_device: vk::PhysicalDevice
+        , device: &ash::Device
+        , queue_family_index: u32
+        , frames_in_flight: usize
+        , max_scopes: u32
+        , pipeline_statistics: bool) -> GpuProfiler
+    {
+        let properties = unsafe { instance.get_physical_device_properties(physical_device) };
+        let features = unsafe { instance.get_physical_device_features(physical_device) };
+        let queue_families = unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
+        let timestamp_valid_bits = queue_families
+            .get(queue_family_index as usize)
+            .map_or(0, |queue_family_prop| queue_family_prop.timestamp_valid_bits);
+
+        let timestamp_supported = timestamp_valid_bits > 0 && properties.limits.timestamp_period > 0.0;
+        let statistics_supported = pipeline_statistics && features.pipeline_statistics_query == vk::TRUE;
+        if !timestamp_supported
+        {
+            println!("GPU Timestamps Are Not Supported On Queue Family {}, Only CPU Timing Is Profiled", queue_family_index);
+        }
+        else if pipeline_statistics && !statistics_supported
+        {
+            println!("Pipeline Statistics Queries Are Not Supported, GPU Scopes Only Report Timings");
+        }
+
+        let frames = (0..frames_in_flight)
+            .map(|_| {
+                let create_pool = |query_type: vk::QueryType, query_count: u32, statistics: vk::QueryPipelineStatisticFlags| {
+                    let query_pool_create_info = vk::QueryPoolCreateInfo::builder()
+                        .query_type(query_type)
+                        .query_count(query_count)
+                        .pipeline_statistics(statistics);
+                    unsafe
+                    {
+                        device
+                            .create_query_pool(&query_pool_create_info, None)
+                            .expect("Failed To Create Query Pool!")
+                    }
+                };
+                ProfilerFrame{
+                    //A Begin And An End Timestamp Per Scope
+                    timestamp_pool: if timestamp_supported { create_pool(vk::QueryType::TIMESTAMP, max_scopes * 2, vk::QueryPipelineStatisticFlags::empty()) } else { vk::QueryPool::null() },
+                    statistics_pool: if timestamp_supported && statistics_supported { create_pool(vk::QueryType::PIPELINE_STATISTICS, max_scopes, PIPELINE_STATISTICS_FLAGS) } else { vk::QueryPool::null() },
+                    scopes: vec![],
+                    open_scopes: vec![],
+                    next_timestamp_query: 0,
+                    next_statistics_query: 0,
+                    dropped_scopes: 0,
+                    frame_number: 0,
+                    frame_start: Instant::now(),
+                    cpu_milliseconds: 0.0,
+                    recorded: false,
+                }
+            })
+            .collect();
+
+        GpuProfiler{
+            frames,
+            current_frame: 0,
+            max_scopes,
+            timestamp_supported,
+            timestamp_period: properties.limits.timestamp_period as f64,
+            timestamp_mask: if timestamp_valid_bits >= 64 { u64::MAX } else { (1u64 << timestamp_valid_bits) - 1 },
+            previous_frame: None,
+            latest_profile: None,
+            fps_counter: FpsCounter::new(),
+            fps_updated: false,
+        }
+    }
+
+    pub fn is_timestamp_supported(&self) -> bool
+    {
+        self.timestamp_supported
+    }
+
+    //Call Once The Fence Of frame_in_flight Has Signalled, Returns The Profile Of The Frame That Last Used It
+    pub fn begin_frame(&mut self, device: &ash::Device, frame_in_flight: usize, frame_number: u64) -> Option<&FrameProfile>
+    {
+        let now = Instant::now();
+        self.fps_updated = self.fps_counter.tick(now);
+        if let Some(previous_frame) = self.previous_frame
+        {
+            let frame = &mut self.frames[previous_frame];
+            frame.cpu_milliseconds = now.saturating_duration_since(frame.frame_start).as_secs_f64() * 1000.0;
+        }
+        self.previous_frame = Some(frame_in_flight);
+
+        self.current_frame = frame_in_flight;
+        let finished_profile = self.read_frame(device, frame_in_flight);
+
+        let frame = &mut self.frames[frame_in_flight];
+        frame.scopes.clear();
+        frame.open_scopes.clear();
+        frame.next_timestamp_query = 0;
+        frame.next_statistics_query = 0;
+        frame.dropped_scopes = 0;
+        frame.frame_number = frame_number;
+        frame.frame_start = now;
+        frame.cpu_milliseconds = 0.0;
+        frame.recorded = false;
+
+        if finished_profile.is_some()
+        {
+            self.latest_profile = finished_profile;
+        }
+        self.latest_profile.as_ref()
+    }
+
+    fn read_frame(&self, device: &ash::Device, frame_in_flight: usize) -> Option<FrameProfile>
+    {
+        let frame = &self.frames[frame_in_flight];
+        if !frame.recorded
+        {
+            return None;
+        }
+
+        //Each Query Carries Its Own Availability, So One Scope Left Open Only Drops Itself
+        let timestamps = unsafe { query_results_with_availability(device, frame.timestamp_pool, frame.next_timestamp_query, [0u64; 2]) };
+        let statistics = unsafe { query_results_with_availability(device, frame.statistics_pool, frame.next_statistics_query, [0u64; PIPELINE_STATISTICS_COUNT + 1]) };
+        let (scopes, unavailable_scopes) = resolve_scopes(&frame.scopes, &timestamps, &statistics, self.timestamp_mask, self.timestamp_period);
+        let dropped_scopes = frame.dropped_scopes + unavailable_scopes;
+
+        Some(FrameProfile{
+            frame_number: frame.frame_number,
+            cpu_milliseconds: frame.cpu_milliseconds,
+            scopes,
+            dropped_scopes,
+        })
+    }
+
+    //First Thing In The Frame's Command Buffer, Outside Any Render Pass
+    pub unsafe fn cmd_begin_frame(&mut self, device: &ash::Device, command_buffer: vk::CommandBuffer)
+    {
+        let max_scopes = self.max_scopes;
+        let frame = &mut self.frames[self.current_frame];
+        if frame.timestamp_pool != vk::QueryPool::null()
+        {
+            device.cmd_reset_query_pool(command_buffer, frame.timestamp_pool, 0, max_scopes * 2);
+        }
+        if frame.statistics_pool != vk::QueryPool::null()
+        {
+            device.cmd_reset_query_pool(command_buffer, frame.statistics_pool, 0, max_scopes);
+        }
+        frame.recorded = true;
+    }
+
+    //A Scope Opened Inside A Render Pass Must Be Closed In The Same Subpass
+    pub unsafe fn cmd_begin_scope(&mut self, device: &ash::Device, command_buffer: vk::CommandBuffer, name: &str)
+    {
+        let max_scopes = self.max_scopes;
+        let frame = &mut self.frames[self.current_frame];
+        if !self.timestamp_supported || !frame.recorded
+        {
+            return;
+        }
+        //Dropped Scopes Still Go On The Stack So Their cmd_end_scope Pairs Up Correctly
+        if frame.next_timestamp_query + 2 > max_scopes * 2
+        {
+            frame.dropped_scopes += 1;
+            frame.open_scopes.push(usize::MAX);
+            return;
+        }
+
+        let depth = frame.open_scopes.len() as u32;
+        let begin_query = frame.next_timestamp_query;
+        frame.next_timestamp_query += 2;
+        device.cmd_write_timestamp(command_buffer, vk::PipelineStageFlags::TOP_OF_PIPE, frame.timestamp_pool, begin_query);
+
+        let statistics_query = if depth == 0 && frame.statistics_pool != vk::QueryPool::null()
+        {
+            let query = frame.next_statistics_query;
+            frame.next_statistics_query += 1;
+            device.cmd_begin_query(command_buffer, frame.statistics_pool, query, vk::QueryControlFlags::empty());
+            Some(query)
+        }
+        else
+        {
+            None
+        };
+
+        frame.open_scopes.push(frame.scopes.len());
+        frame.scopes.push(ScopeRecord{
+            name: name.to_owned(),
+            depth,
+            begin_query,
+            end_query: None,
+            statistics_query,
+        });
+    }
+
+    pub unsafe fn cmd_end_scope(&mut self, device: &ash::Device, command_buffer: vk::CommandBuffer)
+    {
+        let frame = &mut self.frames[self.current_frame];
+        let scope_index = match frame.open_scopes.pop()
+        {
+            Some(scope_index) if scope_index != usize::MAX => scope_index,
+            _ => return,
+        };
+
+        let scope = &mut frame.scopes[scope_index];
+        if let Some(query) = scope.statistics_query
+        {
+            device.cmd_end_query(command_buffer, frame.statistics_pool, query);
+        }
+        //Reserved Next To The Begin Query When The Scope Opened
+        let end_query = scope.begin_query + 1;
+        device.cmd_write_timestamp(command_buffer, vk::PipelineStageFlags::BOTTOM_OF_PIPE, frame.timestamp_pool, end_query);
+        scope.end_query = Some(end_query);
+    }
+
+    //Most Recent Frame Whose Results Have Come Back, Lagging Rendering By The Frames In Flight
+    pub fn latest_profile(&self) -> Option<&FrameProfile>
+    {
+        self.latest_profile.as_ref()
+    }
+
+    pub fn fps(&self) -> f64
+    {
+        self.fps_counter.fps()
+    }
+
+    pub fn frame_milliseconds(&self) -> f64
+    {
+        self.fps_counter.frame_milliseconds()
+    }
+
+    //True For The Frame Whose begin_frame Refreshed fps(), At Most Once Per FPS_UPDATE_INTERVAL
+    pub fn fps_updated(&self) -> bool
+    {
+        self.fps_updated
+    }
+
+    pub fn destroy_profiler(&mut self, device: &ash::Device)
+    {
+        for frame in self.frames.iter_mut()
+        {
+            unsafe
+            {
+                device.destroy_query_pool(frame.timestamp_pool, None);
+                device.destroy_query_pool(frame.statistics_pool, None);
+            }
+            frame.timestamp_pool = vk::QueryPool::null();
+            frame.statistics_pool = vk::QueryPool::null();
+        }
+        self.frames.clear();
+    }
+}
+
+//Results Are Read With WITH_AVAILABILITY, So The Last Element Of Each Is Non Zero Once The Query Has Been Written
+//NOT_READY Still Fills In Every Available Query, Any Other Error Leaves Them All Unavailable
+unsafe fn query_results_with_availability<const N: usize>(device: &ash::Device, query_pool: vk::QueryPool, query_count: u32, unavailable: [u64; N]) -> Vec<[u64; N]>
+{
+    let mut results = vec![unavailable; query_count as usize];
+    if query_count > 0
+    {
+        let read_result = device.get_query_pool_results(query_pool, 0, query_count, &mut results, vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WITH_AVAILABILITY);
+        if !matches!(read_result, Ok(()) | Err(vk::Result::NOT_READY))
+        {
+            results.fill(unavailable);
+        }
+    }
+    results
+}
+
+fn available_result<const N: usize>(results: &[[u64; N]], query: u32) -> Option<&[u64; N]>
+{
+    results.get(query as usize).filter(|result| result[N - 1] != 0)
+}
+
+//Scopes Whose Begin Or End Timestamp Is Missing Are Dropped And Counted, Missing Statistics Only Clear That Scope's Statistics
+fn resolve_scopes(
+    scope_records: &[ScopeRecord]
+    , timestamps: &[[u64; 2]]
+    , statistics: &[[u64; PIPELINE_STATISTICS_COUNT + 1]]
+    , timestamp_mask: u64
+    , timestamp_period: f64) -> (Vec<GpuScopeTiming>, u32)
+{
+    let mut dropped_scopes = 0;
+    let mut scopes = vec![];
+    for scope in scope_records.iter()
+    {
+        let begin = available_result(timestamps, scope.begin_query);
+        let end = scope.end_query.and_then(|end_query| available_result(timestamps, end_query));
+        let (begin, end) = match (begin, end)
+        {
+            (Some(begin), Some(end)) => (begin[0], end[0]),
+            _ =>
+            {
+                dropped_scopes += 1;
+                continue;
+            }
+        };
+        let ticks = (end & timestamp_mask).wrapping_sub(begin & timestamp_mask) & timestamp_mask;
+        scopes.push(GpuScopeTiming{
+            name: scope.name.clone(),
+            depth: scope.depth,
+            gpu_milliseconds: ticks as f64 * timestamp_period / 1_000_000.0,
+            statistics: scope.statistics_query
+                .and_then(|query| available_result(statistics, query))
+                .and_then(|result| result.first_chunk::<PIPELINE_STATISTICS_COUNT>())
+                .map(PipelineStatistics::from_results),
+        });
+    }
+    (scopes, dropped_scopes)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn statistics_count_matches_the_enabled_counters()
+    {
+        assert_eq!(PIPELINE_STATISTICS_FLAGS.as_raw().count_ones() as usize, PIPELINE_STATISTICS_COUNT);
+    }
+
+    #[test]
+    fn statistics_are_read_in_flag_bit_order()
+    {
+        let statistics = PipelineStatistics::from_results(&[1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(statistics, PipelineStatistics{
+            input_assembly_vertices: 1,
+            input_assembly_primitives: 2,
+            vertex_shader_invocations: 3,
+            clipping_invocations: 4,
+            clipping_primitives: 5,
+            fragment_shader_invocations: 6,
+            compute_shader_invocations: 7,
+        });
+    }
+
+    fn scope(name: &str, depth: u32, begin_query: u32, end_query: Option<u32>, statistics_query: Option<u32>) -> ScopeRecord
+    {
+        ScopeRecord{ name: name.to_string(), depth, begin_query, end_query, statistics_query }
+    }
+
+    fn statistics_result(first_counter: u64, available: bool) -> [u64; PIPELINE_STATISTICS_COUNT + 1]
+    {
+        let mut result = [0u64; PIPELINE_STATISTICS_COUNT + 1];
+        result[0] = first_counter;
+        result[PIPELINE_STATISTICS_COUNT] = available as u64;
+        result
+    }
+
+    #[test]
+    fn an_open_scope_only_drops_itself()
+    {
+        let scopes = [
+            scope("Frame", 0, 0, Some(3), Some(0)),
+            scope("Shadows", 1, 1, Some(2), None),
+            scope("Unclosed", 0, 4, None, Some(1)),
+        ];
+        //Query 4 Was Written, But Nothing Ended The Scope
+        let timestamps = [[100, 1], [110, 1], [130, 1], [200, 1], [210, 1]];
+        let statistics = [statistics_result(3, true), statistics_result(0, false)];
+
+        let (timings, dropped_scopes) = resolve_scopes(&scopes, &timestamps, &statistics, u64::MAX, 1_000_000.0);
+        assert_eq!(dropped_scopes, 1);
+        assert_eq!(timings.iter().map(|timing| (timing.name.as_str(), timing.gpu_milliseconds)).collect::<Vec<_>>(), vec![("Frame", 100.0), ("Shadows", 20.0)]);
+        assert_eq!(timings[0].statistics.map(|statistics| statistics.input_assembly_vertices), Some(3));
+        assert_eq!(timings[1].statistics, None);
+    }
+
+    #[test]
+    fn unavailable_queries_drop_only_their_scope()
+    {
+        let scopes = [
+            scope("Ready", 0, 0, Some(1), Some(0)),
+            scope("Pending", 0, 2, Some(3), Some(1)),
+        ];
+        let timestamps = [[10, 1], [20, 1], [30, 1], [0, 0]];
+        let statistics = [statistics_result(0, false), statistics_result(9, true)];
+
+        let (timings, dropped_scopes) = resolve_scopes(&scopes, &timestamps, &statistics, u64::MAX, 1_000_000.0);
+        assert_eq!(dropped_scopes, 1);
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].name, "Ready");
+        //Timing Is Still Reported When Only The Statistics Are Missing
+        assert_eq!(timings[0].statistics, None);
+    }
+
+    #[test]
+    fn timestamps_wrap_within_the_valid_bits()
+    {
+        let scopes = [scope("Wrapped", 0, 0, Some(1), None)];
+        let timestamps = [[0xfff0, 1], [0x0010, 1]];
+        let (timings, dropped_scopes) = resolve_scopes(&scopes, &timestamps, &[], 0xffff, 1_000_000.0);
+        assert_eq!(dropped_scopes, 0);
+        assert_eq!(timings[0].gpu_milliseconds, 0x20 as f64);
+    }
+}
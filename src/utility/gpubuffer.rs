@@ -1,65 +1,198 @@
 
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Range;
+
 use ash::vk;
+use bytemuck::Pod;
 use gpu_allocator::vulkan;
 
-//use crate::utility:
+use crate::utility::{gpuimage::GPUImage, format::*, memory_report::*};
+
+//vkCmdUpdateBuffer Is Limited To 65536 Bytes Per Call
+pub const MAX_UPDATE_BUFFER_SIZE: u64 = 65536;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedicatedAllocation
+{
+    Never,
+    //Follows VkMemoryDedicatedRequirements, Dedicated When The Driver Prefers Or Requires It
+    WhenPreferred,
+    Always,
+}
+
+#[derive(Debug, Clone)]
+pub struct GPUBufferDesc<'a>
+{
+    pub name: &'a str,
+    pub size: u64,
+    pub usage: vk::BufferUsageFlags,
+    pub location: gpu_allocator::MemoryLocation,
+    pub dedicated_allocation: DedicatedAllocation,
+    //More Than One Distinct Family Creates The Buffer With CONCURRENT Sharing
+    pub queue_family_indices: &'a [u32],
+    //Raised To At Least The Driver's Requirement, 0 Keeps The Driver's Alignment
+    pub alignment: u64,
+}
+
+impl<'a> GPUBufferDesc<'a>
+{
+    pub fn new(name: &'a str, size: u64, usage: vk::BufferUsageFlags, location: gpu_allocator::MemoryLocation) -> GPUBufferDesc<'a>
+    {
+        GPUBufferDesc{
+            name,
+            size,
+            usage,
+            location,
+            dedicated_allocation: DedicatedAllocation::WhenPreferred,
+            queue_family_indices: &[],
+            alignment: 0,
+        }
+    }
+
+    //Filled Through An Upload, So Also A Transfer Destination
+    pub fn vertex_buffer(name: &'a str, size: u64) -> GPUBufferDesc<'a>
+    {
+        GPUBufferDesc::new(name, size, vk::BufferUsageFlags::VERTEX_BUFFER | vk::BufferUsageFlags::TRANSFER_DST, gpu_allocator::MemoryLocation::GpuOnly)
+    }
+
+    pub fn index_buffer(name: &'a str, size: u64) -> GPUBufferDesc<'a>
+    {
+        GPUBufferDesc::new(name, size, vk::BufferUsageFlags::INDEX_BUFFER | vk::BufferUsageFlags::TRANSFER_DST, gpu_allocator::MemoryLocation::GpuOnly)
+    }
+
+    pub fn storage_buffer(name: &'a str, size: u64) -> GPUBufferDesc<'a>
+    {
+        GPUBufferDesc::new(name, size, vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::TRANSFER_SRC, gpu_allocator::MemoryLocation::GpuOnly)
+    }
+
+    pub fn uniform_buffer(name: &'a str, size: u64) -> GPUBufferDesc<'a>
+    {
+        GPUBufferDesc::new(name, size, vk::BufferUsageFlags::UNIFORM_BUFFER, gpu_allocator::MemoryLocation::CpuToGpu)
+    }
+
+    pub fn staging_buffer(name: &'a str, size: u64) -> GPUBufferDesc<'a>
+    {
+        GPUBufferDesc::new(name, size, vk::BufferUsageFlags::TRANSFER_SRC, gpu_allocator::MemoryLocation::CpuToGpu)
+    }
+
+    pub fn readback_buffer(name: &'a str, size: u64) -> GPUBufferDesc<'a>
+    {
+        GPUBufferDesc::new(name, size, vk::BufferUsageFlags::TRANSFER_DST, gpu_allocator::MemoryLocation::GpuToCpu)
+    }
+}
+
 pub struct GPUBuffer
 {
     pub buffer: vk::Buffer,
     pub allocation: vulkan::Allocation,
+    //Size The Buffer Was Created With, The Allocation Behind It Can Be Larger
+    pub size: u64,
+    pub usage: vk::BufferUsageFlags,
+    //Only Set When usage Includes SHADER_DEVICE_ADDRESS
+    pub device_address: Option<vk::DeviceAddress>,
 }
 
 impl GPUBuffer
 {
     pub fn create_gpu_buffer(
         device:& ash::Device
-        ,allocator: &mut vulkan::Allocator
-        ,buffer_size: u64
-        ,buffer_usage: vk::BufferUsageFlags
-        ,memory_location: gpu_allocator::MemoryLocation) -> GPUBuffer
+        ,allocator: &mut TrackedAllocator
+        ,desc: &GPUBufferDesc) -> GPUBuffer
     {
+        let mut queue_family_indices = desc.queue_family_indices.to_vec();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
+        let sharing_mode = if queue_family_indices.len() > 1
+        {
+            vk::SharingMode::CONCURRENT
+        }
+        else
+        {
+            queue_family_indices.clear();
+            vk::SharingMode::EXCLUSIVE
+        };
+
         let gpu_buffer_create_info = vk::BufferCreateInfo::builder()
-            .size(buffer_size)
-            .usage(buffer_usage)
-            .sharing_mode(vk::SharingMode::EXCLUSIVE)
-            .queue_family_indices(&[]);
+            .size(desc.size)
+            .usage(desc.usage)
+            .sharing_mode(sharing_mode)
+            .queue_family_indices(&queue_family_indices);
 
         let gpu_buffer = unsafe{
             device
                 .create_buffer(&gpu_buffer_create_info, None)
-                .expect("Failed To Create Vertex Buffer!")
+                .unwrap_or_else(|error| panic!("Failed To Create Buffer {}: {}", desc.name, error))
         };
 
-        let allocation_requirement = unsafe{
+        let mut dedicated_requirements = vk::MemoryDedicatedRequirements::default();
+        let mut memory_requirements = vk::MemoryRequirements2::builder()
+            .push_next(&mut dedicated_requirements);
+        let buffer_requirements_info = vk::BufferMemoryRequirementsInfo2::builder()
+            .buffer(gpu_buffer);
+        unsafe{
             device
-                .get_buffer_memory_requirements(gpu_buffer)
+                .get_buffer_memory_requirements2(&buffer_requirements_info, &mut memory_requirements)
+        };
+        let mut allocation_requirement = memory_requirements.memory_requirements;
+        allocation_requirement.alignment = allocation_requirement.alignment.max(desc.alignment);
+
+        let is_dedicated = match desc.dedicated_allocation
+        {
+            DedicatedAllocation::Never => dedicated_requirements.requires_dedicated_allocation == vk::TRUE,
+            DedicatedAllocation::WhenPreferred => dedicated_requirements.prefers_dedicated_allocation == vk::TRUE
+                || dedicated_requirements.requires_dedicated_allocation == vk::TRUE,
+            DedicatedAllocation::Always => true,
+        };
+        let allocation_scheme = if is_dedicated
+        {
+            vulkan::AllocationScheme::DedicatedBuffer(gpu_buffer)
+        }
+        else
+        {
+            vulkan::AllocationScheme::GpuAllocatorManaged
         };
 
         let allocation = allocator.allocate(&vulkan::AllocationCreateDesc{
-            name: "Custom Vertex Buffer",
+            name: desc.name,
             requirements: allocation_requirement,
-            location: memory_location,
+            location: desc.location,
             linear: true,
-            allocation_scheme: vulkan::AllocationScheme::GpuAllocatorManaged,
-        }).expect("Failed To Create Buffer Memory Allocation!");
+            allocation_scheme,
+        }, MemoryCategory::from_buffer_usage(desc.usage, desc.location))
+            .unwrap_or_else(|error| panic!("Failed To Allocate Memory For Buffer {}: {}", desc.name, error));
 
         unsafe{
             device
                 .bind_buffer_memory(gpu_buffer, allocation.memory(), allocation.offset())
-                .expect("Failed To Bind Memory To Vertex Buffer!")
+                .unwrap_or_else(|error| panic!("Failed To Bind Memory To Buffer {}: {}", desc.name, error))
+        };
+
+        let device_address = if desc.usage.contains(vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS)
+        {
+            let address_info = vk::BufferDeviceAddressInfo::builder()
+                .buffer(gpu_buffer);
+            Some(unsafe { device.get_buffer_device_address(&address_info) })
+        }
+        else
+        {
+            None
         };
 
         GPUBuffer
         {
             buffer: gpu_buffer,
             allocation: allocation,
+            size: desc.size,
+            usage: desc.usage,
+            device_address,
         }
     }
 
     pub fn destroy_gpu_buffer(
         gpu_buffer: &mut GPUBuffer
         , device:& ash::Device
-        , allocator: &mut vulkan::Allocator)
+        , allocator: &mut TrackedAllocator)
     {
         unsafe{
             device.destroy_buffer(gpu_buffer.buffer, None);
@@ -70,25 +203,618 @@ impl GPUBuffer
             .expect("Failed To Destroy GPU Buffer!");
     }
 
+    //Copies The Whole Of src_buffer To The Start Of dst_buffer
     pub unsafe fn cmd_copy_buffer(
         device: &ash::Device
         ,command_buffer: vk::CommandBuffer
         ,src_buffer: &GPUBuffer
-        ,dst_buffer: &GPUBuffer 
-    )
+        ,dst_buffer: &GPUBuffer) -> Result<(), GPUBufferError>
     {
         let copy_regions = [vk::BufferCopy {
             src_offset: 0,
             dst_offset: 0,
-            size: src_buffer.allocation.size(),
+            size: src_buffer.size,
         }];
 
+        GPUBuffer::cmd_copy_buffer_regions(device, command_buffer, src_buffer, dst_buffer, &copy_regions)
+    }
+
+    //Every Region Is Checked Against Both Buffers' Creation Sizes Before Anything Is Recorded
+    pub unsafe fn cmd_copy_buffer_regions(
+        device: &ash::Device
+        ,command_buffer: vk::CommandBuffer
+        ,src_buffer: &GPUBuffer
+        ,dst_buffer: &GPUBuffer
+        ,copy_regions: &[vk::BufferCopy]) -> Result<(), GPUBufferError>
+    {
+        for region in copy_regions.iter()
+        {
+            src_buffer.check_byte_range(region.src_offset, region.size)?;
+            dst_buffer.check_byte_range(region.dst_offset, region.size)?;
+            if src_buffer.buffer == dst_buffer.buffer
+                && region.src_offset < region.dst_offset + region.size
+                && region.dst_offset < region.src_offset + region.size
+            {
+                return Err(GPUBufferError::OverlappingCopy{ src_offset: region.src_offset, dst_offset: region.dst_offset, size: region.size });
+            }
+        }
+        if copy_regions.is_empty()
+        {
+            return Ok(());
+        }
+
         device
             .cmd_copy_buffer(
                 command_buffer
                 , src_buffer.buffer
                 , dst_buffer.buffer
-                , &copy_regions);
+                , copy_regions);
+        Ok(())
+    }
+
+    //dst_image Must Already Be In dst_image_layout, TRANSFER_DST_OPTIMAL Or GENERAL
+    pub unsafe fn cmd_copy_buffer_to_image(
+        device: &ash::Device
+        ,command_buffer: vk::CommandBuffer
+        ,src_buffer: &GPUBuffer
+        ,dst_image: &GPUImage
+        ,dst_image_layout: vk::ImageLayout
+        ,copy_regions: &[vk::BufferImageCopy]) -> Result<(), GPUBufferError>
+    {
+        for region in copy_regions.iter()
+        {
+            src_buffer.check_image_region(dst_image, region)?;
+        }
+        if copy_regions.is_empty()
+        {
+            return Ok(());
+        }
+
+        device
+            .cmd_copy_buffer_to_image(
+                command_buffer
+                , src_buffer.buffer
+                , dst_image.image
+                , dst_image_layout
+                , copy_regions);
+        Ok(())
+    }
+
+    //src_image Must Already Be In src_image_layout, TRANSFER_SRC_OPTIMAL Or GENERAL
+    pub unsafe fn cmd_copy_image_to_buffer(
+        device: &ash::Device
+        ,command_buffer: vk::CommandBuffer
+        ,src_image: &GPUImage
+        ,src_image_layout: vk::ImageLayout
+        ,dst_buffer: &GPUBuffer
+        ,copy_regions: &[vk::BufferImageCopy]) -> Result<(), GPUBufferError>
+    {
+        for region in copy_regions.iter()
+        {
+            dst_buffer.check_image_region(src_image, region)?;
+        }
+        if copy_regions.is_empty()
+        {
+            return Ok(());
+        }
+
+        device
+            .cmd_copy_image_to_buffer(
+                command_buffer
+                , src_image.image
+                , src_image_layout
+                , dst_buffer.buffer
+                , copy_regions);
+        Ok(())
+    }
+
+    //Fills size Bytes From offset With The Repeated u32 data, size May Be vk::WHOLE_SIZE
+    pub unsafe fn cmd_fill_buffer(
+        &self
+        ,device: &ash::Device
+        ,command_buffer: vk::CommandBuffer
+        ,offset: u64
+        ,size: u64
+        ,data: u32) -> Result<(), GPUBufferError>
+    {
+        let fill_size = if size == vk::WHOLE_SIZE
+        {
+            //WHOLE_SIZE Rounds Down To A Multiple Of 4
+            self.size.saturating_sub(offset) / 4 * 4
+        }
+        else
+        {
+            size
+        };
+        self.check_byte_range(offset, fill_size)?;
+        if offset % 4 != 0 || fill_size % 4 != 0
+        {
+            return Err(GPUBufferError::Misaligned{ offset, size: fill_size, alignment: 4 });
+        }
+
+        device
+            .cmd_fill_buffer(command_buffer, self.buffer, offset, size, data);
+        Ok(())
+    }
+
+    //Inline Update Recorded Into The Command Buffer, Only Meant For Small Data
+    pub unsafe fn cmd_update_buffer(
+        &self
+        ,device: &ash::Device
+        ,command_buffer: vk::CommandBuffer
+        ,offset: u64
+        ,data: &[u8]) -> Result<(), GPUBufferError>
+    {
+        let size = data.len() as u64;
+        self.check_byte_range(offset, size)?;
+        if offset % 4 != 0 || size % 4 != 0
+        {
+            return Err(GPUBufferError::Misaligned{ offset, size, alignment: 4 });
+        }
+        if size > MAX_UPDATE_BUFFER_SIZE
+        {
+            return Err(GPUBufferError::UpdateTooLarge(size));
+        }
+
+        device
+            .cmd_update_buffer(command_buffer, self.buffer, offset, data);
+        Ok(())
+    }
+
+    fn check_byte_range(&self, offset: u64, size: u64) -> Result<(), GPUBufferError>
+    {
+        if size == 0 || offset.checked_add(size).map_or(true, |end| end > self.size)
+        {
+            return Err(GPUBufferError::RegionOutOfBounds{ offset, size, buffer_size: self.size });
+        }
+        Ok(())
+    }
+
+    //Checks The Image Side Fits The Mip, And The Buffer Side Fits Within This Buffer
+    fn check_image_region(&self, image: &GPUImage, region: &vk::BufferImageCopy) -> Result<(), GPUBufferError>
+    {
+        let footprint = image.copy_region_footprint(region)?;
+        self.check_byte_range(region.buffer_offset, footprint)
+    }
+}
+
+//Bytes A Buffer Image Copy Reads Or Writes Starting At Its buffer_offset
+pub fn image_region_footprint(format: vk::Format, region: &vk::BufferImageCopy) -> Result<u64, GPUBufferError>
+{
+    let extent = region.image_extent;
+    let layer_count = region.image_subresource.layer_count;
+    if extent.width == 0 || extent.height == 0 || extent.depth == 0 || layer_count == 0
+    {
+        return Err(GPUBufferError::EmptyImageRegion{ extent, layer_count });
+    }
+
+    let (block_width, block_height, block_size) = format_block_info(format)
+        .ok_or(GPUBufferError::UnknownTexelSize(format))?;
+    if region.buffer_offset % (block_size as u64).max(4) != 0
+    {
+        return Err(GPUBufferError::Misaligned{ offset: region.buffer_offset, size: 0, alignment: (block_size as u64).max(4) });
+    }
+
+    //Zero Row Length Or Image Height Means Tightly Packed To The Copy Extent, Anything Else Has To Be At Least The Copy Extent
+    let row_texels = if region.buffer_row_length == 0 { extent.width } else { region.buffer_row_length };
+    let image_rows = if region.buffer_image_height == 0 { extent.height } else { region.buffer_image_height };
+    if row_texels < extent.width || image_rows < extent.height
+    {
+        return Err(GPUBufferError::RowPitchTooSmall{ row_length: region.buffer_row_length, image_height: region.buffer_image_height, extent });
+    }
+
+    let row_bytes = blocks_covering(row_texels, block_width) as u64 * block_size as u64;
+    let slice_rows = blocks_covering(image_rows, block_height) as u64;
+    let copy_rows = blocks_covering(extent.height, block_height) as u64;
+    let copy_row_bytes = blocks_covering(extent.width, block_width) as u64 * block_size as u64;
+    let slices = extent.depth as u64 * layer_count as u64;
+
+    //The Last Row Of The Last Slice Only Reaches As Far As The Copied Width
+    slice_rows
+        .checked_mul(row_bytes)
+        .and_then(|slice_bytes| (slices - 1).checked_mul(slice_bytes))
+        .and_then(|bytes| (copy_rows - 1).checked_mul(row_bytes).and_then(|row_offset| bytes.checked_add(row_offset)))
+        .and_then(|bytes| bytes.checked_add(copy_row_bytes))
+        .ok_or(GPUBufferError::FootprintOverflow{ extent, layer_count })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GPUBufferError
+{
+    NotHostVisible,
+    OutOfBounds{ range: Range<usize>, len: usize },
+    RegionOutOfBounds{ offset: u64, size: u64, buffer_size: u64 },
+    OverlappingCopy{ src_offset: u64, dst_offset: u64, size: u64 },
+    ImageRegionOutOfBounds{ mip_level: u32, offset: vk::Offset3D, extent: vk::Extent3D },
+    EmptyImageRegion{ extent: vk::Extent3D, layer_count: u32 },
+    RowPitchTooSmall{ row_length: u32, image_height: u32, extent: vk::Extent3D },
+    FootprintOverflow{ extent: vk::Extent3D, layer_count: u32 },
+    PixelDataSizeMismatch{ expected: u64, actual: u64 },
+    EmptyUpload{ data_len: u64, region_count: usize },
+    Misaligned{ offset: u64, size: u64, alignment: u64 },
+    UpdateTooLarge(u64),
+    UnknownTexelSize(vk::Format),
+}
+
+impl fmt::Display for GPUBufferError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            GPUBufferError::NotHostVisible => write!(f, "Buffer Memory Is Not Host Visible"),
+            GPUBufferError::OutOfBounds{ range, len } => write!(f, "Elements {}..{} Are Out Of Bounds For A Buffer Of {} Elements", range.start, range.end, len),
+            GPUBufferError::RegionOutOfBounds{ offset, size, buffer_size } => write!(f, "Bytes {}..{} Are Out Of Bounds For A Buffer Of {} Bytes", offset, offset.saturating_add(*size), buffer_size),
+            GPUBufferError::OverlappingCopy{ src_offset, dst_offset, size } => write!(f, "Copy Of {} Bytes From {} To {} Overlaps Within The Same Buffer", size, src_offset, dst_offset),
+            GPUBufferError::ImageRegionOutOfBounds{ mip_level, offset, extent } => write!(f, "Image Region {:?} + {:?} Is Out Of Bounds For Mip {}", offset, extent, mip_level),
+            GPUBufferError::EmptyImageRegion{ extent, layer_count } => write!(f, "Image Region {:?} With {} Layers Copies Nothing", extent, layer_count),
+            GPUBufferError::RowPitchTooSmall{ row_length, image_height, extent } => write!(f, "Buffer Row Length {} And Image Height {} Are Smaller Than The Copied Extent {:?}", row_length, image_height, extent),
+            GPUBufferError::FootprintOverflow{ extent, layer_count } => write!(f, "Image Region {:?} With {} Layers Spans More Than u64::MAX Bytes", extent, layer_count),
+            GPUBufferError::PixelDataSizeMismatch{ expected, actual } => write!(f, "Expected {} Bytes Of Pixel Data But Got {}", expected, actual),
+            GPUBufferError::EmptyUpload{ data_len, region_count } => write!(f, "Upload Of {} Bytes With {} Copy Regions Copies Nothing", data_len, region_count),
+            GPUBufferError::Misaligned{ offset, size, alignment } => write!(f, "Offset {} And Size {} Must Be Multiples Of {}", offset, size, alignment),
+            GPUBufferError::UpdateTooLarge(size) => write!(f, "Buffer Update Of {} Bytes Exceeds The {} Byte Limit", size, MAX_UPDATE_BUFFER_SIZE),
+            GPUBufferError::UnknownTexelSize(format) => write!(f, "Texel Size Of {:?} Is Unknown", format),
+        }
+    }
+}
+
+impl std::error::Error for GPUBufferError {}
+
+//A GPUBuffer That Knows It Holds len Elements Of T, Every Access Is Bounds Checked In Elements
+pub struct TypedGPUBuffer<T: Pod>
+{
+    pub gpu_buffer: GPUBuffer,
+    len: usize,
+    _element: PhantomData<T>,
+}
+
+impl<T: Pod> TypedGPUBuffer<T>
+{
+    //desc.size Is Ignored, The Buffer Is Sized For len Elements
+    pub fn new(
+        device: &ash::Device
+        , allocator: &mut TrackedAllocator
+        , len: usize
+        , desc: &GPUBufferDesc) -> TypedGPUBuffer<T>
+    {
+        //Zero Sized Buffers Are Invalid In Vulkan, Keep At Least One Element Worth Of Memory
+        let buffer_desc = GPUBufferDesc{
+            size: (len.max(1) * std::mem::size_of::<T>()) as u64,
+            ..desc.clone()
+        };
+        TypedGPUBuffer{
+            gpu_buffer: GPUBuffer::create_gpu_buffer(device, allocator, &buffer_desc),
+            len,
+            _element: PhantomData,
+        }
+    }
+
+    //desc.location Must Be Host Visible, Use The Upload Manager For GpuOnly Buffers
+    pub fn from_slice(
+        device: &ash::Device
+        , allocator: &mut TrackedAllocator
+        , data: &[T]
+        , desc: &GPUBufferDesc) -> Result<TypedGPUBuffer<T>, GPUBufferError>
+    {
+        let mut typed_buffer = TypedGPUBuffer::new(device, allocator, data.len(), desc);
+        if let Err(error) = typed_buffer.write(data)
+        {
+            typed_buffer.destroy_typed_buffer(device, allocator);
+            return Err(error);
+        }
+        Ok(typed_buffer)
+    }
+
+    pub fn device_address(&self) -> Option<vk::DeviceAddress>
+    {
+        self.gpu_buffer.device_address
+    }
+
+    pub fn destroy_typed_buffer(&mut self, device: &ash::Device, allocator: &mut TrackedAllocator)
+    {
+        GPUBuffer::destroy_gpu_buffer(&mut self.gpu_buffer, device, allocator);
+        self.len = 0;
+    }
+
+    pub fn buffer(&self) -> vk::Buffer
+    {
+        self.gpu_buffer.buffer
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.len == 0
+    }
+
+    pub fn size_in_bytes(&self) -> u64
+    {
+        (self.len * std::mem::size_of::<T>()) as u64
+    }
+
+    pub fn is_host_visible(&self) -> bool
+    {
+        self.gpu_buffer.allocation.mapped_ptr().is_some()
+    }
+
+    pub fn write(&mut self, data: &[T]) -> Result<(), GPUBufferError>
+    {
+        self.write_at(0, data)
+    }
+
+    pub fn write_at(&mut self, first_element: usize, data: &[T]) -> Result<(), GPUBufferError>
+    {
+        let byte_range = self.byte_range(first_element..first_element + data.len())?;
+        let mapped_memory = self.gpu_buffer.allocation
+            .mapped_slice_mut()
+            .ok_or(GPUBufferError::NotHostVisible)?;
+        mapped_memory[byte_range].copy_from_slice(bytemuck::cast_slice(data));
+        Ok(())
+    }
+
+    pub fn read(&self) -> Result<Vec<T>, GPUBufferError>
+    {
+        self.read_range(0..self.len)
+    }
+
+    //Copies Out Instead Of Casting In Place, Mapped Memory Makes No Promise About T's Alignment
+    pub fn read_range(&self, range: Range<usize>) -> Result<Vec<T>, GPUBufferError>
+    {
+        let byte_range = self.byte_range(range.clone())?;
+        let mapped_memory = self.gpu_buffer.allocation
+            .mapped_slice()
+            .ok_or(GPUBufferError::NotHostVisible)?;
+
+        let mut elements = vec![T::zeroed(); range.len()];
+        bytemuck::cast_slice_mut::<T, u8>(&mut elements).copy_from_slice(&mapped_memory[byte_range]);
+        Ok(elements)
+    }
+
+    //Makes Host Writes To range Visible To The Device, Only Needed For Non Coherent Memory
+    pub fn flush(&self, device: &ash::Device, device_limits: &vk::PhysicalDeviceLimits, range: Range<usize>) -> Result<(), GPUBufferError>
+    {
+        let memory_range = match self.mapped_memory_range(device_limits, range)?
+        {
+            Some(memory_range) => memory_range,
+            None => return Ok(()),
+        };
+        unsafe
+        {
+            device
+                .flush_mapped_memory_ranges(&[memory_range])
+                .expect("Failed To Flush Mapped Memory Range");
+        }
+        Ok(())
+    }
+
+    //Makes Device Writes To range Visible To The Host, Only Needed For Non Coherent Memory
+    pub fn invalidate(&self, device: &ash::Device, device_limits: &vk::PhysicalDeviceLimits, range: Range<usize>) -> Result<(), GPUBufferError>
+    {
+        let memory_range = match self.mapped_memory_range(device_limits, range)?
+        {
+            Some(memory_range) => memory_range,
+            None => return Ok(()),
+        };
+        unsafe
+        {
+            device
+                .invalidate_mapped_memory_ranges(&[memory_range])
+                .expect("Failed To Invalidate Mapped Memory Range");
+        }
+        Ok(())
+    }
+
+    //Copies src_range Of This Buffer To dst Starting At dst_first_element, Both Ends Are Bounds Checked
+    pub unsafe fn cmd_copy_to(
+        &self
+        , device: &ash::Device
+        , command_buffer: vk::CommandBuffer
+        , dst: &TypedGPUBuffer<T>
+        , src_range: Range<usize>
+        , dst_first_element: usize) -> Result<(), GPUBufferError>
+    {
+        let src_bytes = self.byte_range(src_range.clone())?;
+        let dst_bytes = dst.byte_range(dst_first_element..dst_first_element + src_range.len())?;
+        if src_bytes.is_empty()
+        {
+            return Ok(());
+        }
+
+        let copy_regions = [vk::BufferCopy{
+            src_offset: src_bytes.start as u64,
+            dst_offset: dst_bytes.start as u64,
+            size: src_bytes.len() as u64,
+        }];
+
+        GPUBuffer::cmd_copy_buffer_regions(device, command_buffer, &self.gpu_buffer, &dst.gpu_buffer, &copy_regions)
+    }
+
+    fn byte_range(&self, range: Range<usize>) -> Result<Range<usize>, GPUBufferError>
+    {
+        element_byte_range(range, self.len, std::mem::size_of::<T>())
+    }
+
+    //None For An Empty range, Vulkan Does Not Accept Zero Sized Mapped Memory Ranges
+    fn mapped_memory_range(&self, device_limits: &vk::PhysicalDeviceLimits, range: Range<usize>) -> Result<Option<vk::MappedMemoryRange>, GPUBufferError>
+    {
+        if !self.is_host_visible()
+        {
+            return Err(GPUBufferError::NotHostVisible);
+        }
+        let byte_range = self.byte_range(range)?;
+
+        let allocation = &self.gpu_buffer.allocation;
+        let atom_range = non_coherent_atom_range(
+            allocation.offset()
+            , allocation.size()
+            , byte_range.start as u64..byte_range.end as u64
+            , device_limits.non_coherent_atom_size);
+
+        Ok(atom_range.map(|(offset, size)| vk::MappedMemoryRange::builder()
+            .memory(unsafe { allocation.memory() })
+            .offset(offset)
+            .size(size)
+            .build()))
+    }
+}
+
+fn element_byte_range(range: Range<usize>, len: usize, element_size: usize) -> Result<Range<usize>, GPUBufferError>
+{
+    if range.start > range.end || range.end > len
+    {
+        return Err(GPUBufferError::OutOfBounds{ range, len });
+    }
+    Ok(range.start * element_size..range.end * element_size)
+}
+
+//Offset And Size Of byte_range Within The Memory Object, Widened Out To nonCoherentAtomSize
+//A Rounded End Past The Allocation Becomes vk::WHOLE_SIZE, Since Only The End Of The Memory Object May Be Unaligned
+fn non_coherent_atom_range(
+    allocation_offset: u64
+    , allocation_size: u64
+    , byte_range: Range<u64>
+    , non_coherent_atom_size: u64) -> Option<(u64, u64)>
+{
+    if byte_range.is_empty()
+    {
+        return None;
+    }
+
+    let atom_size = non_coherent_atom_size.max(1);
+    let start = allocation_offset + byte_range.start;
+    let end = allocation_offset + byte_range.end;
+    let aligned_start = start / atom_size * atom_size;
+    let aligned_end = end.div_ceil(atom_size) * atom_size;
+    let size = if aligned_end > allocation_offset + allocation_size
+    {
+        vk::WHOLE_SIZE
+    }
+    else
+    {
+        aligned_end - aligned_start
+    };
+
+    Some((aligned_start, size))
+}
 
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn region(width: u32, height: u32, depth: u32, layer_count: u32) -> vk::BufferImageCopy
+    {
+        vk::BufferImageCopy{
+            buffer_offset: 0,
+            buffer_row_length: 0,
+            buffer_image_height: 0,
+            image_subresource: vk::ImageSubresourceLayers{
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count,
+            },
+            image_offset: vk::Offset3D{ x: 0, y: 0, z: 0 },
+            image_extent: vk::Extent3D{ width, height, depth },
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn tightly_packed_footprint()
+    {
+        assert_eq!(image_region_footprint(vk::Format::R8G8B8A8_UNORM, &region(4, 4, 1, 2)), Ok(4 * 4 * 4 * 2));
+    }
+
+    #[test]
+    fn footprint_stops_at_the_copied_width()
+    {
+        let padded = vk::BufferImageCopy{ buffer_row_length: 8, ..region(4, 2, 1, 1) };
+        assert_eq!(image_region_footprint(vk::Format::R8G8B8A8_UNORM, &padded), Ok(8 * 4 + 4 * 4));
+    }
+
+    #[test]
+    fn zero_sized_regions_are_rejected()
+    {
+        for empty_region in [region(0, 4, 1, 1), region(4, 0, 1, 1), region(4, 4, 0, 1), region(4, 4, 1, 0)]
+        {
+            assert!(matches!(
+                image_region_footprint(vk::Format::R8G8B8A8_UNORM, &empty_region),
+                Err(GPUBufferError::EmptyImageRegion{ .. })));
+        }
+    }
+
+    #[test]
+    fn compressed_footprints_round_up_to_whole_blocks()
+    {
+        //5x5 Covers 2x2 BC1 Blocks Of 8 Bytes, The Second Block Row Starts One Padded Row In
+        assert_eq!(image_region_footprint(vk::Format::BC1_RGB_UNORM_BLOCK, &region(5, 5, 1, 1)), Ok(2 * 2 * 8));
+        assert_eq!(image_region_footprint(vk::Format::BC7_UNORM_BLOCK, &region(1, 1, 1, 1)), Ok(16));
+        let padded_rows = vk::BufferImageCopy{ buffer_row_length: 12, buffer_image_height: 8, ..region(4, 4, 1, 2) };
+        assert_eq!(image_region_footprint(vk::Format::BC1_RGB_UNORM_BLOCK, &padded_rows), Ok(2 * 3 * 8 + 8));
+    }
+
+    #[test]
+    fn row_pitch_smaller_than_the_copy_is_rejected()
+    {
+        let short_rows = vk::BufferImageCopy{ buffer_row_length: 3, ..region(4, 4, 1, 1) };
+        let short_slices = vk::BufferImageCopy{ buffer_image_height: 2, ..region(4, 4, 1, 1) };
+        for bad_region in [short_rows, short_slices]
+        {
+            assert!(matches!(
+                image_region_footprint(vk::Format::R8G8B8A8_UNORM, &bad_region),
+                Err(GPUBufferError::RowPitchTooSmall{ .. })));
+        }
+        let exact = vk::BufferImageCopy{ buffer_row_length: 4, buffer_image_height: 4, ..region(4, 4, 1, 1) };
+        assert_eq!(image_region_footprint(vk::Format::R8G8B8A8_UNORM, &exact), Ok(4 * 4 * 4));
+    }
+
+    #[test]
+    fn huge_regions_do_not_overflow()
+    {
+        let widest = region(u32::MAX, 1, 1, 1);
+        assert_eq!(image_region_footprint(vk::Format::BC7_UNORM_BLOCK, &widest), Ok((u32::MAX as u64).div_ceil(4) * 16));
+        assert!(matches!(
+            image_region_footprint(vk::Format::R32G32B32A32_SFLOAT, &region(u32::MAX, u32::MAX, u32::MAX, u32::MAX)),
+            Err(GPUBufferError::FootprintOverflow{ .. })));
+    }
+
+    #[test]
+    fn element_ranges_are_bounds_checked()
+    {
+        assert_eq!(element_byte_range(2..5, 8, 16), Ok(32..80));
+        assert_eq!(element_byte_range(8..8, 8, 16), Ok(128..128));
+        assert!(matches!(element_byte_range(4..9, 8, 16), Err(GPUBufferError::OutOfBounds{ len: 8, .. })));
+        #[allow(clippy::reversed_empty_ranges)]
+        let backwards = element_byte_range(5..4, 8, 16);
+        assert!(matches!(backwards, Err(GPUBufferError::OutOfBounds{ .. })));
+    }
+
+    #[test]
+    fn mapped_ranges_widen_to_whole_atoms()
+    {
+        //Unaligned Start Inside An Allocation That Is Itself Not Atom Aligned
+        assert_eq!(non_coherent_atom_range(1000, 4096, 70..100, 64), Some((1024, 128)));
+        assert_eq!(non_coherent_atom_range(1024, 4096, 0..64, 64), Some((1024, 64)));
+        //A Zero Atom Size Acts Like 1
+        assert_eq!(non_coherent_atom_range(1000, 4096, 3..7, 0), Some((1003, 4)));
+    }
+
+    #[test]
+    fn mapped_ranges_past_the_allocation_run_to_the_end_of_memory()
+    {
+        assert_eq!(non_coherent_atom_range(1000, 100, 90..100, 64), Some((1088, vk::WHOLE_SIZE)));
+        assert_eq!(non_coherent_atom_range(0, 128, 100..128, 64), Some((64, 64)));
+    }
+
+    #[test]
+    fn empty_mapped_ranges_are_skipped()
+    {
+        assert_eq!(non_coherent_atom_range(1000, 4096, 70..70, 64), None);
+        assert_eq!(non_coherent_atom_range(0, 4096, 0..0, 64), None);
+    }
+}
@@ -0,0 +1,718 @@
+use ash::vk;
+use gpu_allocator::vulkan;
+
+use crate::utility::{context, commandbuffers::OneTimeSubmitCommandGroup, gpubuffer::{self, GPUBuffer, GPUBufferDesc, GPUBufferError}, memory_report::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GPUImageDimension
+{
+    Texture2D,
+    Texture2DArray,
+    Texture3D,
+    //Array Layers Must Be A Multiple Of 6, One Face Per Layer
+    Cube,
+    CubeArray,
+}
+
+impl GPUImageDimension
+{
+    pub fn image_type(&self) -> vk::ImageType
+    {
+        match self
+        {
+            GPUImageDimension::Texture3D => vk::ImageType::TYPE_3D,
+            _ => vk::ImageType::TYPE_2D,
+        }
+    }
+
+    pub fn view_type(&self) -> vk::ImageViewType
+    {
+        match self
+        {
+            GPUImageDimension::Texture2D => vk::ImageViewType::TYPE_2D,
+            GPUImageDimension::Texture2DArray => vk::ImageViewType::TYPE_2D_ARRAY,
+            GPUImageDimension::Texture3D => vk::ImageViewType::TYPE_3D,
+            GPUImageDimension::Cube => vk::ImageViewType::CUBE,
+            GPUImageDimension::CubeArray => vk::ImageViewType::CUBE_ARRAY,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GPUImageDesc<'a>
+{
+    pub name: &'a str,
+    pub dimension: GPUImageDimension,
+    pub format: vk::Format,
+    pub extent: vk::Extent3D,
+    pub mip_levels: u32,
+    pub array_layers: u32,
+    pub samples: vk::SampleCountFlags,
+    pub usage: vk::ImageUsageFlags,
+}
+
+impl<'a> GPUImageDesc<'a>
+{
+    pub fn texture_2d(name: &'a str, format: vk::Format, width: u32, height: u32, mip_levels: u32, usage: vk::ImageUsageFlags) -> GPUImageDesc<'a>
+    {
+        GPUImageDesc{
+            name,
+            dimension: GPUImageDimension::Texture2D,
+            format,
+            extent: vk::Extent3D{ width, height, depth: 1 },
+            mip_levels,
+            array_layers: 1,
+            samples: vk::SampleCountFlags::TYPE_1,
+            usage,
+        }
+    }
+
+    pub fn texture_2d_array(name: &'a str, format: vk::Format, width: u32, height: u32, mip_levels: u32, array_layers: u32, usage: vk::ImageUsageFlags) -> GPUImageDesc<'a>
+    {
+        GPUImageDesc{
+            dimension: GPUImageDimension::Texture2DArray,
+            array_layers,
+            ..GPUImageDesc::texture_2d(name, format, width, height, mip_levels, usage)
+        }
+    }
+
+    pub fn texture_3d(name: &'a str, format: vk::Format, width: u32, height: u32, depth: u32, mip_levels: u32, usage: vk::ImageUsageFlags) -> GPUImageDesc<'a>
+    {
+        GPUImageDesc{
+            dimension: GPUImageDimension::Texture3D,
+            extent: vk::Extent3D{ width, height, depth },
+            ..GPUImageDesc::texture_2d(name, format, width, height, mip_levels, usage)
+        }
+    }
+
+    pub fn cube(name: &'a str, format: vk::Format, size: u32, mip_levels: u32, usage: vk::ImageUsageFlags) -> GPUImageDesc<'a>
+    {
+        GPUImageDesc{
+            dimension: GPUImageDimension::Cube,
+            array_layers: 6,
+            ..GPUImageDesc::texture_2d(name, format, size, size, mip_levels, usage)
+        }
+    }
+
+    pub fn cube_array(name: &'a str, format: vk::Format, size: u32, mip_levels: u32, cube_count: u32, usage: vk::ImageUsageFlags) -> GPUImageDesc<'a>
+    {
+        GPUImageDesc{
+            dimension: GPUImageDimension::CubeArray,
+            array_layers: 6 * cube_count,
+            ..GPUImageDesc::texture_2d(name, format, size, size, mip_levels, usage)
+        }
+    }
+
+    pub fn render_target(name: &'a str, format: vk::Format, extent: vk::Extent2D, samples: vk::SampleCountFlags, usage: vk::ImageUsageFlags) -> GPUImageDesc<'a>
+    {
+        GPUImageDesc{
+            samples,
+            ..GPUImageDesc::texture_2d(name, format, extent.width, extent.height, 1, usage)
+        }
+    }
+}
+
+pub struct GPUImage
+{
+    pub image: vk::Image,
+    pub image_view: vk::ImageView,
+    pub allocation: vulkan::Allocation,
+    pub dimension: GPUImageDimension,
+    pub format: vk::Format,
+    pub extent: vk::Extent3D,
+    pub mip_levels: u32,
+    pub array_layers: u32,
+    pub samples: vk::SampleCountFlags,
+    pub usage: vk::ImageUsageFlags,
+    //Layout Of The Whole Image After The Last Transition Recorded Through This Object
+    pub layout: vk::ImageLayout,
+}
+
+impl GPUImage
+{
+    pub fn create_gpu_image(
+        device: &ash::Device
+        ,allocator: &mut TrackedAllocator
+        ,desc: &GPUImageDesc) -> GPUImage
+    {
+        let create_flags = match desc.dimension
+        {
+            GPUImageDimension::Cube | GPUImageDimension::CubeArray => vk::ImageCreateFlags::CUBE_COMPATIBLE,
+            _ => vk::ImageCreateFlags::empty(),
+        };
+
+        let image_create_info = vk::ImageCreateInfo::builder()
+            .flags(create_flags)
+            .image_type(desc.dimension.image_type())
+            .format(desc.format)
+            .extent(desc.extent)
+            .mip_levels(desc.mip_levels)
+            .array_layers(desc.array_layers)
+            .samples(desc.samples)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(desc.usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+
+        let gpu_image = unsafe{
+            device
+                .create_image(&image_create_info, None)
+                .expect("Failed To Create Image!")
+        };
+
+        let allocation_requirement = unsafe{
+            device
+                .get_image_memory_requirements(gpu_image)
+        };
+
+        let allocation = allocator.allocate(&vulkan::AllocationCreateDesc{
+            name: desc.name,
+            requirements: allocation_requirement,
+            location: gpu_allocator::MemoryLocation::GpuOnly,
+            linear: false,
+            allocation_scheme: vulkan::AllocationScheme::GpuAllocatorManaged,
+        }, MemoryCategory::from_image_usage(desc.usage))
+            .expect("Failed To Create Image Memory Allocation!");
+
+        unsafe{
+            device
+                .bind_image_memory(gpu_image, allocation.memory(), allocation.offset())
+                .expect("Failed To Bind Memory To Image!")
+        };
+
+        let mut result = GPUImage{
+            image: gpu_image,
+            image_view: vk::ImageView::null(),
+            allocation,
+            dimension: desc.dimension,
+            format: desc.format,
+            extent: desc.extent,
+            mip_levels: desc.mip_levels,
+            array_layers: desc.array_layers,
+            samples: desc.samples,
+            usage: desc.usage,
+            layout: vk::ImageLayout::UNDEFINED,
+        };
+
+        result.image_view = result.create_view(device, desc.dimension.view_type(), result.full_subresource_range());
+
+        result
+    }
+
+    pub fn destroy_gpu_image(
+        gpu_image: &mut GPUImage
+        , device:& ash::Device
+        , allocator: &mut TrackedAllocator)
+    {
+        unsafe{
+            device.destroy_image_view(gpu_image.image_view, None);
+            device.destroy_image(gpu_image.image, None);
+        }
+        gpu_image.image_view = vk::ImageView::null();
+        gpu_image.image = vk::Image::null();
+        let allocation = std::mem::take(&mut gpu_image.allocation);
+        allocator
+            .free(allocation)
+            .expect("Failed To Destroy GPU Image!");
+    }
+
+    pub fn aspect_mask(&self) -> vk::ImageAspectFlags
+    {
+        format_aspect_mask(self.format)
+    }
+
+    pub fn full_subresource_range(&self) -> vk::ImageSubresourceRange
+    {
+        vk::ImageSubresourceRange{
+            aspect_mask: self.aspect_mask(),
+            base_mip_level: 0,
+            level_count: self.mip_levels,
+            base_array_layer: 0,
+            layer_count: self.array_layers,
+        }
+    }
+
+    //Extra Views (Single Mip, Single Layer, Cube Face...) Are Owned By The Caller
+    pub fn create_view(&self, device: &ash::Device, view_type: vk::ImageViewType, subresource_range: vk::ImageSubresourceRange) -> vk::ImageView
+    {
+        context::create_image_view(device, self.image, self.format, view_type, subresource_range)
+    }
+
+    pub fn mip_extent(&self, mip_level: u32) -> vk::Extent3D
+    {
+        mip_level_extent(self.extent, mip_level)
+    }
+
+    //Checks A Buffer Image Copy Fits This Image And Returns The Bytes It Covers From Its buffer_offset
+    pub fn copy_region_footprint(&self, region: &vk::BufferImageCopy) -> Result<u64, GPUBufferError>
+    {
+        let subresource = &region.image_subresource;
+        let offset = region.image_offset;
+        //Mip Is Checked First, mip_extent Shifts By It And Overflows From 32 Up
+        let fits_image = subresource.mip_level < self.mip_levels
+            && subresource.base_array_layer.checked_add(subresource.layer_count).is_some_and(|layer_end| layer_end <= self.array_layers)
+            && offset.x >= 0 && offset.y >= 0 && offset.z >= 0
+            && {
+                let mip_extent = self.mip_extent(subresource.mip_level);
+                fits_mip_axis(offset.x as u32, region.image_extent.width, mip_extent.width)
+                    && fits_mip_axis(offset.y as u32, region.image_extent.height, mip_extent.height)
+                    && fits_mip_axis(offset.z as u32, region.image_extent.depth, mip_extent.depth)
+            };
+        if !fits_image
+        {
+            return Err(GPUBufferError::ImageRegionOutOfBounds{ mip_level: subresource.mip_level, offset: region.image_offset, extent: region.image_extent });
+        }
+
+        gpubuffer::image_region_footprint(self.format, region)
+    }
+
+    //Every Region Has To Fit This Image And Read Only From The data_len Bytes Given For It
+    pub fn check_upload_regions(&self, data_len: u64, copy_regions: &[vk::BufferImageCopy]) -> Result<(), GPUBufferError>
+    {
+        if data_len == 0 || copy_regions.is_empty()
+        {
+            return Err(GPUBufferError::EmptyUpload{ data_len, region_count: copy_regions.len() });
+        }
+        for region in copy_regions
+        {
+            let footprint = self.copy_region_footprint(region)?;
+            if region.buffer_offset.checked_add(footprint).is_none_or(|end| end > data_len)
+            {
+                return Err(GPUBufferError::RegionOutOfBounds{ offset: region.buffer_offset, size: footprint, buffer_size: data_len });
+            }
+        }
+        Ok(())
+    }
+
+    //Mip 0 Of Every Layer, Tightly Packed
+    fn full_upload_region(&self) -> vk::BufferImageCopy
+    {
+        vk::BufferImageCopy{
+            buffer_offset: 0,
+            buffer_row_length: 0,
+            buffer_image_height: 0,
+            image_subresource: vk::ImageSubresourceLayers{
+                aspect_mask: self.aspect_mask(),
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: self.array_layers,
+            },
+            image_offset: vk::Offset3D{ x: 0, y: 0, z: 0 },
+            image_extent: self.extent,
+        }
+    }
+
+    //pixel_data Must Hold Exactly The Bytes The Full Mip 0 Region Covers
+    fn check_full_upload(&self, pixel_data: &[u8]) -> Result<vk::BufferImageCopy, GPUBufferError>
+    {
+        let region = self.full_upload_region();
+        let expected = self.copy_region_footprint(&region)?;
+        if pixel_data.len() as u64 != expected
+        {
+            return Err(GPUBufferError::PixelDataSizeMismatch{ expected, actual: pixel_data.len() as u64 });
+        }
+        Ok(region)
+    }
+
+    pub unsafe fn cmd_transition_layout(
+        &mut self
+        ,device: &ash::Device
+        ,command_buffer: vk::CommandBuffer
+        ,new_layout: vk::ImageLayout)
+    {
+        cmd_transition_image_layout(
+            device
+            , command_buffer
+            , self.image
+            , self.full_subresource_range()
+            , self.layout
+            , new_layout);
+        self.layout = new_layout;
+    }
+
+    //Uploads Tightly Packed Pixels Of Mip 0 For Every Layer (Or Every Depth Slice)
+    pub fn upload_pixel_data(
+        &mut self
+        ,device: &ash::Device
+        ,allocator: &mut TrackedAllocator
+        ,command_group: &mut OneTimeSubmitCommandGroup
+        ,queue: vk::Queue
+        ,pixel_data: &[u8]
+        ,final_layout: vk::ImageLayout) -> Result<(), GPUBufferError>
+    {
+        let copy_regions = [self.check_full_upload(pixel_data)?];
+
+        self.upload_pixel_data_regions(device, allocator, command_group, queue, pixel_data, &copy_regions, final_layout)
+    }
+
+    //Buffer Offsets In The Regions Are Relative To The Start Of pixel_data
+    pub fn upload_pixel_data_regions(
+        &mut self
+        ,device: &ash::Device
+        ,allocator: &mut TrackedAllocator
+        ,command_group: &mut OneTimeSubmitCommandGroup
+        ,queue: vk::Queue
+        ,pixel_data: &[u8]
+        ,copy_regions: &[vk::BufferImageCopy]
+        ,final_layout: vk::ImageLayout) -> Result<(), GPUBufferError>
+    {
+        self.check_upload_regions(pixel_data.len() as u64, copy_regions)?;
+
+        self.upload_through_staging(device, allocator, command_group, queue, pixel_data
+            , |gpu_image, device, command_buffer, staging_buffer|
+            unsafe
+            {
+                device
+                    .cmd_copy_buffer_to_image(
+                        command_buffer
+                        , staging_buffer
+                        , gpu_image.image
+                        , vk::ImageLayout::TRANSFER_DST_OPTIMAL
+                        , copy_regions);
+
+                gpu_image.cmd_transition_layout(device, command_buffer, final_layout);
+            });
+        Ok(())
+    }
+
+    //Uploads Mip 0 Like upload_pixel_data, Then Fills The Remaining Mips With Linear Blits
+    //Caller Must Check The Format Supports SAMPLED_IMAGE_FILTER_LINEAR And Create The Image With TRANSFER_SRC Usage
+    pub fn upload_pixel_data_and_generate_mips(
+        &mut self
+        ,device: &ash::Device
+        ,allocator: &mut TrackedAllocator
+        ,command_group: &mut OneTimeSubmitCommandGroup
+        ,queue: vk::Queue
+        ,pixel_data: &[u8]
+        ,final_layout: vk::ImageLayout) -> Result<(), GPUBufferError>
+    {
+        let copy_regions = [self.check_full_upload(pixel_data)?];
+
+        self.upload_through_staging(device, allocator, command_group, queue, pixel_data
+            , |gpu_image, device, command_buffer, staging_buffer|
+            unsafe
+            {
+                device
+                    .cmd_copy_buffer_to_image(
+                        command_buffer
+                        , staging_buffer
+                        , gpu_image.image
+                        , vk::ImageLayout::TRANSFER_DST_OPTIMAL
+                        , &copy_regions);
+
+                gpu_image.cmd_generate_mips(device, command_buffer, final_layout);
+            });
+        Ok(())
+    }
+
+    fn upload_through_staging<F>(
+        &mut self
+        ,device: &ash::Device
+        ,allocator: &mut TrackedAllocator
+        ,command_group: &mut OneTimeSubmitCommandGroup
+        ,queue: vk::Queue
+        ,pixel_data: &[u8]
+        ,record_copy: F)
+        where F: FnOnce(&mut GPUImage, &ash::Device, vk::CommandBuffer, vk::Buffer)
+    {
+        let mut staging_buffer = GPUBuffer::create_gpu_buffer(
+            device
+            , allocator
+            , &GPUBufferDesc::staging_buffer("Image Upload Staging Buffer", pixel_data.len() as u64));
+
+        staging_buffer.allocation
+            .mapped_slice_mut()
+            .expect("Failed To Get Mapped Memory")[..pixel_data.len()]
+            .copy_from_slice(pixel_data);
+
+        let command_buffer = command_group.allocate_command_buffers(device, 1)[0];
+
+        let command_buffer_begin_info = vk::CommandBufferBeginInfo::builder()
+            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+
+        unsafe
+        {
+            device
+                .begin_command_buffer(command_buffer, &command_buffer_begin_info)
+                .expect("Begin Upload Commandbuffer Failed!");
+
+            //Previous Contents Are Discarded
+            self.layout = vk::ImageLayout::UNDEFINED;
+            self.cmd_transition_layout(device, command_buffer, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+        }
+
+        record_copy(self, device, command_buffer, staging_buffer.buffer);
+
+        unsafe
+        {
+            device
+                .end_command_buffer(command_buffer)
+                .expect("Failed To End Upload Command Buffer");
+        }
+
+        command_group.submit_and_wait(device, queue);
+
+        GPUBuffer::destroy_gpu_buffer(&mut staging_buffer, device, allocator);
+    }
+
+    //Expects Every Mip In TRANSFER_DST_OPTIMAL With Mip 0 Filled, Leaves The Whole Image In final_layout
+    pub unsafe fn cmd_generate_mips(
+        &mut self
+        ,device: &ash::Device
+        ,command_buffer: vk::CommandBuffer
+        ,final_layout: vk::ImageLayout)
+    {
+        let aspect_mask = self.aspect_mask();
+        let mip_range = |mip_level: u32| vk::ImageSubresourceRange{
+            aspect_mask,
+            base_mip_level: mip_level,
+            level_count: 1,
+            base_array_layer: 0,
+            layer_count: self.array_layers,
+        };
+        let mip_offset = |extent: vk::Extent3D| vk::Offset3D{
+            x: extent.width as i32,
+            y: extent.height as i32,
+            z: extent.depth as i32,
+        };
+
+        for mip_level in 1..self.mip_levels
+        {
+            //Previous Mip Becomes The Blit Source
+            cmd_transition_image_layout(
+                device
+                , command_buffer
+                , self.image
+                , mip_range(mip_level - 1)
+                , vk::ImageLayout::TRANSFER_DST_OPTIMAL
+                , vk::ImageLayout::TRANSFER_SRC_OPTIMAL);
+
+            let blit_regions = [vk::ImageBlit{
+                src_subresource: vk::ImageSubresourceLayers{
+                    aspect_mask,
+                    mip_level: mip_level - 1,
+                    base_array_layer: 0,
+                    layer_count: self.array_layers,
+                },
+                src_offsets: [vk::Offset3D::default(), mip_offset(self.mip_extent(mip_level - 1))],
+                dst_subresource: vk::ImageSubresourceLayers{
+                    aspect_mask,
+                    mip_level,
+                    base_array_layer: 0,
+                    layer_count: self.array_layers,
+                },
+                dst_offsets: [vk::Offset3D::default(), mip_offset(self.mip_extent(mip_level))],
+            }];
+
+            device
+                .cmd_blit_image(
+                    command_buffer
+                    , self.image
+                    , vk::ImageLayout::TRANSFER_SRC_OPTIMAL
+                    , self.image
+                    , vk::ImageLayout::TRANSFER_DST_OPTIMAL
+                    , &blit_regions
+                    , vk::Filter::LINEAR);
+
+            cmd_transition_image_layout(
+                device
+                , command_buffer
+                , self.image
+                , mip_range(mip_level - 1)
+                , vk::ImageLayout::TRANSFER_SRC_OPTIMAL
+                , final_layout);
+        }
+
+        //Last Mip Was Only Ever Written
+        cmd_transition_image_layout(
+            device
+            , command_buffer
+            , self.image
+            , mip_range(self.mip_levels - 1)
+            , vk::ImageLayout::TRANSFER_DST_OPTIMAL
+            , final_layout);
+
+        self.layout = final_layout;
+    }
+}
+
+pub fn format_aspect_mask(format: vk::Format) -> vk::ImageAspectFlags
+{
+    match format
+    {
+        vk::Format::D16_UNORM | vk::Format::D32_SFLOAT | vk::Format::X8_D24_UNORM_PACK32 => vk::ImageAspectFlags::DEPTH,
+        vk::Format::S8_UINT => vk::ImageAspectFlags::STENCIL,
+        vk::Format::D16_UNORM_S8_UINT | vk::Format::D24_UNORM_S8_UINT | vk::Format::D32_SFLOAT_S8_UINT => vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL,
+        _ => vk::ImageAspectFlags::COLOR,
+    }
+}
+
+fn fits_mip_axis(offset: u32, length: u32, mip_length: u32) -> bool
+{
+    offset.checked_add(length).is_some_and(|end| end <= mip_length)
+}
+
+//Mips Past The Last One Clamp To 1x1x1 Instead Of Overflowing The Shift
+pub fn mip_level_extent(extent: vk::Extent3D, mip_level: u32) -> vk::Extent3D
+{
+    vk::Extent3D{
+        width: extent.width.checked_shr(mip_level).unwrap_or(0).max(1),
+        height: extent.height.checked_shr(mip_level).unwrap_or(0).max(1),
+        depth: extent.depth.checked_shr(mip_level).unwrap_or(0).max(1),
+    }
+}
+
+pub fn full_mip_levels(width: u32, height: u32) -> u32
+{
+    32 - width.max(height).max(1).leading_zeros()
+}
+
+//Most Conservative Access And Stage Masks That Cover Every Usage Of The Layout
+pub fn layout_access_and_stage(layout: vk::ImageLayout) -> (vk::AccessFlags, vk::PipelineStageFlags)
+{
+    match layout
+    {
+        vk::ImageLayout::UNDEFINED => (vk::AccessFlags::empty(), vk::PipelineStageFlags::TOP_OF_PIPE),
+        vk::ImageLayout::TRANSFER_DST_OPTIMAL => (vk::AccessFlags::TRANSFER_WRITE, vk::PipelineStageFlags::TRANSFER),
+        vk::ImageLayout::TRANSFER_SRC_OPTIMAL => (vk::AccessFlags::TRANSFER_READ, vk::PipelineStageFlags::TRANSFER),
+        vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL => (
+            vk::AccessFlags::SHADER_READ,
+            vk::PipelineStageFlags::VERTEX_SHADER | vk::PipelineStageFlags::FRAGMENT_SHADER | vk::PipelineStageFlags::COMPUTE_SHADER),
+        vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL => (
+            vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT),
+        vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL => (
+            vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS),
+        vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL => (
+            vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ | vk::AccessFlags::SHADER_READ,
+            vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS | vk::PipelineStageFlags::FRAGMENT_SHADER),
+        vk::ImageLayout::PRESENT_SRC_KHR => (vk::AccessFlags::empty(), vk::PipelineStageFlags::BOTTOM_OF_PIPE),
+        _ => (vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE, vk::PipelineStageFlags::ALL_COMMANDS),
+    }
+}
+
+pub unsafe fn cmd_transition_image_layout(
+    device: &ash::Device
+    ,command_buffer: vk::CommandBuffer
+    ,image: vk::Image
+    ,subresource_range: vk::ImageSubresourceRange
+    ,old_layout: vk::ImageLayout
+    ,new_layout: vk::ImageLayout)
+{
+    let (src_access_mask, src_stage_mask) = layout_access_and_stage(old_layout);
+    let (dst_access_mask, dst_stage_mask) = layout_access_and_stage(new_layout);
+
+    let image_barriers = [vk::ImageMemoryBarrier::builder()
+        .src_access_mask(src_access_mask)
+        .dst_access_mask(dst_access_mask)
+        .old_layout(old_layout)
+        .new_layout(new_layout)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .image(image)
+        .subresource_range(subresource_range)
+        .build()];
+
+    device
+        .cmd_pipeline_barrier(
+            command_buffer
+            , src_stage_mask
+            , dst_stage_mask
+            , vk::DependencyFlags::empty()
+            , &[]
+            , &[]
+            , &image_barriers);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn test_image(extent: vk::Extent3D, mip_levels: u32, array_layers: u32) -> GPUImage
+    {
+        GPUImage{
+            image: vk::Image::null(),
+            image_view: vk::ImageView::null(),
+            allocation: vulkan::Allocation::default(),
+            dimension: GPUImageDimension::Texture2DArray,
+            format: vk::Format::R8G8B8A8_UNORM,
+            extent,
+            mip_levels,
+            array_layers,
+            samples: vk::SampleCountFlags::TYPE_1,
+            usage: vk::ImageUsageFlags::TRANSFER_DST,
+            layout: vk::ImageLayout::UNDEFINED,
+        }
+    }
+
+    #[test]
+    fn full_upload_requires_exact_pixel_data()
+    {
+        let image = test_image(vk::Extent3D{ width: 4, height: 4, depth: 1 }, 1, 2);
+
+        assert!(image.check_full_upload(&[0u8; 4 * 4 * 4 * 2]).is_ok());
+        assert_eq!(
+            image.check_full_upload(&[0u8; 4 * 4 * 4]).unwrap_err()
+            , GPUBufferError::PixelDataSizeMismatch{ expected: 128, actual: 64 });
+    }
+
+    #[test]
+    fn copy_regions_past_the_image_limits_are_rejected()
+    {
+        let image = test_image(vk::Extent3D{ width: 4, height: 4, depth: 1 }, 3, 2);
+        let region = image.full_upload_region();
+        assert!(image.copy_region_footprint(&region).is_ok());
+
+        let out_of_bounds = |region: vk::BufferImageCopy| matches!(image.copy_region_footprint(&region), Err(GPUBufferError::ImageRegionOutOfBounds{ .. }));
+        for mip_level in [3, 32, 40, u32::MAX]
+        {
+            let subresource = vk::ImageSubresourceLayers{ mip_level, ..region.image_subresource };
+            assert!(out_of_bounds(vk::BufferImageCopy{ image_subresource: subresource, ..region }), "Mip {}", mip_level);
+        }
+
+        let past_last_layer = vk::ImageSubresourceLayers{ base_array_layer: 1, layer_count: 2, ..region.image_subresource };
+        let wrapping_layers = vk::ImageSubresourceLayers{ base_array_layer: u32::MAX, layer_count: 2, ..region.image_subresource };
+        assert!(out_of_bounds(vk::BufferImageCopy{ image_subresource: past_last_layer, ..region }));
+        assert!(out_of_bounds(vk::BufferImageCopy{ image_subresource: wrapping_layers, ..region }));
+
+        let small_extent = vk::Extent3D{ width: 1, height: 1, depth: 1 };
+        assert!(out_of_bounds(vk::BufferImageCopy{ image_offset: vk::Offset3D{ x: -1, y: 0, z: 0 }, image_extent: small_extent, ..region }));
+        assert!(out_of_bounds(vk::BufferImageCopy{ image_offset: vk::Offset3D{ x: 0, y: 0, z: i32::MIN }, image_extent: small_extent, ..region }));
+        assert!(out_of_bounds(vk::BufferImageCopy{ image_offset: vk::Offset3D{ x: 3, y: 0, z: 0 }, image_extent: vk::Extent3D{ width: u32::MAX, height: 1, depth: 1 }, ..region }));
+        assert!(out_of_bounds(vk::BufferImageCopy{ image_offset: vk::Offset3D{ x: i32::MAX, y: 0, z: 0 }, image_extent: small_extent, ..region }));
+
+        //The Last Texel Of The Smallest Mip Still Fits
+        let last_mip = vk::ImageSubresourceLayers{ mip_level: 2, base_array_layer: 1, layer_count: 1, ..region.image_subresource };
+        assert_eq!(image.copy_region_footprint(&vk::BufferImageCopy{ image_subresource: last_mip, image_extent: small_extent, ..region }), Ok(4));
+    }
+
+    #[test]
+    fn upload_regions_must_read_inside_the_pixel_data()
+    {
+        let image = test_image(vk::Extent3D{ width: 4, height: 4, depth: 1 }, 1, 1);
+        let region = image.full_upload_region();
+        assert!(image.check_upload_regions(64, &[region]).is_ok());
+        assert_eq!(image.check_upload_regions(0, &[region]), Err(GPUBufferError::EmptyUpload{ data_len: 0, region_count: 1 }));
+        assert_eq!(image.check_upload_regions(64, &[]), Err(GPUBufferError::EmptyUpload{ data_len: 64, region_count: 0 }));
+        assert_eq!(image.check_upload_regions(60, &[region]), Err(GPUBufferError::RegionOutOfBounds{ offset: 0, size: 64, buffer_size: 60 }));
+
+        let shifted = vk::BufferImageCopy{ buffer_offset: 16, ..region };
+        assert_eq!(image.check_upload_regions(64, &[shifted]), Err(GPUBufferError::RegionOutOfBounds{ offset: 16, size: 64, buffer_size: 64 }));
+        assert!(image.check_upload_regions(80, &[region, shifted]).is_ok());
+    }
+
+    #[test]
+    fn copy_region_must_fit_the_mip()
+    {
+        let image = test_image(vk::Extent3D{ width: 8, height: 8, depth: 1 }, 4, 1);
+        let mut region = image.full_upload_region();
+        region.image_subresource.mip_level = 1;
+        region.image_extent = vk::Extent3D{ width: 4, height: 4, depth: 1 };
+        assert_eq!(image.copy_region_footprint(&region), Ok(64));
+
+        region.image_offset.x = 1;
+        assert!(matches!(image.copy_region_footprint(&region), Err(GPUBufferError::ImageRegionOutOfBounds{ .. })));
+    }
+}
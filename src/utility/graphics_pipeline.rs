@@ -0,0 +1,1402 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
+
+use ash::vk;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::utility::{descriptors::DescriptorLayoutCache, file_system::CompiledShader, shader_reflection::{PipelineLayoutDesc, ShaderReflectionError, VertexInputLayout}};
+
+#[derive(Debug)]
+pub enum GraphicsPipelineError
+{
+    Io{ path: String, error: io::Error },
+    //A Field Is Missing, Unknown, Has The Wrong Type Or An Unknown Value, field Is Its Dotted Path
+    Json{ field: String, error: serde_json::Error },
+    Ron{ field: String, error: ron::error::SpannedError },
+    //A Field Built In Code Holds A Value Vulkan Does Not Define
+    InvalidField{ field: String, message: String },
+    //The Desc Parsed But Cannot Describe A Working Pipeline
+    Invalid(String),
+    //The Shaders Do Not Fit The Desc Or Each Other
+    ShaderMismatch(ShaderReflectionError),
+    Creation(vk::Result),
+}
+
+impl From<ShaderReflectionError> for GraphicsPipelineError
+{
+    fn from(error: ShaderReflectionError) -> Self
+    {
+        GraphicsPipelineError::ShaderMismatch(error)
+    }
+}
+
+impl fmt::Display for GraphicsPipelineError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            GraphicsPipelineError::Io{ path, error } => write!(f, "Failed To Read Pipeline Desc {}: {}", path, error),
+            GraphicsPipelineError::Json{ field, error } => write!(f, "Malformed Pipeline Desc Field {}: {}", field, error),
+            GraphicsPipelineError::Ron{ field, error } => write!(f, "Malformed Pipeline Desc Field {}: {}", field, error),
+            GraphicsPipelineError::InvalidField{ field, message } => write!(f, "Invalid Pipeline Desc Field {}: {}", field, message),
+            GraphicsPipelineError::Invalid(message) => write!(f, "Invalid Pipeline Desc: {}", message),
+            GraphicsPipelineError::ShaderMismatch(error) => write!(f, "{}", error),
+            GraphicsPipelineError::Creation(result) => write!(f, "Failed To Create Graphics Pipeline: {}", result),
+        }
+    }
+}
+
+impl std::error::Error for GraphicsPipelineError
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        match self
+        {
+            GraphicsPipelineError::Io{ error, .. } => Some(error),
+            GraphicsPipelineError::Json{ error, .. } => Some(error),
+            GraphicsPipelineError::Ron{ error, .. } => Some(error),
+            GraphicsPipelineError::ShaderMismatch(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VertexBindingDesc
+{
+    pub binding: u32,
+    pub stride: u32,
+    #[serde(with = "vk_enum", default)]
+    pub input_rate: vk::VertexInputRate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VertexAttributeDesc
+{
+    pub location: u32,
+    #[serde(default)]
+    pub binding: u32,
+    #[serde(with = "vk_enum")]
+    pub format: vk::Format,
+    pub offset: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VertexLayoutDesc
+{
+    pub bindings: Vec<VertexBindingDesc>,
+    pub attributes: Vec<VertexAttributeDesc>,
+}
+
+impl VertexLayoutDesc
+{
+    pub fn from_type<V: VertexInputLayout>() -> VertexLayoutDesc
+    {
+        VertexLayoutDesc{
+            bindings: V::get_binding_descriptions()
+                .iter()
+                .map(|binding| VertexBindingDesc{ binding: binding.binding, stride: binding.stride, input_rate: binding.input_rate })
+                .collect(),
+            attributes: V::get_attribute_descriptions()
+                .iter()
+                .map(|attribute| VertexAttributeDesc{ location: attribute.location, binding: attribute.binding, format: attribute.format, offset: attribute.offset })
+                .collect(),
+        }
+    }
+
+    pub fn binding_descriptions(&self) -> Vec<vk::VertexInputBindingDescription>
+    {
+        self.bindings
+            .iter()
+            .map(|binding| vk::VertexInputBindingDescription{ binding: binding.binding, stride: binding.stride, input_rate: binding.input_rate })
+            .collect()
+    }
+
+    pub fn attribute_descriptions(&self) -> Vec<vk::VertexInputAttributeDescription>
+    {
+        self.attributes
+            .iter()
+            .map(|attribute| vk::VertexInputAttributeDescription{ location: attribute.location, binding: attribute.binding, format: attribute.format, offset: attribute.offset })
+            .collect()
+    }
+}
+
+//Compared And Hashed By Bit Pattern, So Descs Can Key A HashMap
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DepthBiasDesc
+{
+    pub constant_factor: f32,
+    pub clamp: f32,
+    pub slope_factor: f32,
+}
+
+impl DepthBiasDesc
+{
+    fn bits(&self) -> [u32; 3]
+    {
+        [self.constant_factor.to_bits(), self.clamp.to_bits(), self.slope_factor.to_bits()]
+    }
+}
+
+impl PartialEq for DepthBiasDesc
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        self.bits() == other.bits()
+    }
+}
+
+impl Eq for DepthBiasDesc {}
+
+impl Hash for DepthBiasDesc
+{
+    fn hash<H: Hasher>(&self, state: &mut H)
+    {
+        self.bits().hash(state);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RasterDesc
+{
+    #[serde(with = "vk_enum")]
+    pub polygon_mode: vk::PolygonMode,
+    #[serde(with = "cull_mode")]
+    pub cull_mode: vk::CullModeFlags,
+    #[serde(with = "vk_enum")]
+    pub front_face: vk::FrontFace,
+    pub depth_clamp_enable: bool,
+    //None Disables Depth Bias
+    pub depth_bias: Option<DepthBiasDesc>,
+    pub line_width: f32,
+}
+
+impl RasterDesc
+{
+    pub fn new() -> RasterDesc
+    {
+        RasterDesc{
+            polygon_mode: vk::PolygonMode::FILL,
+            cull_mode: vk::CullModeFlags::BACK,
+            front_face: vk::FrontFace::CLOCKWISE,
+            depth_clamp_enable: false,
+            depth_bias: None,
+            line_width: 1.0,
+        }
+    }
+
+    fn key(&self) -> (vk::PolygonMode, vk::CullModeFlags, vk::FrontFace, bool, Option<DepthBiasDesc>, u32)
+    {
+        (self.polygon_mode, self.cull_mode, self.front_face, self.depth_clamp_enable, self.depth_bias, self.line_width.to_bits())
+    }
+}
+
+impl Default for RasterDesc
+{
+    fn default() -> Self
+    {
+        RasterDesc::new()
+    }
+}
+
+impl PartialEq for RasterDesc
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for RasterDesc {}
+
+impl Hash for RasterDesc
+{
+    fn hash<H: Hasher>(&self, state: &mut H)
+    {
+        self.key().hash(state);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StencilFaceDesc
+{
+    #[serde(with = "vk_enum")]
+    pub fail_op: vk::StencilOp,
+    #[serde(with = "vk_enum")]
+    pub pass_op: vk::StencilOp,
+    #[serde(with = "vk_enum")]
+    pub depth_fail_op: vk::StencilOp,
+    #[serde(with = "vk_enum")]
+    pub compare_op: vk::CompareOp,
+    pub compare_mask: u32,
+    pub write_mask: u32,
+    pub reference: u32,
+}
+
+impl StencilFaceDesc
+{
+    pub fn keep() -> StencilFaceDesc
+    {
+        StencilFaceDesc{
+            fail_op: vk::StencilOp::KEEP,
+            pass_op: vk::StencilOp::KEEP,
+            depth_fail_op: vk::StencilOp::KEEP,
+            compare_op: vk::CompareOp::ALWAYS,
+            compare_mask: 0,
+            write_mask: 0,
+            reference: 0,
+        }
+    }
+
+    fn vk_state(&self) -> vk::StencilOpState
+    {
+        vk::StencilOpState{
+            fail_op: self.fail_op,
+            pass_op: self.pass_op,
+            depth_fail_op: self.depth_fail_op,
+            compare_op: self.compare_op,
+            compare_mask: self.compare_mask,
+            write_mask: self.write_mask,
+            reference: self.reference,
+        }
+    }
+}
+
+impl Default for StencilFaceDesc
+{
+    fn default() -> Self
+    {
+        StencilFaceDesc::keep()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DepthStencilDesc
+{
+    pub depth_test_enable: bool,
+    pub depth_write_enable: bool,
+    #[serde(with = "vk_enum")]
+    pub depth_compare_op: vk::CompareOp,
+    pub stencil_test_enable: bool,
+    pub front: StencilFaceDesc,
+    pub back: StencilFaceDesc,
+}
+
+impl DepthStencilDesc
+{
+    pub fn depth_less_or_equal() -> DepthStencilDesc
+    {
+        DepthStencilDesc{
+            depth_test_enable: true,
+            depth_write_enable: true,
+            depth_compare_op: vk::CompareOp::LESS_OR_EQUAL,
+            stencil_test_enable: false,
+            front: StencilFaceDesc::keep(),
+            back: StencilFaceDesc::keep(),
+        }
+    }
+
+    pub fn disabled() -> DepthStencilDesc
+    {
+        DepthStencilDesc{
+            depth_test_enable: false,
+            depth_write_enable: false,
+            depth_compare_op: vk::CompareOp::ALWAYS,
+            ..DepthStencilDesc::depth_less_or_equal()
+        }
+    }
+}
+
+impl Default for DepthStencilDesc
+{
+    fn default() -> Self
+    {
+        DepthStencilDesc::depth_less_or_equal()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BlendAttachmentDesc
+{
+    pub blend_enable: bool,
+    #[serde(with = "vk_enum")]
+    pub src_color_blend_factor: vk::BlendFactor,
+    #[serde(with = "vk_enum")]
+    pub dst_color_blend_factor: vk::BlendFactor,
+    #[serde(with = "vk_enum")]
+    pub color_blend_op: vk::BlendOp,
+    #[serde(with = "vk_enum")]
+    pub src_alpha_blend_factor: vk::BlendFactor,
+    #[serde(with = "vk_enum")]
+    pub dst_alpha_blend_factor: vk::BlendFactor,
+    #[serde(with = "vk_enum")]
+    pub alpha_blend_op: vk::BlendOp,
+    #[serde(with = "color_write_mask")]
+    pub color_write_mask: vk::ColorComponentFlags,
+}
+
+impl BlendAttachmentDesc
+{
+    pub fn opaque() -> BlendAttachmentDesc
+    {
+        BlendAttachmentDesc{
+            blend_enable: false,
+            src_color_blend_factor: vk::BlendFactor::ONE,
+            dst_color_blend_factor: vk::BlendFactor::ZERO,
+            color_blend_op: vk::BlendOp::ADD,
+            src_alpha_blend_factor: vk::BlendFactor::ONE,
+            dst_alpha_blend_factor: vk::BlendFactor::ZERO,
+            alpha_blend_op: vk::BlendOp::ADD,
+            color_write_mask: vk::ColorComponentFlags::RGBA,
+        }
+    }
+
+    pub fn alpha_blend() -> BlendAttachmentDesc
+    {
+        BlendAttachmentDesc{
+            blend_enable: true,
+            src_color_blend_factor: vk::BlendFactor::SRC_ALPHA,
+            dst_color_blend_factor: vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            src_alpha_blend_factor: vk::BlendFactor::ONE,
+            dst_alpha_blend_factor: vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+            ..BlendAttachmentDesc::opaque()
+        }
+    }
+
+    pub fn additive() -> BlendAttachmentDesc
+    {
+        BlendAttachmentDesc{
+            blend_enable: true,
+            src_color_blend_factor: vk::BlendFactor::ONE,
+            dst_color_blend_factor: vk::BlendFactor::ONE,
+            src_alpha_blend_factor: vk::BlendFactor::ONE,
+            dst_alpha_blend_factor: vk::BlendFactor::ONE,
+            ..BlendAttachmentDesc::opaque()
+        }
+    }
+
+    fn vk_state(&self) -> vk::PipelineColorBlendAttachmentState
+    {
+        vk::PipelineColorBlendAttachmentState{
+            blend_enable: self.blend_enable as vk::Bool32,
+            src_color_blend_factor: self.src_color_blend_factor,
+            dst_color_blend_factor: self.dst_color_blend_factor,
+            color_blend_op: self.color_blend_op,
+            src_alpha_blend_factor: self.src_alpha_blend_factor,
+            dst_alpha_blend_factor: self.dst_alpha_blend_factor,
+            alpha_blend_op: self.alpha_blend_op,
+            color_write_mask: self.color_write_mask,
+        }
+    }
+}
+
+impl Default for BlendAttachmentDesc
+{
+    fn default() -> Self
+    {
+        BlendAttachmentDesc::opaque()
+    }
+}
+
+//Everything Fixed Function About A Graphics Pipeline, Loadable From JSON Or RON So New Materials Need No Rust Code
+//Viewport And Scissor Are Always Dynamic, So One Pipeline Serves Every Swapchain Size
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GraphicsPipelineDesc
+{
+    pub name: String,
+    pub vertex_shader: String,
+    pub fragment_shader: String,
+    #[serde(default)]
+    pub vertex_layout: VertexLayoutDesc,
+    #[serde(with = "vk_enum", default = "default_topology")]
+    pub topology: vk::PrimitiveTopology,
+    #[serde(default)]
+    pub primitive_restart_enable: bool,
+    #[serde(default)]
+    pub raster: RasterDesc,
+    #[serde(default)]
+    pub depth_stencil: DepthStencilDesc,
+    //One Per Color Attachment
+    #[serde(default = "default_blend_attachments")]
+    pub blend_attachments: Vec<BlendAttachmentDesc>,
+    //The Render Pass Attachment Formats, Must Line Up With blend_attachments When Given
+    //Part Of The Cache Key, So Retargeting A Desc Builds A New Pipeline Instead Of Reusing A Mismatched One
+    #[serde(with = "vk_enum_list", default)]
+    pub color_formats: Vec<vk::Format>,
+    #[serde(with = "optional_vk_enum", default)]
+    pub depth_format: Option<vk::Format>,
+    #[serde(with = "sample_count", default = "default_samples")]
+    pub samples: vk::SampleCountFlags,
+}
+
+impl GraphicsPipelineDesc
+{
+    //Opaque, Back Face Culled Triangle List With Depth Testing, The Same Defaults Fields Missing From JSON Get
+    pub fn new(name: &str, vertex_shader: &str, fragment_shader: &str) -> GraphicsPipelineDesc
+    {
+        GraphicsPipelineDesc{
+            name: name.to_owned(),
+            vertex_shader: vertex_shader.to_owned(),
+            fragment_shader: fragment_shader.to_owned(),
+            vertex_layout: VertexLayoutDesc::default(),
+            topology: vk::PrimitiveTopology::TRIANGLE_LIST,
+            primitive_restart_enable: false,
+            raster: RasterDesc::new(),
+            depth_stencil: DepthStencilDesc::depth_less_or_equal(),
+            blend_attachments: vec![BlendAttachmentDesc::opaque()],
+            color_formats: vec![],
+            depth_format: None,
+            samples: vk::SampleCountFlags::TYPE_1,
+        }
+    }
+
+    //.ron Files Are Read As RON, Anything Else As JSON
+    pub fn load(path: &str) -> Result<GraphicsPipelineDesc, GraphicsPipelineError>
+    {
+        let text = fs::read_to_string(path).map_err(|error| GraphicsPipelineError::Io{ path: path.to_owned(), error })?;
+        match Path::new(path).extension().and_then(|extension| extension.to_str())
+        {
+            Some("ron") => GraphicsPipelineDesc::from_ron(&text),
+            _ => GraphicsPipelineDesc::from_json(&text),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), GraphicsPipelineError>
+    {
+        if self.blend_attachments.is_empty()
+        {
+            return Err(GraphicsPipelineError::Invalid(format!("{} Has No Color Attachments", self.name)));
+        }
+        if !self.color_formats.is_empty() && self.color_formats.len() != self.blend_attachments.len()
+        {
+            return Err(GraphicsPipelineError::Invalid(format!("{} Has {} Color Formats But {} Blend Attachments", self.name, self.color_formats.len(), self.blend_attachments.len())));
+        }
+        if self.depth_format.is_none() && !self.color_formats.is_empty() && (self.depth_stencil.depth_test_enable || self.depth_stencil.stencil_test_enable)
+        {
+            return Err(GraphicsPipelineError::Invalid(format!("{} Tests Depth Or Stencil Without A Depth Attachment", self.name)));
+        }
+        if !is_sample_count(self.samples)
+        {
+            return Err(self.invalid_field("samples", format!("{} Is Not A Power Of Two Up To {}", self.samples.as_raw(), MAX_SAMPLE_COUNT)));
+        }
+        self.check_vk_enum("topology", &self.topology)?;
+        self.check_vk_enum("raster.polygon_mode", &self.raster.polygon_mode)?;
+        self.check_vk_enum("raster.front_face", &self.raster.front_face)?;
+        self.check_vk_enum("depth_stencil.depth_compare_op", &self.depth_stencil.depth_compare_op)?;
+        for (face_name, face) in [("front", &self.depth_stencil.front), ("back", &self.depth_stencil.back)]
+        {
+            self.check_vk_enum(&format!("depth_stencil.{}.fail_op", face_name), &face.fail_op)?;
+            self.check_vk_enum(&format!("depth_stencil.{}.pass_op", face_name), &face.pass_op)?;
+            self.check_vk_enum(&format!("depth_stencil.{}.depth_fail_op", face_name), &face.depth_fail_op)?;
+            self.check_vk_enum(&format!("depth_stencil.{}.compare_op", face_name), &face.compare_op)?;
+        }
+        for (index, blend_attachment) in self.blend_attachments.iter().enumerate()
+        {
+            let field = |name: &str| format!("blend_attachments[{}].{}", index, name);
+            self.check_vk_enum(&field("src_color_blend_factor"), &blend_attachment.src_color_blend_factor)?;
+            self.check_vk_enum(&field("dst_color_blend_factor"), &blend_attachment.dst_color_blend_factor)?;
+            self.check_vk_enum(&field("color_blend_op"), &blend_attachment.color_blend_op)?;
+            self.check_vk_enum(&field("src_alpha_blend_factor"), &blend_attachment.src_alpha_blend_factor)?;
+            self.check_vk_enum(&field("dst_alpha_blend_factor"), &blend_attachment.dst_alpha_blend_factor)?;
+            self.check_vk_enum(&field("alpha_blend_op"), &blend_attachment.alpha_blend_op)?;
+        }
+        for (index, format) in self.color_formats.iter().enumerate()
+        {
+            self.check_vk_enum(&format!("color_formats[{}]", index), format)?;
+        }
+        if let Some(depth_format) = self.depth_format.as_ref()
+        {
+            self.check_vk_enum("depth_format", depth_format)?;
+        }
+        for (index, binding) in self.vertex_layout.bindings.iter().enumerate()
+        {
+            self.check_vk_enum(&format!("vertex_layout.bindings[{}].input_rate", index), &binding.input_rate)?;
+        }
+        for (index, attribute) in self.vertex_layout.attributes.iter().enumerate()
+        {
+            self.check_vk_enum(&format!("vertex_layout.attributes[{}].format", index), &attribute.format)?;
+        }
+        for attribute in self.vertex_layout.attributes.iter()
+        {
+            if !self.vertex_layout.bindings.iter().any(|binding| binding.binding == attribute.binding)
+            {
+                return Err(GraphicsPipelineError::Invalid(format!("{} Attribute At Location {} Uses Undeclared Binding {}", self.name, attribute.location, attribute.binding)));
+            }
+        }
+        Ok(())
+    }
+
+    //The Loader Only Knows The Sample Count Is A Power Of Two, The Device Decides Which Ones Work
+    pub fn validate_samples(&self, supported_samples: vk::SampleCountFlags) -> Result<(), GraphicsPipelineError>
+    {
+        if supported_samples.contains(self.samples)
+        {
+            Ok(())
+        }
+        else
+        {
+            Err(self.invalid_field("samples", format!("{} Samples Are Not Supported By The Device, Supported {:?}", self.samples.as_raw(), supported_samples)))
+        }
+    }
+
+    fn invalid_field(&self, field: &str, message: String) -> GraphicsPipelineError
+    {
+        GraphicsPipelineError::InvalidField{ field: field.to_owned(), message: format!("{}: {}", self.name, message) }
+    }
+
+    fn check_vk_enum<T: fmt::Debug>(&self, field: &str, value: &T) -> Result<(), GraphicsPipelineError>
+    {
+        if is_known_vk_enum(value)
+        {
+            Ok(())
+        }
+        else
+        {
+            Err(self.invalid_field(field, format!("Unknown Value {:?}", value)))
+        }
+    }
+
+    pub fn create_pipeline(
+        &self
+        , device: &ash::Device
+        , pipeline_cache: vk::PipelineCache
+        , pipeline_layout: vk::PipelineLayout
+        , render_pass: vk::RenderPass
+        , vertex_shader: &CompiledShader
+        , fragment_shader: &CompiledShader) -> Result<vk::Pipeline, GraphicsPipelineError>
+    {
+        self.validate()?;
+        //Dynamic Rendering Is Not Enabled On The Device, So Every Pipeline Needs A Render Pass
+        if render_pass == vk::RenderPass::null()
+        {
+            return Err(GraphicsPipelineError::Invalid(format!("{} Has No Render Pass", self.name)));
+        }
+
+        let create_module = |spirv: &[u32]| {
+            let shader_module_create_info = vk::ShaderModuleCreateInfo::builder().code(spirv);
+            unsafe { device.create_shader_module(&shader_module_create_info, None) }.map_err(GraphicsPipelineError::Creation)
+        };
+        let vertex_module = create_module(&vertex_shader.spirv)?;
+        let fragment_module = match create_module(&fragment_shader.spirv)
+        {
+            Ok(fragment_module) => fragment_module,
+            Err(error) =>
+            {
+                unsafe { device.destroy_shader_module(vertex_module, None) };
+                return Err(error);
+            }
+        };
+
+        let shader_stages = [vk::PipelineShaderStageCreateInfo::builder()
+            .module(vertex_module)
+            .name(&vertex_shader.entry_point)
+            .stage(vertex_shader.stage_flags()).build(),
+            vk::PipelineShaderStageCreateInfo::builder()
+            .module(fragment_module)
+            .name(&fragment_shader.entry_point)
+            .stage(fragment_shader.stage_flags()).build()];
+
+        let vertex_binding_descriptions = self.vertex_layout.binding_descriptions();
+        let vertex_attribute_descriptions = self.vertex_layout.attribute_descriptions();
+        let vertex_input_state_create_info = vk::PipelineVertexInputStateCreateInfo::builder()
+            .vertex_binding_descriptions(&vertex_binding_descriptions)
+            .vertex_attribute_descriptions(&vertex_attribute_descriptions);
+
+        let input_assembly_state_create_info = vk::PipelineInputAssemblyStateCreateInfo::builder()
+            .primitive_restart_enable(self.primitive_restart_enable)
+            .topology(self.topology);
+
+        //Counts Only, The Values Come From cmd_set_viewport And cmd_set_scissor
+        let viewport_state_create_info = vk::PipelineViewportStateCreateInfo::builder()
+            .viewport_count(1)
+            .scissor_count(1);
+
+        let depth_bias = self.raster.depth_bias.unwrap_or(DepthBiasDesc{ constant_factor: 0.0, clamp: 0.0, slope_factor: 0.0 });
+        let rasterizer_state_create_info = vk::PipelineRasterizationStateCreateInfo::builder()
+            .depth_clamp_enable(self.raster.depth_clamp_enable)
+            .cull_mode(self.raster.cull_mode)
+            .front_face(self.raster.front_face)
+            .line_width(self.raster.line_width)
+            .polygon_mode(self.raster.polygon_mode)
+            .rasterizer_discard_enable(false)
+            .depth_bias_enable(self.raster.depth_bias.is_some())
+            .depth_bias_clamp(depth_bias.clamp)
+            .depth_bias_constant_factor(depth_bias.constant_factor)
+            .depth_bias_slope_factor(depth_bias.slope_factor);
+
+        let multisample_state_create_info = vk::PipelineMultisampleStateCreateInfo::builder()
+            .rasterization_samples(self.samples)
+            .sample_shading_enable(false)
+            .min_sample_shading(0.0)
+            .alpha_to_one_enable(false)
+            .alpha_to_coverage_enable(false);
+
+        let depth_state_create_info = vk::PipelineDepthStencilStateCreateInfo::builder()
+            .depth_test_enable(self.depth_stencil.depth_test_enable)
+            .depth_write_enable(self.depth_stencil.depth_write_enable)
+            .depth_compare_op(self.depth_stencil.depth_compare_op)
+            .depth_bounds_test_enable(false)
+            .stencil_test_enable(self.depth_stencil.stencil_test_enable)
+            .front(self.depth_stencil.front.vk_state())
+            .back(self.depth_stencil.back.vk_state())
+            .max_depth_bounds(1.0)
+            .min_depth_bounds(0.0);
+
+        let color_blend_attachment_states: Vec<vk::PipelineColorBlendAttachmentState> = self.blend_attachments
+            .iter()
+            .map(|blend_attachment| blend_attachment.vk_state())
+            .collect();
+        let color_blend_state = vk::PipelineColorBlendStateCreateInfo::builder()
+            .logic_op_enable(false)
+            .logic_op(vk::LogicOp::COPY)
+            .attachments(&color_blend_attachment_states)
+            .blend_constants([0.0, 0.0, 0.0, 0.0]);
+
+        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_state_create_info = vk::PipelineDynamicStateCreateInfo::builder()
+            .dynamic_states(&dynamic_states);
+
+        let pipeline_create_info = vk::GraphicsPipelineCreateInfo::builder()
+            .stages(&shader_stages)
+            .viewport_state(&viewport_state_create_info)
+            .vertex_input_state(&vertex_input_state_create_info)
+            .input_assembly_state(&input_assembly_state_create_info)
+            .rasterization_state(&rasterizer_state_create_info)
+            .multisample_state(&multisample_state_create_info)
+            .depth_stencil_state(&depth_state_create_info)
+            .color_blend_state(&color_blend_state)
+            .dynamic_state(&dynamic_state_create_info)
+            .layout(pipeline_layout)
+            .render_pass(render_pass)
+            .subpass(0);
+
+        let pipeline_result = unsafe
+        {
+            device.create_graphics_pipelines(pipeline_cache, std::slice::from_ref(&pipeline_create_info), None)
+        };
+        unsafe
+        {
+            device.destroy_shader_module(vertex_module, None);
+            device.destroy_shader_module(fragment_module, None);
+        }
+        pipeline_result
+            .map(|pipelines| pipelines[0])
+            .map_err(|(_, error)| GraphicsPipelineError::Creation(error))
+    }
+
+    pub fn to_json(&self) -> String
+    {
+        serde_json::to_string_pretty(self).expect("Failed To Serialize Pipeline Desc!")
+    }
+
+    pub fn to_ron(&self) -> String
+    {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()).expect("Failed To Serialize Pipeline Desc!")
+    }
+
+    //Enums Use Their Vulkan Names Without The Prefix, e.g. "TRIANGLE_LIST", Missing Fields Keep The Defaults Of new()
+    pub fn from_json(text: &str) -> Result<GraphicsPipelineDesc, GraphicsPipelineError>
+    {
+        let mut deserializer = serde_json::Deserializer::from_str(text);
+        let desc: GraphicsPipelineDesc = serde_path_to_error::deserialize(&mut deserializer)
+            .map_err(|error| GraphicsPipelineError::Json{ field: error.path().to_string(), error: error.into_inner() })?;
+        deserializer.end().map_err(|error| GraphicsPipelineError::Json{ field: ".".to_owned(), error })?;
+        desc.validate()?;
+        Ok(desc)
+    }
+
+    //Same Fields And Defaults As from_json
+    pub fn from_ron(text: &str) -> Result<GraphicsPipelineDesc, GraphicsPipelineError>
+    {
+        let mut deserializer = ron::Deserializer::from_str(text).map_err(|error| GraphicsPipelineError::Ron{ field: ".".to_owned(), error })?;
+        let desc: GraphicsPipelineDesc = match serde_path_to_error::deserialize(&mut deserializer)
+        {
+            Ok(desc) => desc,
+            Err(error) =>
+            {
+                let field = error.path().to_string();
+                return Err(GraphicsPipelineError::Ron{ field, error: deserializer.span_error(error.into_inner()) });
+            }
+        };
+        deserializer.end().map_err(|error| GraphicsPipelineError::Ron{ field: ".".to_owned(), error: deserializer.span_error(error) })?;
+        desc.validate()?;
+        Ok(desc)
+    }
+}
+
+fn default_topology() -> vk::PrimitiveTopology
+{
+    vk::PrimitiveTopology::TRIANGLE_LIST
+}
+
+fn default_blend_attachments() -> Vec<BlendAttachmentDesc>
+{
+    vec![BlendAttachmentDesc::opaque()]
+}
+
+fn default_samples() -> vk::SampleCountFlags
+{
+    vk::SampleCountFlags::TYPE_1
+}
+
+//Highest Core 1.0 Format, Extension Formats Can Still Be Given As Raw Numbers
+const MAX_CORE_FORMAT: i32 = 184;
+const MAX_SAMPLE_COUNT: u32 = 64;
+
+//ash Prints Values It Has No Name For As Plain Numbers
+fn is_known_vk_enum<T: fmt::Debug>(value: &T) -> bool
+{
+    format!("{:?}", value).parse::<i64>().is_err()
+}
+
+//One Of TYPE_1 To TYPE_64
+fn is_sample_count(samples: vk::SampleCountFlags) -> bool
+{
+    samples.as_raw().is_power_of_two() && samples.as_raw() <= MAX_SAMPLE_COUNT
+}
+
+//Accepts The Name ash Prints For The Value, Or The Raw Number Of A Value ash Has A Name For
+fn parse_vk_enum<T: fmt::Debug>(name: &str, raw_values: std::ops::RangeInclusive<i32>, from_raw: fn(i32) -> T) -> Option<T>
+{
+    if let Ok(raw) = name.parse::<i32>()
+    {
+        return Some(from_raw(raw)).filter(is_known_vk_enum);
+    }
+    raw_values
+        .map(from_raw)
+        .find(|value| format!("{:?}", value) == name)
+}
+
+fn cull_mode_name(cull_mode: vk::CullModeFlags) -> &'static str
+{
+    match cull_mode
+    {
+        vk::CullModeFlags::NONE => "NONE",
+        vk::CullModeFlags::FRONT => "FRONT",
+        vk::CullModeFlags::BACK => "BACK",
+        _ => "FRONT_AND_BACK",
+    }
+}
+
+fn parse_cull_mode(name: &str) -> Option<vk::CullModeFlags>
+{
+    match name
+    {
+        "NONE" => Some(vk::CullModeFlags::NONE),
+        "FRONT" => Some(vk::CullModeFlags::FRONT),
+        "BACK" => Some(vk::CullModeFlags::BACK),
+        "FRONT_AND_BACK" => Some(vk::CullModeFlags::FRONT_AND_BACK),
+        _ => None,
+    }
+}
+
+//Written As The Enabled Channels, e.g. "RGBA" Or "RGB"
+fn color_write_mask_name(color_write_mask: vk::ColorComponentFlags) -> String
+{
+    [(vk::ColorComponentFlags::R, 'R'), (vk::ColorComponentFlags::G, 'G'), (vk::ColorComponentFlags::B, 'B'), (vk::ColorComponentFlags::A, 'A')]
+        .iter()
+        .filter(|(flag, _)| color_write_mask.contains(*flag))
+        .map(|(_, channel)| *channel)
+        .collect()
+}
+
+fn parse_color_write_mask(name: &str) -> Option<vk::ColorComponentFlags>
+{
+    name.chars().try_fold(vk::ColorComponentFlags::empty(), |mask, channel| match channel
+    {
+        'R' => Some(mask | vk::ColorComponentFlags::R),
+        'G' => Some(mask | vk::ColorComponentFlags::G),
+        'B' => Some(mask | vk::ColorComponentFlags::B),
+        'A' => Some(mask | vk::ColorComponentFlags::A),
+        _ => None,
+    })
+}
+
+//Vulkan Enums The Desc Files Name By Value, raw_values Bounds The Name Search
+trait VkEnum: fmt::Debug + Sized
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>;
+    fn from_raw_value(raw: i32) -> Self;
+}
+
+impl VkEnum for vk::VertexInputRate
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>
+    {
+        0..=1
+    }
+
+    fn from_raw_value(raw: i32) -> Self
+    {
+        vk::VertexInputRate::from_raw(raw)
+    }
+}
+
+impl VkEnum for vk::Format
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>
+    {
+        0..=MAX_CORE_FORMAT
+    }
+
+    fn from_raw_value(raw: i32) -> Self
+    {
+        vk::Format::from_raw(raw)
+    }
+}
+
+impl VkEnum for vk::PrimitiveTopology
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>
+    {
+        0..=10
+    }
+
+    fn from_raw_value(raw: i32) -> Self
+    {
+        vk::PrimitiveTopology::from_raw(raw)
+    }
+}
+
+impl VkEnum for vk::PolygonMode
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>
+    {
+        0..=2
+    }
+
+    fn from_raw_value(raw: i32) -> Self
+    {
+        vk::PolygonMode::from_raw(raw)
+    }
+}
+
+impl VkEnum for vk::FrontFace
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>
+    {
+        0..=1
+    }
+
+    fn from_raw_value(raw: i32) -> Self
+    {
+        vk::FrontFace::from_raw(raw)
+    }
+}
+
+impl VkEnum for vk::CompareOp
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>
+    {
+        0..=7
+    }
+
+    fn from_raw_value(raw: i32) -> Self
+    {
+        vk::CompareOp::from_raw(raw)
+    }
+}
+
+impl VkEnum for vk::StencilOp
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>
+    {
+        0..=7
+    }
+
+    fn from_raw_value(raw: i32) -> Self
+    {
+        vk::StencilOp::from_raw(raw)
+    }
+}
+
+impl VkEnum for vk::BlendFactor
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>
+    {
+        0..=18
+    }
+
+    fn from_raw_value(raw: i32) -> Self
+    {
+        vk::BlendFactor::from_raw(raw)
+    }
+}
+
+impl VkEnum for vk::BlendOp
+{
+    fn raw_values() -> std::ops::RangeInclusive<i32>
+    {
+        0..=4
+    }
+
+    fn from_raw_value(raw: i32) -> Self
+    {
+        vk::BlendOp::from_raw(raw)
+    }
+}
+
+//Serialized As The Name ash Prints, So Lists And Options Of Enums Can Share One Impl
+struct VkEnumName<T>(T);
+
+impl<T: fmt::Debug> Serialize for VkEnumName<T>
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        serializer.collect_str(&format_args!("{:?}", self.0))
+    }
+}
+
+impl<'de, T: VkEnum> Deserialize<'de> for VkEnumName<T>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        let name = String::deserialize(deserializer)?;
+        parse_vk_enum(&name, T::raw_values(), T::from_raw_value)
+            .map(VkEnumName)
+            .ok_or_else(|| D::Error::custom(format!("Unknown Value {}", name)))
+    }
+}
+
+//The serde(with) Modules Below Map Fields That Are Plain Vulkan Types
+mod vk_enum
+{
+    use super::*;
+
+    pub fn serialize<T: fmt::Debug, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        VkEnumName(value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: VkEnum, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error>
+    {
+        VkEnumName::deserialize(deserializer).map(|name| name.0)
+    }
+}
+
+mod vk_enum_list
+{
+    use super::*;
+
+    pub fn serialize<T: fmt::Debug, S: Serializer>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    {
+        serializer.collect_seq(values.iter().map(VkEnumName))
+    }
+
+    pub fn deserialize<'de, T: VkEnum, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<T>, D::Error>
+    {
+        Vec::<VkEnumName<T>>::deserialize(deserializer).map(|names| names.into_iter().map(|name| name.0).collect())
+    }
+}
+
+mod optional_vk_enum
+{
+    use super::*;
+
+    pub fn serialize<T: fmt::Debug, S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        value.as_ref().map(VkEnumName).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: VkEnum, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error>
+    {
+        Option::<VkEnumName<T>>::deserialize(deserializer).map(|name| name.map(|name| name.0))
+    }
+}
+
+mod cull_mode
+{
+    use super::*;
+
+    pub fn serialize<S: Serializer>(cull_mode: &vk::CullModeFlags, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        serializer.serialize_str(cull_mode_name(*cull_mode))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<vk::CullModeFlags, D::Error>
+    {
+        let name = String::deserialize(deserializer)?;
+        parse_cull_mode(&name).ok_or_else(|| D::Error::custom(format!("Unknown Cull Mode {}", name)))
+    }
+}
+
+mod color_write_mask
+{
+    use super::*;
+
+    pub fn serialize<S: Serializer>(color_write_mask: &vk::ColorComponentFlags, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        serializer.serialize_str(&color_write_mask_name(*color_write_mask))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<vk::ColorComponentFlags, D::Error>
+    {
+        let name = String::deserialize(deserializer)?;
+        parse_color_write_mask(&name).ok_or_else(|| D::Error::custom(format!("Expected Channels Like RGBA, Got {}", name)))
+    }
+}
+
+//The Raw Sample Count, e.g. 4
+mod sample_count
+{
+    use super::*;
+
+    pub fn serialize<S: Serializer>(samples: &vk::SampleCountFlags, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        serializer.serialize_u32(samples.as_raw())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<vk::SampleCountFlags, D::Error>
+    {
+        let samples = vk::SampleCountFlags::from_raw(u32::deserialize(deserializer)?);
+        if is_sample_count(samples)
+        {
+            Ok(samples)
+        }
+        else
+        {
+            Err(D::Error::custom(format!("Sample Count {} Is Not A Power Of Two Up To {}", samples.as_raw(), MAX_SAMPLE_COUNT)))
+        }
+    }
+}
+
+//Everything In A Desc That Reaches The Driver, name Is Only A Label And The Shader Paths Are Covered By The Content Hashes
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GraphicsPipelineState
+{
+    vertex_layout: VertexLayoutDesc,
+    topology: vk::PrimitiveTopology,
+    primitive_restart_enable: bool,
+    raster: RasterDesc,
+    depth_stencil: DepthStencilDesc,
+    blend_attachments: Vec<BlendAttachmentDesc>,
+    color_formats: Vec<vk::Format>,
+    depth_format: Option<vk::Format>,
+    samples: vk::SampleCountFlags,
+}
+
+impl GraphicsPipelineState
+{
+    fn new(desc: &GraphicsPipelineDesc) -> GraphicsPipelineState
+    {
+        GraphicsPipelineState{
+            vertex_layout: desc.vertex_layout.clone(),
+            topology: desc.topology,
+            primitive_restart_enable: desc.primitive_restart_enable,
+            raster: desc.raster,
+            depth_stencil: desc.depth_stencil,
+            blend_attachments: desc.blend_attachments.clone(),
+            color_formats: desc.color_formats.clone(),
+            depth_format: desc.depth_format,
+            samples: desc.samples,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GraphicsPipelineKey
+{
+    state: GraphicsPipelineState,
+    render_pass: vk::RenderPass,
+    //Layouts Come From The Same Cache, So Equal Layout Descs Always Share A Handle
+    pipeline_layout: vk::PipelineLayout,
+    //Shaders Are Keyed By Content, So A Hot Reloaded Shader Never Hits A Stale Pipeline
+    vertex_shader: u64,
+    fragment_shader: u64,
+}
+
+fn shader_content_hash(shader: &CompiledShader) -> u64
+{
+    let mut hasher = DefaultHasher::new();
+    shader.spirv.hash(&mut hasher);
+    shader.entry_point.hash(&mut hasher);
+    hasher.finish()
+}
+
+//Returns The Existing Pipeline For A Desc It Has Seen With The Same Shaders, Render Pass And Layout
+//Owns Every Pipeline It Hands Out, Callers Take One Back With remove Before Destroying It Themselves
+//Pipeline Layouts Are Owned Too And Live Until destroy_cache
+pub struct GraphicsPipelineCache
+{
+    pipelines: HashMap<GraphicsPipelineKey, vk::Pipeline>,
+    layouts: HashMap<PipelineLayoutDesc, (vk::PipelineLayout, Vec<vk::DescriptorSetLayout>)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl GraphicsPipelineCache
+{
+    pub fn new() -> GraphicsPipelineCache
+    {
+        GraphicsPipelineCache{
+            pipelines: HashMap::new(),
+            layouts: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    //Set Layouts Stay Owned By layout_cache
+    pub fn get_or_create_layout(&mut self, device: &ash::Device, layout_cache: &mut DescriptorLayoutCache, layout_desc: &PipelineLayoutDesc) -> (vk::PipelineLayout, Vec<vk::DescriptorSetLayout>)
+    {
+        self.layouts
+            .entry(layout_desc.clone())
+            .or_insert_with(|| layout_desc.create_pipeline_layout(device, layout_cache))
+            .clone()
+    }
+
+    //pipeline_layout Should Come From get_or_create_layout, A Fresh Handle Per Call Never Hits
+    pub fn get_or_create(
+        &mut self
+        , device: &ash::Device
+        , pipeline_cache: vk::PipelineCache
+        , desc: &GraphicsPipelineDesc
+        , pipeline_layout: vk::PipelineLayout
+        , render_pass: vk::RenderPass
+        , vertex_shader: &CompiledShader
+        , fragment_shader: &CompiledShader) -> Result<vk::Pipeline, GraphicsPipelineError>
+    {
+        let key = GraphicsPipelineKey{
+            state: GraphicsPipelineState::new(desc),
+            render_pass,
+            pipeline_layout,
+            vertex_shader: shader_content_hash(vertex_shader),
+            fragment_shader: shader_content_hash(fragment_shader),
+        };
+        if let Some(&pipeline) = self.pipelines.get(&key)
+        {
+            self.hits += 1;
+            return Ok(pipeline);
+        }
+
+        self.misses += 1;
+        let pipeline = desc.create_pipeline(device, pipeline_cache, pipeline_layout, render_pass, vertex_shader, fragment_shader)?;
+        self.pipelines.insert(key, pipeline);
+        Ok(pipeline)
+    }
+
+    //Hands Ownership Back, e.g. To Defer Its Destruction, Returns False If The Cache Did Not Hold It
+    pub fn remove(&mut self, pipeline: vk::Pipeline) -> bool
+    {
+        let pipeline_count = self.pipelines.len();
+        self.pipelines.retain(|_, cached_pipeline| *cached_pipeline != pipeline);
+        self.pipelines.len() != pipeline_count
+    }
+
+    //Every Pipeline Built Against render_pass, For When The Render Pass Is About To Go
+    pub fn remove_render_pass(&mut self, render_pass: vk::RenderPass) -> Vec<vk::Pipeline>
+    {
+        let mut removed = vec![];
+        self.pipelines.retain(|key, pipeline| {
+            let keep = key.render_pass != render_pass;
+            if !keep
+            {
+                removed.push(*pipeline);
+            }
+            keep
+        });
+        removed
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.pipelines.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.pipelines.is_empty()
+    }
+
+    //(Hits, Misses) Since The Cache Was Created
+    pub fn statistics(&self) -> (u64, u64)
+    {
+        (self.hits, self.misses)
+    }
+
+    pub fn destroy_cache(&mut self, device: &ash::Device)
+    {
+        for (_, pipeline) in self.pipelines.drain()
+        {
+            unsafe
+            {
+                device.destroy_pipeline(pipeline, None);
+            }
+        }
+        for (_, (pipeline_layout, _)) in self.layouts.drain()
+        {
+            unsafe
+            {
+                device.destroy_pipeline_layout(pipeline_layout, None);
+            }
+        }
+    }
+}
+
+impl Default for GraphicsPipelineCache
+{
+    fn default() -> Self
+    {
+        GraphicsPipelineCache::new()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const TRIANGLE_JSON: &str = include_str!("../../pipelines/triangle.json");
+
+    fn blended_desc() -> GraphicsPipelineDesc
+    {
+        GraphicsPipelineDesc{
+            topology: vk::PrimitiveTopology::LINE_STRIP,
+            raster: RasterDesc{
+                cull_mode: vk::CullModeFlags::FRONT_AND_BACK,
+                depth_bias: Some(DepthBiasDesc{ constant_factor: 1.25, clamp: 0.0, slope_factor: 2.0 }),
+                ..RasterDesc::new()
+            },
+            depth_stencil: DepthStencilDesc::disabled(),
+            blend_attachments: vec![BlendAttachmentDesc::alpha_blend(), BlendAttachmentDesc{ color_write_mask: vk::ColorComponentFlags::R | vk::ColorComponentFlags::A, ..BlendAttachmentDesc::additive() }],
+            color_formats: vec![vk::Format::B8G8R8A8_SRGB, vk::Format::R16G16B16A16_SFLOAT],
+            depth_format: Some(vk::Format::D32_SFLOAT),
+            samples: vk::SampleCountFlags::TYPE_4,
+            ..GraphicsPipelineDesc::new("Blended", "shaders/blended.vert", "shaders/blended.frag")
+        }
+    }
+
+    #[test]
+    fn missing_fields_keep_the_defaults_of_new()
+    {
+        let desc = GraphicsPipelineDesc::from_json(TRIANGLE_JSON).unwrap();
+
+        assert_eq!(desc.vertex_layout.bindings, vec![VertexBindingDesc{ binding: 0, stride: 20, input_rate: vk::VertexInputRate::VERTEX }]);
+        assert_eq!(desc.vertex_layout.attributes[1], VertexAttributeDesc{ location: 1, binding: 0, format: vk::Format::R32G32B32_SFLOAT, offset: 8 });
+        assert_eq!(desc.raster, RasterDesc::new());
+        assert_eq!(desc.depth_stencil, DepthStencilDesc::depth_less_or_equal());
+        assert_eq!(desc.blend_attachments, vec![BlendAttachmentDesc::opaque()]);
+        assert_eq!(desc.samples, vk::SampleCountFlags::TYPE_1);
+        assert_eq!(desc.depth_format, None);
+
+        let minimal = GraphicsPipelineDesc::from_ron(r#"(name: "Minimal", vertex_shader: "a.vert", fragment_shader: "a.frag")"#).unwrap();
+        assert_eq!(minimal, GraphicsPipelineDesc::new("Minimal", "a.vert", "a.frag"));
+    }
+
+    #[test]
+    fn json_and_ron_round_trip()
+    {
+        let desc = blended_desc();
+        assert_eq!(GraphicsPipelineDesc::from_json(&desc.to_json()).unwrap(), desc);
+        assert_eq!(GraphicsPipelineDesc::from_ron(&desc.to_ron()).unwrap(), desc);
+        assert!(desc.to_json().contains("\"FRONT_AND_BACK\""));
+        assert!(desc.to_json().contains("\"RA\""));
+    }
+
+    #[test]
+    fn descs_built_in_code_are_range_checked()
+    {
+        let unknown_topology = GraphicsPipelineDesc{ topology: vk::PrimitiveTopology::from_raw(999), ..blended_desc() };
+        assert!(matches!(unknown_topology.validate(), Err(GraphicsPipelineError::InvalidField{ field, .. }) if field == "topology"));
+
+        let unknown_stencil_op = GraphicsPipelineDesc{
+            depth_stencil: DepthStencilDesc{ back: StencilFaceDesc{ pass_op: vk::StencilOp::from_raw(8), ..StencilFaceDesc::keep() }, ..DepthStencilDesc::disabled() },
+            ..blended_desc()
+        };
+        assert!(matches!(unknown_stencil_op.validate(), Err(GraphicsPipelineError::InvalidField{ field, .. }) if field == "depth_stencil.back.pass_op"));
+
+        let too_many_samples = GraphicsPipelineDesc{ samples: vk::SampleCountFlags::from_raw(128), ..blended_desc() };
+        assert!(matches!(too_many_samples.validate(), Err(GraphicsPipelineError::InvalidField{ field, .. }) if field == "samples"));
+
+        let desc = blended_desc();
+        assert!(desc.validate().is_ok());
+        assert!(desc.validate_samples(vk::SampleCountFlags::TYPE_1 | vk::SampleCountFlags::TYPE_4).is_ok());
+        assert!(desc.validate_samples(vk::SampleCountFlags::TYPE_1 | vk::SampleCountFlags::TYPE_2).is_err());
+    }
+
+    #[test]
+    fn cache_state_ignores_labels()
+    {
+        let desc = blended_desc();
+        let renamed = GraphicsPipelineDesc{
+            name: "Renamed".to_owned(),
+            vertex_shader: "shaders/renamed.vert".to_owned(),
+            ..desc.clone()
+        };
+        assert_ne!(desc, renamed);
+        assert_eq!(GraphicsPipelineState::new(&desc), GraphicsPipelineState::new(&renamed));
+
+        let retargeted = GraphicsPipelineDesc{ samples: vk::SampleCountFlags::TYPE_1, ..desc.clone() };
+        assert_ne!(GraphicsPipelineState::new(&desc), GraphicsPipelineState::new(&retargeted));
+    }
+
+    #[test]
+    fn vk_enums_accept_raw_numbers()
+    {
+        let desc = GraphicsPipelineDesc::from_json(r#"{
+            "name": "Raw", "vertex_shader": "a.vert", "fragment_shader": "a.frag",
+            "topology": "3", "color_formats": ["1000156000"], "depth_format": "126"
+        }"#).unwrap();
+
+        assert_eq!(desc.topology, vk::PrimitiveTopology::TRIANGLE_LIST);
+        assert_eq!(desc.color_formats, vec![vk::Format::from_raw(1000156000)]);
+        assert_eq!(desc.depth_format, Some(vk::Format::D32_SFLOAT));
+    }
+
+    #[test]
+    fn malformed_descs_are_rejected()
+    {
+        let malformed = [
+            r#"{ "vertex_shader": "a.vert", "fragment_shader": "a.frag" }"#,
+            r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "colour_formats": [] }"#,
+            r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "raster": { "wireframe": true } }"#,
+            r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "topology": "TRIANGLES" }"#,
+            r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "raster": { "cull_mode": "SIDEWAYS" } }"#,
+            r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "blend_attachments": [{ "color_write_mask": "RGBX" }] }"#,
+        ];
+        for text in malformed
+        {
+            assert!(matches!(GraphicsPipelineDesc::from_json(text), Err(GraphicsPipelineError::Json{ .. })), "{}", text);
+        }
+
+        let out_of_range = [
+            (r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "topology": "999" }"#, "topology"),
+            (r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "raster": { "polygon_mode": "-3" } }"#, "raster.polygon_mode"),
+            (r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "samples": 128 }"#, "samples"),
+            (r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "samples": 3 }"#, "samples"),
+            (r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "blend_attachments": [{ "color_blend_op": "77" }] }"#, "blend_attachments[0].color_blend_op"),
+        ];
+        for (text, expected_field) in out_of_range
+        {
+            match GraphicsPipelineDesc::from_json(text)
+            {
+                Err(GraphicsPipelineError::Json{ field, .. }) => assert_eq!(field, expected_field),
+                result => panic!("{} Loaded As {:?}", text, result),
+            }
+        }
+        match GraphicsPipelineDesc::from_ron(r#"(name: "A", vertex_shader: "a.vert", fragment_shader: "a.frag", raster: (front_face: "5"))"#)
+        {
+            Err(GraphicsPipelineError::Ron{ field, .. }) => assert_eq!(field, "raster.front_face"),
+            result => panic!("RON Loaded As {:?}", result),
+        }
+
+        let invalid = r#"{ "name": "A", "vertex_shader": "a.vert", "fragment_shader": "a.frag", "blend_attachments": [] }"#;
+        assert!(matches!(GraphicsPipelineDesc::from_json(invalid), Err(GraphicsPipelineError::Invalid(_))));
+        assert!(matches!(GraphicsPipelineDesc::from_ron("(name: \"A\")"), Err(GraphicsPipelineError::Ron{ .. })));
+    }
+}
@@ -0,0 +1,527 @@
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::fmt;
+
+use ash::vk::{self, Handle};
+use gpu_allocator::vulkan;
+
+//Heaps Above This Fraction Of Their Budget Get A Console Warning
+pub const DEFAULT_BUDGET_WARNING_THRESHOLD: f32 = 0.9;
+pub const MEMORY_BUDGET_CHECK_INTERVAL: u64 = 120;
+
+//gpu_allocator 0.22 Keeps Its Block List Private, These Mirror The Block Sizes It Carves Sub Allocations From
+const ALLOCATOR_DEVICE_BLOCK_SIZE: u64 = 256 * 1024 * 1024;
+const ALLOCATOR_HOST_BLOCK_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MemoryCategory
+{
+    Geometry,
+    Uniform,
+    Storage,
+    Staging,
+    Texture,
+    RenderTarget,
+    Other,
+}
+
+impl MemoryCategory
+{
+    pub fn from_buffer_usage(usage: vk::BufferUsageFlags, location: gpu_allocator::MemoryLocation) -> MemoryCategory
+    {
+        if usage.intersects(vk::BufferUsageFlags::VERTEX_BUFFER | vk::BufferUsageFlags::INDEX_BUFFER)
+        {
+            MemoryCategory::Geometry
+        }
+        else if usage.contains(vk::BufferUsageFlags::UNIFORM_BUFFER)
+        {
+            MemoryCategory::Uniform
+        }
+        else if usage.contains(vk::BufferUsageFlags::STORAGE_BUFFER)
+        {
+            MemoryCategory::Storage
+        }
+        else if location != gpu_allocator::MemoryLocation::GpuOnly && usage.contains(vk::BufferUsageFlags::TRANSFER_SRC)
+        {
+            MemoryCategory::Staging
+        }
+        else
+        {
+            MemoryCategory::Other
+        }
+    }
+
+    pub fn from_image_usage(usage: vk::ImageUsageFlags) -> MemoryCategory
+    {
+        if usage.intersects(vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT)
+        {
+            MemoryCategory::RenderTarget
+        }
+        else
+        {
+            MemoryCategory::Texture
+        }
+    }
+}
+
+struct AllocationRecord
+{
+    name: String,
+    category: MemoryCategory,
+    size: u64,
+    //Size Of The VkDeviceMemory Block This Allocation Lives In
+    block_size: u64,
+}
+
+//Dedicated And Oversized Allocations Get A Block Of Their Own, Everything Else Shares A Default Sized Block
+fn allocator_block_size(location: gpu_allocator::MemoryLocation, size: u64, is_dedicated: bool) -> u64
+{
+    let shared_block_size = if location == gpu_allocator::MemoryLocation::GpuOnly { ALLOCATOR_DEVICE_BLOCK_SIZE } else { ALLOCATOR_HOST_BLOCK_SIZE };
+    if is_dedicated || size > shared_block_size { size } else { shared_block_size }
+}
+
+fn allocation_key(allocation: &vulkan::Allocation) -> (u64, u64)
+{
+    (unsafe { allocation.memory() }.as_raw(), allocation.offset())
+}
+
+//Owns The gpu_allocator Allocator And Records A Name And Category For Everything It Hands Out,
+//gpu_allocator Does Not Expose Its Own Bookkeeping
+pub struct TrackedAllocator
+{
+    allocator: vulkan::Allocator,
+    //Keyed By (VkDeviceMemory, Offset)
+    records: BTreeMap<(u64, u64), AllocationRecord>,
+}
+
+impl TrackedAllocator
+{
+    pub fn new(allocator: vulkan::Allocator) -> TrackedAllocator
+    {
+        TrackedAllocator{
+            allocator,
+            records: BTreeMap::new(),
+        }
+    }
+
+    pub fn allocate(&mut self, desc: &vulkan::AllocationCreateDesc, category: MemoryCategory) -> gpu_allocator::Result<vulkan::Allocation>
+    {
+        let allocation = self.allocator.allocate(desc)?;
+        self.records.insert(allocation_key(&allocation), AllocationRecord{
+            name: desc.name.to_owned(),
+            category,
+            size: allocation.size(),
+            block_size: allocator_block_size(desc.location, allocation.size(), allocation.is_dedicated()),
+        });
+        Ok(allocation)
+    }
+
+    pub fn free(&mut self, allocation: vulkan::Allocation) -> gpu_allocator::Result<()>
+    {
+        if !allocation.is_null()
+        {
+            self.records.remove(&allocation_key(&allocation));
+        }
+        self.allocator.free(allocation)
+    }
+
+    pub fn tracked_bytes(&self) -> u64
+    {
+        self.records.values().map(|record| record.size).sum()
+    }
+
+    pub fn tracked_count(&self) -> usize
+    {
+        self.records.len()
+    }
+
+    //Allocations Still Tracked, Largest First, Call After Everything Should Have Been Freed
+    pub fn leaked_allocations(&self) -> Vec<(String, MemoryCategory, u64)>
+    {
+        let mut leaks: Vec<(String, MemoryCategory, u64)> = self.records
+            .values()
+            .map(|record| (record.name.clone(), record.category, record.size))
+            .collect();
+        leaks.sort_by(|a, b| b.2.cmp(&a.2));
+        leaks
+    }
+
+    pub fn print_leak_report(&self)
+    {
+        let leaks = self.leaked_allocations();
+        if leaks.is_empty()
+        {
+            return;
+        }
+
+        let leaked_bytes: u64 = leaks.iter().map(|leak| leak.2).sum();
+        println!("GPU Memory Leaks At Shutdown: {} Allocations, {}", leaks.len(), format_bytes(leaked_bytes));
+        for (name, category, size) in leaks.iter()
+        {
+            println!("\t{} ({:?}): {}", name, category, format_bytes(*size));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HeapBudget
+{
+    pub heap_index: u32,
+    pub flags: vk::MemoryHeapFlags,
+    pub heap_size: u64,
+    //Without VK_EXT_memory_budget The Budget Falls Back To The Heap Size And Usage Is Unknown (0)
+    pub budget: u64,
+    pub usage: u64,
+}
+
+impl HeapBudget
+{
+    pub fn usage_ratio(&self) -> f32
+    {
+        if self.budget == 0 { 0.0 } else { self.usage as f32 / self.budget as f32 }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AllocationGroupStats
+{
+    pub label: String,
+    pub count: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryBlockStats
+{
+    pub memory: vk::DeviceMemory,
+    pub block_size: u64,
+    pub used_bytes: u64,
+    pub allocation_count: usize,
+}
+
+impl MemoryBlockStats
+{
+    pub fn free_bytes(&self) -> u64
+    {
+        self.block_size.saturating_sub(self.used_bytes)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MemoryReport
+{
+    pub budget_supported: bool,
+    pub heaps: Vec<HeapBudget>,
+    pub blocks: Vec<MemoryBlockStats>,
+    pub by_category: Vec<AllocationGroupStats>,
+    pub by_name: Vec<AllocationGroupStats>,
+    pub tracked_bytes: u64,
+    pub tracked_count: usize,
+}
+
+impl MemoryReport
+{
+    //Free Bytes Inside Allocated Blocks Over Their Total Size, None Before Anything Is Allocated
+    pub fn fragmentation_ratio(&self) -> Option<f32>
+    {
+        let reserved_bytes: u64 = self.blocks.iter().map(|block| block.block_size).sum();
+        if reserved_bytes == 0
+        {
+            return None;
+        }
+        let free_bytes: u64 = self.blocks.iter().map(|block| block.free_bytes()).sum();
+        Some(free_bytes as f32 / reserved_bytes as f32)
+    }
+}
+
+impl fmt::Display for MemoryReport
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        writeln!(f, "GPU Memory Report ({} Allocations, {})", self.tracked_count, format_bytes(self.tracked_bytes))?;
+        for heap in self.heaps.iter()
+        {
+            let heap_kind = if heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL) { "Device Local" } else { "Host" };
+            if self.budget_supported
+            {
+                writeln!(f, "\tHeap {} ({}): {} / {} Budget ({:.1}%), Heap Size {}",
+                    heap.heap_index, heap_kind, format_bytes(heap.usage), format_bytes(heap.budget), heap.usage_ratio() * 100.0, format_bytes(heap.heap_size))?;
+            }
+            else
+            {
+                writeln!(f, "\tHeap {} ({}): Heap Size {}, Budget Unavailable", heap.heap_index, heap_kind, format_bytes(heap.heap_size))?;
+            }
+        }
+        if let Some(fragmentation_ratio) = self.fragmentation_ratio()
+        {
+            let reserved_bytes: u64 = self.blocks.iter().map(|block| block.block_size).sum();
+            writeln!(f, "\t{} Memory Blocks, {} Reserved, {:.1}% Free Inside Blocks", self.blocks.len(), format_bytes(reserved_bytes), fragmentation_ratio * 100.0)?;
+        }
+        writeln!(f, "\tBy Category:")?;
+        for group in self.by_category.iter()
+        {
+            writeln!(f, "\t\t{:<16} {:>5} x {}", group.label, group.count, format_bytes(group.bytes))?;
+        }
+        writeln!(f, "\tBy Name:")?;
+        for group in self.by_name.iter()
+        {
+            writeln!(f, "\t\t{:<32} {:>5} x {}", group.label, group.count, format_bytes(group.bytes))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String
+{
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut amount = bytes as f64;
+    let mut unit = 0;
+    while amount >= 1024.0 && unit + 1 < UNITS.len()
+    {
+        amount /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.2} {}", amount, UNITS[unit]) }
+}
+
+fn group_allocations<F: Fn(&AllocationRecord) -> String>(records: &BTreeMap<(u64, u64), AllocationRecord>, label_of: F) -> Vec<AllocationGroupStats>
+{
+    let mut groups: BTreeMap<String, AllocationGroupStats> = BTreeMap::new();
+    for record in records.values()
+    {
+        let label = label_of(record);
+        let group = groups.entry(label.clone()).or_insert(AllocationGroupStats{ label, count: 0, bytes: 0 });
+        group.count += 1;
+        group.bytes += record.size;
+    }
+    let mut groups: Vec<AllocationGroupStats> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    groups
+}
+
+//One Entry Per VkDeviceMemory, Emptiest First
+fn block_stats(records: &BTreeMap<(u64, u64), AllocationRecord>) -> Vec<MemoryBlockStats>
+{
+    let mut blocks: BTreeMap<u64, MemoryBlockStats> = BTreeMap::new();
+    for ((memory, _), record) in records.iter()
+    {
+        let block = blocks.entry(*memory).or_insert(MemoryBlockStats{
+            memory: vk::DeviceMemory::from_raw(*memory),
+            block_size: record.block_size,
+            used_bytes: 0,
+            allocation_count: 0,
+        });
+        block.used_bytes += record.size;
+        block.allocation_count += 1;
+    }
+    let mut blocks: Vec<MemoryBlockStats> = blocks.into_values().collect();
+    blocks.sort_by_key(|block| std::cmp::Reverse(block.free_bytes()));
+    blocks
+}
+
+pub fn is_memory_budget_supported(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> bool
+{
+    let available_extensions = unsafe {
+        instance
+            .enumerate_device_extension_properties(physical_device)
+            .expect("Failed To Enumerate Device Extension Properties!")
+    };
+    available_extensions.iter().any(|extension| {
+        let extension_name = unsafe { CStr::from_ptr(extension.extension_name.as_ptr()) };
+        extension_name == vk::ExtMemoryBudgetFn::name()
+    })
+}
+
+//Queries Per Heap Budgets And Builds Reports, Warns Once Per Heap Each Time Usage Crosses warning_threshold
+pub struct MemoryBudgetTracker
+{
+    physical_device: vk::PhysicalDevice,
+    budget_supported: bool,
+    pub warning_threshold: f32,
+    warned_heaps: Vec<bool>,
+}
+
+impl MemoryBudgetTracker
+{
+    //budget_supported Must Only Be True If VK_EXT_memory_budget Was Enabled On The Device
+    pub fn new(physical_device: vk::PhysicalDevice, budget_supported: bool) -> MemoryBudgetTracker
+    {
+        MemoryBudgetTracker{
+            physical_device,
+            budget_supported,
+            warning_threshold: DEFAULT_BUDGET_WARNING_THRESHOLD,
+            warned_heaps: vec![],
+        }
+    }
+
+    pub fn budget_supported(&self) -> bool
+    {
+        self.budget_supported
+    }
+
+    pub fn query_heap_budgets(&self, instance: &ash::Instance) -> Vec<HeapBudget>
+    {
+        let mut budget_properties = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+        let mut memory_properties = vk::PhysicalDeviceMemoryProperties2::default();
+        if self.budget_supported
+        {
+            memory_properties.p_next = &mut budget_properties as *mut _ as *mut std::ffi::c_void;
+        }
+        unsafe
+        {
+            instance.get_physical_device_memory_properties2(self.physical_device, &mut memory_properties);
+        }
+
+        let heap_count = memory_properties.memory_properties.memory_heap_count as usize;
+        memory_properties.memory_properties.memory_heaps[..heap_count]
+            .iter()
+            .enumerate()
+            .map(|(heap_index, heap)| HeapBudget{
+                heap_index: heap_index as u32,
+                flags: heap.flags,
+                heap_size: heap.size,
+                budget: if self.budget_supported { budget_properties.heap_budget[heap_index] } else { heap.size },
+                usage: if self.budget_supported { budget_properties.heap_usage[heap_index] } else { 0 },
+            })
+            .collect()
+    }
+
+    pub fn report(&self, instance: &ash::Instance, allocator: &TrackedAllocator) -> MemoryReport
+    {
+        MemoryReport{
+            budget_supported: self.budget_supported,
+            heaps: self.query_heap_budgets(instance),
+            blocks: block_stats(&allocator.records),
+            by_category: group_allocations(&allocator.records, |record| format!("{:?}", record.category)),
+            by_name: group_allocations(&allocator.records, |record| record.name.clone()),
+            tracked_bytes: allocator.tracked_bytes(),
+            tracked_count: allocator.tracked_count(),
+        }
+    }
+
+    //Returns The Heaps That Newly Crossed The Threshold, Each Is Also Printed
+    pub fn check_budgets(&mut self, instance: &ash::Instance) -> Vec<HeapBudget>
+    {
+        if !self.budget_supported
+        {
+            return vec![];
+        }
+
+        let heaps = self.query_heap_budgets(instance);
+        self.heaps_newly_over_threshold(&heaps)
+    }
+
+    //A Heap Warns Once When It Crosses The Threshold And Re-Arms After Dropping Back Below It
+    fn heaps_newly_over_threshold(&mut self, heaps: &[HeapBudget]) -> Vec<HeapBudget>
+    {
+        self.warned_heaps.resize(heaps.len(), false);
+
+        let mut newly_over = vec![];
+        for heap in heaps.iter()
+        {
+            let is_over = heap.usage_ratio() >= self.warning_threshold;
+            let warned = &mut self.warned_heaps[heap.heap_index as usize];
+            if is_over && !*warned
+            {
+                println!("Warning: GPU Memory Heap {} At {:.1}% Of Budget ({} / {})",
+                    heap.heap_index, heap.usage_ratio() * 100.0, format_bytes(heap.usage), format_bytes(heap.budget));
+                newly_over.push(*heap);
+            }
+            *warned = is_over;
+        }
+        newly_over
+    }
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn record(name: &str, category: MemoryCategory, size: u64, block_size: u64) -> AllocationRecord
+    {
+        AllocationRecord{ name: name.to_string(), category, size, block_size }
+    }
+
+    fn heap(heap_index: u32, usage: u64) -> HeapBudget
+    {
+        HeapBudget{ heap_index, flags: vk::MemoryHeapFlags::DEVICE_LOCAL, heap_size: 1000, budget: 1000, usage }
+    }
+
+    #[test]
+    fn bytes_format_with_binary_units()
+    {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.00 KiB");
+        assert_eq!(format_bytes(1536), "1.50 KiB");
+        assert_eq!(format_bytes(256 * 1024 * 1024), "256.00 MiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.00 GiB");
+        //GiB Is The Largest Unit
+        assert_eq!(format_bytes(2048 * 1024 * 1024 * 1024), "2048.00 GiB");
+    }
+
+    #[test]
+    fn budget_warnings_fire_once_and_re_arm_below_the_threshold()
+    {
+        let mut tracker = MemoryBudgetTracker::new(vk::PhysicalDevice::null(), true);
+        let newly_over = |tracker: &mut MemoryBudgetTracker, usages: &[u64]| -> Vec<u32> {
+            let heaps: Vec<HeapBudget> = usages.iter().enumerate().map(|(heap_index, usage)| heap(heap_index as u32, *usage)).collect();
+            tracker.heaps_newly_over_threshold(&heaps).iter().map(|heap| heap.heap_index).collect()
+        };
+
+        assert!(newly_over(&mut tracker, &[100, 899]).is_empty());
+        assert_eq!(newly_over(&mut tracker, &[100, 900]), vec![1]);
+        assert!(newly_over(&mut tracker, &[100, 950]).is_empty());
+        assert_eq!(newly_over(&mut tracker, &[950, 950]), vec![0]);
+
+        //Dropping Below Re-Arms Only That Heap
+        assert!(newly_over(&mut tracker, &[950, 500]).is_empty());
+        assert_eq!(newly_over(&mut tracker, &[950, 990]), vec![1]);
+    }
+
+    #[test]
+    fn allocations_group_by_label_largest_first()
+    {
+        let mut records = BTreeMap::new();
+        records.insert((1, 0), record("Vertices", MemoryCategory::Geometry, 300, 1000));
+        records.insert((1, 300), record("Indices", MemoryCategory::Geometry, 100, 1000));
+        records.insert((2, 0), record("Albedo", MemoryCategory::Texture, 500, 500));
+        records.insert((3, 0), record("Vertices", MemoryCategory::Geometry, 50, 1000));
+
+        let by_category = group_allocations(&records, |record| format!("{:?}", record.category));
+        let by_category: Vec<(&str, usize, u64)> = by_category.iter().map(|group| (group.label.as_str(), group.count, group.bytes)).collect();
+        assert_eq!(by_category, vec![("Texture", 1, 500), ("Geometry", 3, 450)]);
+
+        let by_name = group_allocations(&records, |record| record.name.clone());
+        let by_name: Vec<(&str, usize, u64)> = by_name.iter().map(|group| (group.label.as_str(), group.count, group.bytes)).collect();
+        assert_eq!(by_name, vec![("Albedo", 1, 500), ("Vertices", 2, 350), ("Indices", 1, 100)]);
+    }
+
+    #[test]
+    fn fragmentation_counts_free_space_inside_blocks()
+    {
+        let mut records = BTreeMap::new();
+        records.insert((1, 0), record("Vertices", MemoryCategory::Geometry, 300, 1000));
+        records.insert((1, 512), record("Indices", MemoryCategory::Geometry, 100, 1000));
+        records.insert((2, 0), record("Albedo", MemoryCategory::Texture, 500, 500));
+
+        let blocks = block_stats(&records);
+        assert_eq!(blocks.iter().map(|block| (block.memory.as_raw(), block.used_bytes, block.allocation_count, block.free_bytes())).collect::<Vec<_>>(), vec![(1, 400, 2, 600), (2, 500, 1, 0)]);
+
+        let mut report = MemoryReport{ budget_supported: false, heaps: vec![], blocks, by_category: vec![], by_name: vec![], tracked_bytes: 900, tracked_count: 3 };
+        assert_eq!(report.fragmentation_ratio(), Some(600.0 / 1500.0));
+        report.blocks.clear();
+        assert_eq!(report.fragmentation_ratio(), None);
+    }
+
+    #[test]
+    fn block_sizes_follow_the_allocator_defaults()
+    {
+        assert_eq!(allocator_block_size(gpu_allocator::MemoryLocation::GpuOnly, 1024, false), ALLOCATOR_DEVICE_BLOCK_SIZE);
+        assert_eq!(allocator_block_size(gpu_allocator::MemoryLocation::CpuToGpu, 1024, false), ALLOCATOR_HOST_BLOCK_SIZE);
+        assert_eq!(allocator_block_size(gpu_allocator::MemoryLocation::GpuOnly, 1024, true), 1024);
+        assert_eq!(allocator_block_size(gpu_allocator::MemoryLocation::GpuToCpu, ALLOCATOR_HOST_BLOCK_SIZE + 1, false), ALLOCATOR_HOST_BLOCK_SIZE + 1);
+    }
+}
@@ -8,4 +8,25 @@ pub mod swapchain;
 pub mod file_system;
 pub mod commandbuffers;
 pub mod gpubuffer;
-pub mod window_context;
\ No newline at end of file
+pub mod gpuimage;
+pub mod format;
+pub mod window_context;
+pub mod texture_loader;
+pub mod sampler_cache;
+pub mod descriptors;
+pub mod bindless;
+pub mod shader_reflection;
+pub mod push_constants;
+pub mod uniform_ring;
+pub mod upload_manager;
+pub mod deletion_queue;
+pub mod memory_report;
+pub mod shader_include;
+pub mod shader_error;
+pub mod shader_hot_reload;
+pub mod shader_cache;
+pub mod shader_permutation;
+pub mod pipeline_cache;
+pub mod graphics_pipeline;
+pub mod compute;
+pub mod gpu_profiler;
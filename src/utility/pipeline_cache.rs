@@ -0,0 +1,233 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ash::vk;
+
+//VkPipelineCacheHeaderVersionOne: Header Size, Header Version, Vendor ID, Device ID, Cache UUID
+const PIPELINE_CACHE_HEADER_SIZE: usize = 4 + 4 + 4 + 4 + vk::UUID_SIZE;
+
+//What The Running Device Expects To Find At The Start Of Cache Data It Produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineCacheIdentity
+{
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub pipeline_cache_uuid: [u8; vk::UUID_SIZE],
+}
+
+impl PipelineCacheIdentity
+{
+    pub fn new(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> PipelineCacheIdentity
+    {
+        let properties = unsafe { instance.get_physical_device_properties(physical_device) };
+        PipelineCacheIdentity{
+            vendor_id: properties.vendor_id,
+            device_id: properties.device_id,
+            pipeline_cache_uuid: properties.pipeline_cache_uuid,
+        }
+    }
+
+    //Drivers Are Meant To Reject Foreign Data Themselves, Not All Of Them Do So Gracefully
+    pub fn validate(&self, data: &[u8]) -> Result<(), String>
+    {
+        if data.len() < PIPELINE_CACHE_HEADER_SIZE
+        {
+            return Err(format!("{} Bytes Is Too Short For A Header", data.len()));
+        }
+        let read_u32 = |offset: usize| u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+
+        let header_size = read_u32(0) as usize;
+        if header_size < PIPELINE_CACHE_HEADER_SIZE || header_size > data.len()
+        {
+            return Err(format!("Bad Header Size {}", header_size));
+        }
+        let header_version = read_u32(4) as i32;
+        if header_version != vk::PipelineCacheHeaderVersion::ONE.as_raw()
+        {
+            return Err(format!("Unsupported Header Version {}", header_version));
+        }
+        let (vendor_id, device_id) = (read_u32(8), read_u32(12));
+        if vendor_id != self.vendor_id || device_id != self.device_id
+        {
+            return Err(format!("Written By Device {:04x}:{:04x}, Running On {:04x}:{:04x}", vendor_id, device_id, self.vendor_id, self.device_id));
+        }
+        if data[16..16 + vk::UUID_SIZE] != self.pipeline_cache_uuid
+        {
+            return Err("Pipeline Cache UUID Mismatch, Probably A Different Driver Version".to_owned());
+        }
+        Ok(())
+    }
+}
+
+//One VkPipelineCache Shared By All Pipeline Creation, Seeded From Disk At Startup And Written Back At Shutdown
+pub struct PipelineCache
+{
+    cache: vk::PipelineCache,
+    path: PathBuf,
+    identity: PipelineCacheIdentity,
+}
+
+impl PipelineCache
+{
+    //Missing, Corrupt Or Foreign Files Start An Empty Cache Instead Of Failing
+    pub fn new<P: AsRef<Path>>(instance: &ash::Instance, physical_device: vk::PhysicalDevice, device: &ash::Device, path: P) -> PipelineCache
+    {
+        let path = path.as_ref().to_path_buf();
+        let identity = PipelineCacheIdentity::new(instance, physical_device);
+
+        let initial_data = match fs::read(&path)
+        {
+            Ok(data) => match identity.validate(&data)
+            {
+                Ok(()) => data,
+                Err(reason) =>
+                {
+                    println!("Discarding Pipeline Cache {}: {}", path.display(), reason);
+                    vec![]
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => vec![],
+            Err(error) =>
+            {
+                println!("Failed To Read Pipeline Cache {}: {}", path.display(), error);
+                vec![]
+            }
+        };
+
+        let cache = match PipelineCache::create_cache(device, &initial_data)
+        {
+            Ok(cache) => cache,
+            //Passed Our Checks But The Driver Still Refused It
+            Err(error) if !initial_data.is_empty() =>
+            {
+                println!("Driver Rejected Pipeline Cache {}: {}", path.display(), error);
+                PipelineCache::create_cache(device, &[]).expect("Failed To Create Pipeline Cache!")
+            }
+            Err(error) => panic!("Failed To Create Pipeline Cache: {}", error),
+        };
+
+        PipelineCache{
+            cache,
+            path,
+            identity,
+        }
+    }
+
+    fn create_cache(device: &ash::Device, initial_data: &[u8]) -> ash::prelude::VkResult<vk::PipelineCache>
+    {
+        let pipeline_cache_create_info = vk::PipelineCacheCreateInfo::builder()
+            .initial_data(initial_data);
+        unsafe { device.create_pipeline_cache(&pipeline_cache_create_info, None) }
+    }
+
+    pub fn handle(&self) -> vk::PipelineCache
+    {
+        self.cache
+    }
+
+    pub fn path(&self) -> &Path
+    {
+        &self.path
+    }
+
+    //Written To A Temporary File And Renamed, So A Crash Mid Write Leaves The Previous File Intact
+    pub fn save(&self, device: &ash::Device) -> io::Result<()>
+    {
+        let data = unsafe { device.get_pipeline_cache_data(self.cache) }
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, format!("Failed To Get Pipeline Cache Data: {}", error)))?;
+        if let Err(reason) = self.identity.validate(&data)
+        {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Driver Returned Unexpected Pipeline Cache Data: {}", reason)));
+        }
+
+        if let Some(directory) = self.path.parent().filter(|directory| !directory.as_os_str().is_empty())
+        {
+            fs::create_dir_all(directory)?;
+        }
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(format!(".{}.tmp", std::process::id()));
+        fs::write(&temp_path, &data)?;
+        fs::rename(&temp_path, &self.path).map_err(|error| {
+            let _ = fs::remove_file(&temp_path);
+            error
+        })
+    }
+
+    pub fn destroy_cache(&mut self, device: &ash::Device)
+    {
+        unsafe
+        {
+            device.destroy_pipeline_cache(self.cache, None);
+        }
+        self.cache = vk::PipelineCache::null();
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn test_identity() -> PipelineCacheIdentity
+    {
+        PipelineCacheIdentity{
+            vendor_id: 0x10de,
+            device_id: 0x2484,
+            pipeline_cache_uuid: [7; vk::UUID_SIZE],
+        }
+    }
+
+    //A Header As The Identity's Driver Would Write It, Followed By Some Opaque Cache Data
+    fn header_for(identity: &PipelineCacheIdentity) -> Vec<u8>
+    {
+        let mut data = vec![];
+        data.extend_from_slice(&(PIPELINE_CACHE_HEADER_SIZE as u32).to_le_bytes());
+        data.extend_from_slice(&(vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32).to_le_bytes());
+        data.extend_from_slice(&identity.vendor_id.to_le_bytes());
+        data.extend_from_slice(&identity.device_id.to_le_bytes());
+        data.extend_from_slice(&identity.pipeline_cache_uuid);
+        data.extend_from_slice(&[0xab; 64]);
+        data
+    }
+
+    #[test]
+    fn matching_header_is_accepted()
+    {
+        let identity = test_identity();
+        assert_eq!(identity.validate(&header_for(&identity)), Ok(()));
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected()
+    {
+        let identity = test_identity();
+        let data = header_for(&identity);
+        assert!(identity.validate(&data[..PIPELINE_CACHE_HEADER_SIZE - 1]).is_err());
+
+        let mut short_header_size = data.clone();
+        short_header_size[0..4].copy_from_slice(&4u32.to_le_bytes());
+        assert!(identity.validate(&short_header_size).is_err());
+
+        let mut oversized_header_size = data.clone();
+        oversized_header_size[0..4].copy_from_slice(&(data.len() as u32 + 1).to_le_bytes());
+        assert!(identity.validate(&oversized_header_size).is_err());
+
+        let mut bad_version = data.clone();
+        bad_version[4..8].copy_from_slice(&2u32.to_le_bytes());
+        assert!(identity.validate(&bad_version).is_err());
+    }
+
+    #[test]
+    fn foreign_devices_and_drivers_are_rejected()
+    {
+        let identity = test_identity();
+        let other_device = PipelineCacheIdentity{ device_id: 0x1234, ..identity };
+        let other_vendor = PipelineCacheIdentity{ vendor_id: 0x1002, ..identity };
+        let other_driver = PipelineCacheIdentity{ pipeline_cache_uuid: [8; vk::UUID_SIZE], ..identity };
+
+        assert!(identity.validate(&header_for(&other_device)).is_err());
+        assert!(identity.validate(&header_for(&other_vendor)).is_err());
+        assert!(identity.validate(&header_for(&other_driver)).is_err());
+    }
+}
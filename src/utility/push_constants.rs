@@ -0,0 +1,201 @@
+use std::marker::PhantomData;
+
+use ash::vk;
+use bytemuck::Pod;
+
+use crate::utility::shader_reflection::*;
+
+//Binds A #[repr(C)] Pod Struct To One Push Constant Range Of A Pipeline Layout
+//The Struct Must Match The Shader Block Layout, validate Checks Its Size And Stages Against Reflection
+#[derive(Debug, Clone, Copy)]
+pub struct PushConstantRange<T: Pod>
+{
+    pub stage_flags: vk::ShaderStageFlags,
+    pub offset: u32,
+    _data: PhantomData<T>,
+}
+
+impl<T: Pod> PushConstantRange<T>
+{
+    pub const fn new(stage_flags: vk::ShaderStageFlags, offset: u32) -> PushConstantRange<T>
+    {
+        PushConstantRange{
+            stage_flags,
+            offset,
+            _data: PhantomData,
+        }
+    }
+
+    pub fn size(&self) -> u32
+    {
+        std::mem::size_of::<T>() as u32
+    }
+
+    pub fn vk_range(&self) -> vk::PushConstantRange
+    {
+        vk::PushConstantRange{
+            stage_flags: self.stage_flags,
+            offset: self.offset,
+            size: self.size(),
+        }
+    }
+
+    pub fn validate(&self, device_limits: &vk::PhysicalDeviceLimits, reflections: &[&ShaderReflection]) -> Result<(), ShaderReflectionError>
+    {
+        let mut mismatches = vec![];
+        let range_end = match self.offset.checked_add(self.size())
+        {
+            Some(range_end) => range_end,
+            None => return Err(ShaderReflectionError::InterfaceMismatch(vec![
+                format!("Push Constant Range At Offset {} With Size {} Overflows", self.offset, self.size())])),
+        };
+
+        if self.offset % 4 != 0 || self.size() % 4 != 0
+        {
+            mismatches.push(format!("Push Constant Range {}..{} Is Not 4 Byte Aligned", self.offset, range_end));
+        }
+        if range_end > device_limits.max_push_constants_size
+        {
+            mismatches.push(format!("Push Constant Range {}..{} Exceeds maxPushConstantsSize Of {}", self.offset, range_end, device_limits.max_push_constants_size));
+        }
+
+        for reflection in reflections.iter()
+        {
+            let block = match &reflection.push_constant_block
+            {
+                Some(block) => block,
+                None => continue,
+            };
+
+            if !self.stage_flags.contains(reflection.stage)
+            {
+                mismatches.push(format!("{:?} Reads Push Constants ({}), But The Range Only Covers {:?}", reflection.stage, block.name, self.stage_flags));
+            }
+            let block_end = block.offset.saturating_add(block.size);
+            if block.offset < self.offset || block_end > range_end
+            {
+                mismatches.push(format!("{:?} Reads Push Constant Bytes {}..{} ({}), But The Rust Type Only Covers {}..{}",
+                    reflection.stage, block.offset, block_end, block.name, self.offset, range_end));
+            }
+        }
+
+        if mismatches.is_empty()
+        {
+            Ok(())
+        }
+        else
+        {
+            Err(ShaderReflectionError::InterfaceMismatch(mismatches))
+        }
+    }
+
+    //Unsafe Like The Other cmd_* Helpers, Nothing Here Can Check The Command Buffer Or Layout
+    /// # Safety
+    /// command_buffer Must Be In The Recording State And pipeline_layout Must Have Been Created With vk_range,
+    /// Otherwise The Push Is Undefined Behaviour Per vkCmdPushConstants
+    pub unsafe fn cmd_push(&self, device: &ash::Device, command_buffer: vk::CommandBuffer, pipeline_layout: vk::PipelineLayout, data: &T)
+    {
+        device.cmd_push_constants(command_buffer, pipeline_layout, self.stage_flags, self.offset, bytemuck::bytes_of(data));
+    }
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+    struct TestPushConstants
+    {
+        transform: [f32; 4],
+        color: [f32; 4],
+    }
+
+    fn limits(max_push_constants_size: u32) -> vk::PhysicalDeviceLimits
+    {
+        vk::PhysicalDeviceLimits{ max_push_constants_size, ..Default::default() }
+    }
+
+    fn reflection(stage: vk::ShaderStageFlags, offset: u32, size: u32) -> ShaderReflection
+    {
+        ShaderReflection{
+            stage,
+            entry_point: "main".to_string(),
+            descriptor_bindings: vec![],
+            push_constant_block: Some(ReflectedPushConstantBlock{ name: "PushConstants".to_string(), offset, size }),
+            inputs: vec![],
+            outputs: vec![],
+            workgroup_size: None,
+        }
+    }
+
+    fn mismatch_count(result: Result<(), ShaderReflectionError>) -> usize
+    {
+        match result
+        {
+            Ok(()) => 0,
+            Err(ShaderReflectionError::InterfaceMismatch(mismatches)) => mismatches.len(),
+            Err(error) => panic!("Unexpected Error {}", error),
+        }
+    }
+
+    #[test]
+    fn matching_range_validates()
+    {
+        let range = PushConstantRange::<TestPushConstants>::new(vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT, 0);
+        let vertex = reflection(vk::ShaderStageFlags::VERTEX, 0, 16);
+        let fragment = reflection(vk::ShaderStageFlags::FRAGMENT, 16, 16);
+        assert!(range.validate(&limits(128), &[&vertex, &fragment]).is_ok());
+        let vk_range = range.vk_range();
+        assert_eq!((vk_range.stage_flags, vk_range.offset, vk_range.size), (vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT, 0, 32));
+    }
+
+    #[test]
+    fn ranges_past_the_device_limit_are_rejected()
+    {
+        let range = PushConstantRange::<TestPushConstants>::new(vk::ShaderStageFlags::VERTEX, 112);
+        assert_eq!(mismatch_count(range.validate(&limits(128), &[])), 1);
+        assert!(range.validate(&limits(256), &[]).is_ok());
+
+        let overflowing = PushConstantRange::<TestPushConstants>::new(vk::ShaderStageFlags::VERTEX, u32::MAX - 4);
+        assert_eq!(mismatch_count(overflowing.validate(&limits(128), &[])), 1);
+    }
+
+    #[test]
+    fn ranges_must_be_four_byte_aligned()
+    {
+        let range = PushConstantRange::<TestPushConstants>::new(vk::ShaderStageFlags::VERTEX, 2);
+        assert_eq!(mismatch_count(range.validate(&limits(128), &[])), 1);
+
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+        struct OddSized
+        {
+            bytes: [u8; 6],
+        }
+        let odd_sized = PushConstantRange::<OddSized>::new(vk::ShaderStageFlags::VERTEX, 0);
+        assert_eq!(mismatch_count(odd_sized.validate(&limits(128), &[])), 1);
+    }
+
+    #[test]
+    fn stages_reading_outside_the_range_are_rejected()
+    {
+        let range = PushConstantRange::<TestPushConstants>::new(vk::ShaderStageFlags::VERTEX, 16);
+
+        //Stage Not Covered By The Range
+        let fragment = reflection(vk::ShaderStageFlags::FRAGMENT, 16, 16);
+        assert_eq!(mismatch_count(range.validate(&limits(128), &[&fragment])), 1);
+
+        //Block Starts Before Or Ends After The Range
+        let before = reflection(vk::ShaderStageFlags::VERTEX, 0, 32);
+        let after = reflection(vk::ShaderStageFlags::VERTEX, 32, 32);
+        assert_eq!(mismatch_count(range.validate(&limits(128), &[&before])), 1);
+        assert_eq!(mismatch_count(range.validate(&limits(128), &[&after])), 1);
+
+        //Both Problems In One Stage Are Reported Together
+        let fragment_after = reflection(vk::ShaderStageFlags::FRAGMENT, 40, 16);
+        assert_eq!(mismatch_count(range.validate(&limits(128), &[&fragment_after])), 2);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use ash::vk;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SamplerDesc
+{
+    pub mag_filter: vk::Filter,
+    pub min_filter: vk::Filter,
+    pub mipmap_mode: vk::SamplerMipmapMode,
+    pub address_mode_u: vk::SamplerAddressMode,
+    pub address_mode_v: vk::SamplerAddressMode,
+    pub address_mode_w: vk::SamplerAddressMode,
+    //None Disables Anisotropic Filtering, Values Are Clamped To maxSamplerAnisotropy
+    pub max_anisotropy: Option<f32>,
+    //Some(..) Makes This A Comparison (Shadow) Sampler
+    pub compare_op: Option<vk::CompareOp>,
+    pub mip_lod_bias: f32,
+    pub min_lod: f32,
+    pub max_lod: f32,
+    pub border_color: vk::BorderColor,
+}
+
+impl SamplerDesc
+{
+    pub fn linear_repeat() -> SamplerDesc
+    {
+        SamplerDesc{
+            mag_filter: vk::Filter::LINEAR,
+            min_filter: vk::Filter::LINEAR,
+            mipmap_mode: vk::SamplerMipmapMode::LINEAR,
+            address_mode_u: vk::SamplerAddressMode::REPEAT,
+            address_mode_v: vk::SamplerAddressMode::REPEAT,
+            address_mode_w: vk::SamplerAddressMode::REPEAT,
+            max_anisotropy: None,
+            compare_op: None,
+            mip_lod_bias: 0.0,
+            min_lod: 0.0,
+            max_lod: vk::LOD_CLAMP_NONE,
+            border_color: vk::BorderColor::FLOAT_TRANSPARENT_BLACK,
+        }
+    }
+
+    pub fn linear_clamp() -> SamplerDesc
+    {
+        SamplerDesc{
+            address_mode_u: vk::SamplerAddressMode::CLAMP_TO_EDGE,
+            address_mode_v: vk::SamplerAddressMode::CLAMP_TO_EDGE,
+            address_mode_w: vk::SamplerAddressMode::CLAMP_TO_EDGE,
+            ..SamplerDesc::linear_repeat()
+        }
+    }
+
+    pub fn nearest_clamp() -> SamplerDesc
+    {
+        SamplerDesc{
+            mag_filter: vk::Filter::NEAREST,
+            min_filter: vk::Filter::NEAREST,
+            mipmap_mode: vk::SamplerMipmapMode::NEAREST,
+            ..SamplerDesc::linear_clamp()
+        }
+    }
+
+    pub fn anisotropic_repeat(max_anisotropy: f32) -> SamplerDesc
+    {
+        SamplerDesc{
+            max_anisotropy: Some(max_anisotropy),
+            ..SamplerDesc::linear_repeat()
+        }
+    }
+
+    //Depth Comparison With White Border, So Samples Outside The Shadow Map Are Lit
+    pub fn shadow_compare() -> SamplerDesc
+    {
+        SamplerDesc{
+            address_mode_u: vk::SamplerAddressMode::CLAMP_TO_BORDER,
+            address_mode_v: vk::SamplerAddressMode::CLAMP_TO_BORDER,
+            address_mode_w: vk::SamplerAddressMode::CLAMP_TO_BORDER,
+            compare_op: Some(vk::CompareOp::LESS_OR_EQUAL),
+            border_color: vk::BorderColor::FLOAT_OPAQUE_WHITE,
+            ..SamplerDesc::linear_repeat()
+        }
+    }
+}
+
+//Floats Are Compared By Bit Pattern, So Descriptions Built The Same Way Always Share A Sampler
+impl PartialEq for SamplerDesc
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        self.mag_filter == other.mag_filter
+            && self.min_filter == other.min_filter
+            && self.mipmap_mode == other.mipmap_mode
+            && self.address_mode_u == other.address_mode_u
+            && self.address_mode_v == other.address_mode_v
+            && self.address_mode_w == other.address_mode_w
+            && self.max_anisotropy.map(f32::to_bits) == other.max_anisotropy.map(f32::to_bits)
+            && self.compare_op == other.compare_op
+            && self.mip_lod_bias.to_bits() == other.mip_lod_bias.to_bits()
+            && self.min_lod.to_bits() == other.min_lod.to_bits()
+            && self.max_lod.to_bits() == other.max_lod.to_bits()
+            && self.border_color == other.border_color
+    }
+}
+
+impl Eq for SamplerDesc {}
+
+impl Hash for SamplerDesc
+{
+    fn hash<H: Hasher>(&self, state: &mut H)
+    {
+        self.mag_filter.hash(state);
+        self.min_filter.hash(state);
+        self.mipmap_mode.hash(state);
+        self.address_mode_u.hash(state);
+        self.address_mode_v.hash(state);
+        self.address_mode_w.hash(state);
+        self.max_anisotropy.map(f32::to_bits).hash(state);
+        self.compare_op.hash(state);
+        self.mip_lod_bias.to_bits().hash(state);
+        self.min_lod.to_bits().hash(state);
+        self.max_lod.to_bits().hash(state);
+        self.border_color.hash(state);
+    }
+}
+
+pub struct SamplerCache
+{
+    samplers: HashMap<SamplerDesc, vk::Sampler>,
+    anisotropy_supported: bool,
+    max_sampler_anisotropy: f32,
+    max_sampler_allocation_count: u32,
+}
+
+impl SamplerCache
+{
+    pub fn new(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> SamplerCache
+    {
+        let (device_properties, device_features) = unsafe {
+            (
+                instance.get_physical_device_properties(physical_device),
+                instance.get_physical_device_features(physical_device),
+            )
+        };
+
+        SamplerCache{
+            samplers: HashMap::new(),
+            //create_logical_device Enables samplerAnisotropy Whenever The Device Supports It
+            anisotropy_supported: device_features.sampler_anisotropy == vk::TRUE,
+            max_sampler_anisotropy: device_properties.limits.max_sampler_anisotropy,
+            max_sampler_allocation_count: device_properties.limits.max_sampler_allocation_count,
+        }
+    }
+
+    //Returns The Shared Sampler For desc, Creating It On First Use
+    //Fails With ERROR_TOO_MANY_OBJECTS Instead Of Going Past maxSamplerAllocationCount
+    pub fn get_sampler(&mut self, device: &ash::Device, desc: &SamplerDesc) -> Result<vk::Sampler, vk::Result>
+    {
+        let desc = self.clamp_to_limits(desc);
+
+        if let Some(&sampler) = self.samplers.get(&desc)
+        {
+            return Ok(sampler);
+        }
+
+        if self.samplers.len() as u32 >= self.max_sampler_allocation_count
+        {
+            println!("Sampler Allocation Limit ({}) Reached, Cannot Create {:?}", self.max_sampler_allocation_count, desc);
+            return Err(vk::Result::ERROR_TOO_MANY_OBJECTS);
+        }
+
+        let sampler_create_info = vk::SamplerCreateInfo::builder()
+            .mag_filter(desc.mag_filter)
+            .min_filter(desc.min_filter)
+            .mipmap_mode(desc.mipmap_mode)
+            .address_mode_u(desc.address_mode_u)
+            .address_mode_v(desc.address_mode_v)
+            .address_mode_w(desc.address_mode_w)
+            .mip_lod_bias(desc.mip_lod_bias)
+            .anisotropy_enable(desc.max_anisotropy.is_some())
+            .max_anisotropy(desc.max_anisotropy.unwrap_or(1.0))
+            .compare_enable(desc.compare_op.is_some())
+            .compare_op(desc.compare_op.unwrap_or(vk::CompareOp::ALWAYS))
+            .min_lod(desc.min_lod)
+            .max_lod(desc.max_lod)
+            .border_color(desc.border_color)
+            .unnormalized_coordinates(false);
+
+        let sampler = unsafe{
+            device
+                .create_sampler(&sampler_create_info, None)?
+        };
+
+        self.samplers.insert(desc, sampler);
+        Ok(sampler)
+    }
+
+    pub fn sampler_count(&self) -> usize
+    {
+        self.samplers.len()
+    }
+
+    pub fn destroy_cache(&mut self, device: &ash::Device)
+    {
+        unsafe
+        {
+            for &sampler in self.samplers.values()
+            {
+                device.destroy_sampler(sampler, None);
+            }
+        }
+        self.samplers.clear();
+    }
+
+    //Requests That Only Differ Beyond What The Device Can Do Map To The Same Sampler
+    fn clamp_to_limits(&self, desc: &SamplerDesc) -> SamplerDesc
+    {
+        let max_anisotropy = match desc.max_anisotropy
+        {
+            Some(anisotropy) if self.anisotropy_supported && anisotropy > 1.0 => Some(anisotropy.min(self.max_sampler_anisotropy)),
+            _ => None,
+        };
+
+        SamplerDesc{
+            max_anisotropy,
+            ..*desc
+        }
+    }
+}
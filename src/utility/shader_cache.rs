@@ -0,0 +1,304 @@
+use std::fs;
+use std::hash::Hasher;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use twox_hash::XxHash64;
+
+use crate::utility::{file_system::*, shader_include::ShaderIncludeResolver, shader_error::ShaderError, shader_reflection::SPIRV_MAGIC_NUMBER};
+
+const CACHE_FILE_MAGIC: [u8; 4] = *b"SPVC";
+//Bump When The File Layout Or The Key Contents Change
+const CACHE_FORMAT_VERSION: u32 = 1;
+//Magic, Version, Key, Word Count, CRC32
+const CACHE_HEADER_SIZE: usize = 4 + 4 + 16 + 4 + 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShaderCacheKey
+{
+    hash: [u64; 2],
+}
+
+impl ShaderCacheKey
+{
+    //Two Differently Seeded 64 Bit Hashes, Each Part Is Length Prefixed So Boundaries Cannot Shift
+    pub fn new<P: AsRef<[u8]>>(key_parts: &[P]) -> ShaderCacheKey
+    {
+        let mut hash = [0u64; 2];
+        for (seed, hash) in hash.iter_mut().enumerate()
+        {
+            let mut hasher = XxHash64::with_seed(seed as u64);
+            for key_part in key_parts.iter()
+            {
+                let key_part = key_part.as_ref();
+                hasher.write_u64(key_part.len() as u64);
+                hasher.write(key_part);
+            }
+            *hash = hasher.finish();
+        }
+        ShaderCacheKey{ hash }
+    }
+
+    pub fn file_name(&self) -> String
+    {
+        format!("{:016x}{:016x}.spvc", self.hash[0], self.hash[1])
+    }
+
+    fn to_bytes(self) -> [u8; 16]
+    {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.hash[0].to_le_bytes());
+        bytes[8..].copy_from_slice(&self.hash[1].to_le_bytes());
+        bytes
+    }
+}
+
+//One File Per Compiled Shader, Written To A Temporary File And Renamed Into Place So Readers Never See Half A Blob
+//Entries That Fail Validation Are Deleted And Treated As A Miss
+pub struct ShaderCache
+{
+    directory: PathBuf,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    temp_file_counter: AtomicU64,
+}
+
+impl ShaderCache
+{
+    pub fn new<P: AsRef<Path>>(directory: P) -> io::Result<ShaderCache>
+    {
+        fs::create_dir_all(directory.as_ref())?;
+        Ok(ShaderCache{
+            directory: directory.as_ref().to_path_buf(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            temp_file_counter: AtomicU64::new(0),
+        })
+    }
+
+    pub fn directory(&self) -> &Path
+    {
+        &self.directory
+    }
+
+    //(Hits, Misses) Since The Cache Was Opened
+    pub fn statistics(&self) -> (u64, u64)
+    {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+
+    pub fn load(&self, key: &ShaderCacheKey) -> Option<Vec<u32>>
+    {
+        let entry_path = self.directory.join(key.file_name());
+        let bytes = match fs::read(&entry_path)
+        {
+            Ok(bytes) => bytes,
+            Err(_) =>
+            {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+
+        match ShaderCache::decode_entry(key, &bytes)
+        {
+            Ok(spirv) =>
+            {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(spirv)
+            }
+            Err(reason) =>
+            {
+                println!("Discarding Corrupt Shader Cache Entry {}: {}", entry_path.display(), reason);
+                let _ = fs::remove_file(&entry_path);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    pub fn store(&self, key: &ShaderCacheKey, spirv: &[u32]) -> io::Result<()>
+    {
+        let bytes = ShaderCache::encode_entry(key, spirv);
+
+        //Unique Per Process And Call, So Concurrent Writers Of The Same Key Never Share A Temporary File
+        let temp_path = self.directory.join(format!("{}.{}.{}.tmp",
+            key.file_name(),
+            std::process::id(),
+            self.temp_file_counter.fetch_add(1, Ordering::Relaxed)));
+        fs::write(&temp_path, &bytes)?;
+        fs::rename(&temp_path, self.directory.join(key.file_name())).map_err(|error| {
+            let _ = fs::remove_file(&temp_path);
+            error
+        })
+    }
+
+    //Removes Every Entry, Leftover Temporary Files Included
+    pub fn clear(&self) -> io::Result<()>
+    {
+        for entry in fs::read_dir(&self.directory)?
+        {
+            let entry_path = entry?.path();
+            let is_cache_file = entry_path
+                .file_name()
+                .map_or(false, |name| {
+                    let name = name.to_string_lossy();
+                    name.ends_with(".spvc") || name.ends_with(".tmp")
+                });
+            if is_cache_file
+            {
+                fs::remove_file(entry_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_entry(key: &ShaderCacheKey, spirv: &[u32]) -> Vec<u8>
+    {
+        let payload: Vec<u8> = spirv.iter().flat_map(|word| word.to_le_bytes()).collect();
+
+        let mut bytes = Vec::with_capacity(CACHE_HEADER_SIZE + payload.len());
+        bytes.extend_from_slice(&CACHE_FILE_MAGIC);
+        bytes.extend_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&key.to_bytes());
+        bytes.extend_from_slice(&(spirv.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        bytes
+    }
+
+    fn decode_entry(key: &ShaderCacheKey, bytes: &[u8]) -> Result<Vec<u32>, &'static str>
+    {
+        if bytes.len() < CACHE_HEADER_SIZE
+        {
+            return Err("Truncated Header");
+        }
+        let read_u32 = |offset: usize| u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+
+        if bytes[0..4] != CACHE_FILE_MAGIC
+        {
+            return Err("Bad Magic");
+        }
+        if read_u32(4) != CACHE_FORMAT_VERSION
+        {
+            return Err("Unsupported Format Version");
+        }
+        if bytes[8..24] != key.to_bytes()
+        {
+            return Err("Key Mismatch");
+        }
+        let word_count = read_u32(24) as usize;
+        let payload = &bytes[CACHE_HEADER_SIZE..];
+        if payload.len() != word_count * 4
+        {
+            return Err("Payload Size Mismatch");
+        }
+        if crc32fast::hash(payload) != read_u32(28)
+        {
+            return Err("Checksum Mismatch");
+        }
+
+        let spirv: Vec<u32> = payload
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+        if spirv.first() != Some(&SPIRV_MAGIC_NUMBER)
+        {
+            return Err("Not SPIR-V");
+        }
+        Ok(spirv)
+    }
+}
+
+//Compiles Every Listed Shader Into The Cache Ahead Of Time, Meant For Build Scripts And Offline Tools
+//Shaders Already Cached Are Only Preprocessed, Failures Do Not Stop The Remaining Shaders
+pub fn precompile_shaders(
+    shaders: &[(&str, ShaderType)]
+    , include_resolver: &ShaderIncludeResolver
+    , shader_cache: &ShaderCache) -> Vec<(String, ShaderType, Result<(), ShaderError>)>
+{
+    shaders
+        .iter()
+        .map(|&(path, shader_type)| {
+            let result = compile_shader_file(path, shader_type, include_resolver, Some(shader_cache)).map(|_| ());
+            (path.to_owned(), shader_type, result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const TEST_SPIRV: [u32; 6] = [SPIRV_MAGIC_NUMBER, 0x0001_0000, 0, 8, 0, (1 << 16) | 253];
+
+    fn test_key() -> ShaderCacheKey
+    {
+        ShaderCacheKey::new(&["test.vert", "main"])
+    }
+
+    #[test]
+    fn entries_round_trip()
+    {
+        let bytes = ShaderCache::encode_entry(&test_key(), &TEST_SPIRV);
+        assert_eq!(ShaderCache::decode_entry(&test_key(), &bytes), Ok(TEST_SPIRV.to_vec()));
+    }
+
+    #[test]
+    fn bad_headers_are_rejected()
+    {
+        let bytes = ShaderCache::encode_entry(&test_key(), &TEST_SPIRV);
+        assert_eq!(ShaderCache::decode_entry(&test_key(), &bytes[..CACHE_HEADER_SIZE - 1]), Err("Truncated Header"));
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 0xff;
+        assert_eq!(ShaderCache::decode_entry(&test_key(), &bad_magic), Err("Bad Magic"));
+
+        let mut bad_version = bytes.clone();
+        bad_version[4..8].copy_from_slice(&(CACHE_FORMAT_VERSION + 1).to_le_bytes());
+        assert_eq!(ShaderCache::decode_entry(&test_key(), &bad_version), Err("Unsupported Format Version"));
+    }
+
+    #[test]
+    fn truncated_or_corrupt_payloads_are_rejected()
+    {
+        let bytes = ShaderCache::encode_entry(&test_key(), &TEST_SPIRV);
+        assert_eq!(ShaderCache::decode_entry(&test_key(), &bytes[..bytes.len() - 4]), Err("Payload Size Mismatch"));
+        assert_eq!(ShaderCache::decode_entry(&test_key(), &bytes[..bytes.len() - 1]), Err("Payload Size Mismatch"));
+
+        let mut flipped = bytes.clone();
+        *flipped.last_mut().unwrap() ^= 0x01;
+        assert_eq!(ShaderCache::decode_entry(&test_key(), &flipped), Err("Checksum Mismatch"));
+
+        let not_spirv = ShaderCache::encode_entry(&test_key(), &[0xdead_beef, 0]);
+        assert_eq!(ShaderCache::decode_entry(&test_key(), &not_spirv), Err("Not SPIR-V"));
+    }
+
+    #[test]
+    fn entries_for_another_key_are_rejected()
+    {
+        let bytes = ShaderCache::encode_entry(&test_key(), &TEST_SPIRV);
+        let other_key = ShaderCacheKey::new(&["test.vert", "other"]);
+        assert_eq!(ShaderCache::decode_entry(&other_key, &bytes), Err("Key Mismatch"));
+    }
+
+    #[test]
+    fn corrupt_files_count_as_misses_and_are_removed()
+    {
+        let directory = std::env::temp_dir().join(format!("shader_cache_test_{}", std::process::id()));
+        let shader_cache = ShaderCache::new(&directory).unwrap();
+        shader_cache.store(&test_key(), &TEST_SPIRV).unwrap();
+        assert_eq!(shader_cache.load(&test_key()), Some(TEST_SPIRV.to_vec()));
+
+        let entry_path = directory.join(test_key().file_name());
+        let bytes = fs::read(&entry_path).unwrap();
+        fs::write(&entry_path, &bytes[..bytes.len() - 4]).unwrap();
+        assert_eq!(shader_cache.load(&test_key()), None);
+        assert!(!entry_path.exists());
+        assert_eq!(shader_cache.statistics(), (1, 1));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,299 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity
+{
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShaderDiagnostic
+{
+    pub severity: DiagnosticSeverity,
+    pub file: String,
+    //1 Based, Like The Compiler Prints Them
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+    //The Offending Line, When The File Could Be Read Back
+    pub source_line: Option<String>,
+}
+
+impl fmt::Display for ShaderDiagnostic
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let severity = match self.severity
+        {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+        };
+        write!(f, "{}", self.file)?;
+        if let Some(line) = self.line
+        {
+            write!(f, ":{}", line)?;
+        }
+        if let Some(column) = self.column
+        {
+            write!(f, ":{}", column)?;
+        }
+        write!(f, ": {}: {}", severity, self.message)?;
+
+        if let (Some(line), Some(source_line)) = (self.line, &self.source_line)
+        {
+            let gutter = line.to_string();
+            write!(f, "\n {} | {}", gutter, source_line)?;
+            if let Some(column) = self.column
+            {
+                write!(f, "\n {} | {}^", " ".repeat(gutter.len()), " ".repeat(column.saturating_sub(1) as usize))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ShaderError
+{
+    Io{ path: String, error: io::Error },
+    CompilerUnavailable(String),
+    Compilation{ path: String, diagnostics: Vec<ShaderDiagnostic> },
+    Internal{ path: String, message: String },
+    //A Precompiled .spv That Is Not Usable As The Requested Stage
+    InvalidBinary{ path: String, message: String },
+    //A Keyword Set That Does Not Fit The Shader's Declared Keywords
+    InvalidPermutation{ path: String, message: String },
+    //A ShaderDesc That Cannot Be Compiled As Given
+    InvalidDesc{ path: String, message: String },
+}
+
+impl ShaderError
+{
+    pub fn diagnostics(&self) -> &[ShaderDiagnostic]
+    {
+        match self
+        {
+            ShaderError::Compilation{ diagnostics, .. } => diagnostics,
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for ShaderError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            ShaderError::Io{ path, error } => write!(f, "Failed To Read Shader {}: {}", path, error),
+            ShaderError::CompilerUnavailable(message) => write!(f, "Shader Compiler Unavailable: {}", message),
+            ShaderError::Compilation{ path, diagnostics } =>
+            {
+                write!(f, "Failed To Compile Shader {}", path)?;
+                for diagnostic in diagnostics.iter()
+                {
+                    write!(f, "\n{}", diagnostic)?;
+                }
+                Ok(())
+            }
+            ShaderError::Internal{ path, message } => write!(f, "Internal Shader Compiler Error In {}: {}", path, message),
+            ShaderError::InvalidBinary{ path, message } => write!(f, "Invalid SPIR-V Binary {}: {}", path, message),
+            ShaderError::InvalidPermutation{ path, message } => write!(f, "Invalid Permutation Of {}: {}", path, message),
+            ShaderError::InvalidDesc{ path, message } => write!(f, "Invalid Shader Desc For {}: {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for ShaderError
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        match self
+        {
+            ShaderError::Io{ error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+//Splits "file:line:column" Or "file:line" From The Right, So Drive Letters In Windows Paths Survive
+fn parse_location(location: &str) -> (String, Option<u32>, Option<u32>)
+{
+    let mut file = location;
+    let mut numbers = vec![];
+    while numbers.len() < 2
+    {
+        match file.rsplit_once(':')
+        {
+            Some((rest, number)) if !number.is_empty() && number.trim().chars().all(|c| c.is_ascii_digit()) =>
+            {
+                numbers.push(number.trim().parse::<u32>().ok());
+                file = rest;
+            }
+            _ => break,
+        }
+    }
+    match numbers.as_slice()
+    {
+        [column, line] => (file.to_owned(), *line, *column),
+        [line] => (file.to_owned(), *line, None),
+        _ => (file.to_owned(), None, None),
+    }
+}
+
+//Turns shaderc's Error Or Warning Text Into Diagnostics, read_source Fetches A File So The Offending Line Can Be Shown
+pub fn parse_shaderc_messages<F>(messages: &str, fallback_file: &str, read_source: F) -> Vec<ShaderDiagnostic>
+    where F: Fn(&str) -> Option<String>
+{
+    let mut diagnostics: Vec<ShaderDiagnostic> = vec![];
+    for message_line in messages.lines()
+    {
+        let trimmed = message_line.trim();
+        if trimmed.is_empty()
+            || trimmed.ends_with("error generated.") || trimmed.ends_with("errors generated.")
+            || trimmed.ends_with("warning generated.") || trimmed.ends_with("warnings generated.")
+        {
+            continue;
+        }
+
+        let marker = [(": error: ", DiagnosticSeverity::Error), (": warning: ", DiagnosticSeverity::Warning)]
+            .iter()
+            .filter_map(|&(marker, severity)| message_line.find(marker).map(|index| (index, marker, severity)))
+            .min_by_key(|&(index, _, _)| index);
+
+        match marker
+        {
+            Some((index, marker, severity)) =>
+            {
+                let (file, line, column) = parse_location(&message_line[..index]);
+                let file = if file.is_empty() { fallback_file.to_owned() } else { file };
+                let source_line = line.and_then(|line| {
+                    read_source(&file)
+                        .and_then(|source| source.lines().nth(line.saturating_sub(1) as usize).map(|source_line| source_line.to_owned()))
+                });
+                diagnostics.push(ShaderDiagnostic{
+                    severity,
+                    file,
+                    line,
+                    column,
+                    message: message_line[index + marker.len()..].trim().to_owned(),
+                    source_line,
+                });
+            }
+            //Continuation Of The Previous Message, Or Text With No Location At All
+            None => match diagnostics.last_mut()
+            {
+                Some(previous) =>
+                {
+                    previous.message.push('\n');
+                    previous.message.push_str(trimmed);
+                }
+                None => diagnostics.push(ShaderDiagnostic{
+                    severity: DiagnosticSeverity::Error,
+                    file: fallback_file.to_owned(),
+                    line: None,
+                    column: None,
+                    message: trimmed.to_owned(),
+                    source_line: None,
+                }),
+            },
+        }
+    }
+    diagnostics
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn no_source(_: &str) -> Option<String>
+    {
+        None
+    }
+
+    #[test]
+    fn locations_split_from_the_right()
+    {
+        assert_eq!(parse_location("shaders/triangle.frag:12"), ("shaders/triangle.frag".to_string(), Some(12), None));
+        assert_eq!(parse_location("shaders/triangle.frag:12:7"), ("shaders/triangle.frag".to_string(), Some(12), Some(7)));
+        assert_eq!(parse_location("shaders/triangle.frag"), ("shaders/triangle.frag".to_string(), None, None));
+        assert_eq!(parse_location(r"C:\shaders\triangle.frag:12"), (r"C:\shaders\triangle.frag".to_string(), Some(12), None));
+        assert_eq!(parse_location(r"C:\shaders\triangle.frag:12:7"), (r"C:\shaders\triangle.frag".to_string(), Some(12), Some(7)));
+        assert_eq!(parse_location(r"C:\shaders\triangle.frag"), (r"C:\shaders\triangle.frag".to_string(), None, None));
+        assert_eq!(parse_location(""), (String::new(), None, None));
+    }
+
+    #[test]
+    fn file_and_line_messages_pick_up_the_source_line()
+    {
+        let messages = "shaders/triangle.frag:2: error: 'colour' : undeclared identifier\nshaders/common.glsl:1: warning: unused variable\n";
+        let read_source = |file: &str| match file
+        {
+            "shaders/triangle.frag" => Some("#version 450\nout_color = colour;\n".to_string()),
+            _ => None,
+        };
+        let diagnostics = parse_shaderc_messages(messages, "shaders/triangle.frag", read_source);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].file, "shaders/triangle.frag");
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert_eq!(diagnostics[0].message, "'colour' : undeclared identifier");
+        assert_eq!(diagnostics[0].source_line.as_deref(), Some("out_color = colour;"));
+        assert_eq!(diagnostics[0].to_string(), "shaders/triangle.frag:2: error: 'colour' : undeclared identifier\n 2 | out_color = colour;");
+
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Warning);
+        assert_eq!(diagnostics[1].file, "shaders/common.glsl");
+        assert_eq!(diagnostics[1].source_line, None);
+    }
+
+    #[test]
+    fn messages_without_a_location_use_the_fallback_file()
+    {
+        let diagnostics = parse_shaderc_messages("shaders/triangle.frag: error: #version: missing\n", "fallback.frag", no_source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, "shaders/triangle.frag");
+        assert_eq!(diagnostics[0].line, None);
+        assert_eq!(diagnostics[0].message, "#version: missing");
+
+        let diagnostics = parse_shaderc_messages("internal compiler failure\n", "fallback.frag", no_source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].file, "fallback.frag");
+        assert_eq!(diagnostics[0].message, "internal compiler failure");
+    }
+
+    #[test]
+    fn continuation_lines_join_the_previous_message()
+    {
+        let messages = "shaders/triangle.frag:4: error: 'assign' : cannot convert from\n  'vec3' to 'vec4'\nshaders/triangle.frag:5: error: syntax error\n";
+        let diagnostics = parse_shaderc_messages(messages, "shaders/triangle.frag", no_source);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].message, "'assign' : cannot convert from\n'vec3' to 'vec4'");
+        assert_eq!(diagnostics[1].line, Some(5));
+    }
+
+    #[test]
+    fn windows_paths_keep_their_drive_letter()
+    {
+        let diagnostics = parse_shaderc_messages(r"C:\shaders\triangle.frag:3:9: error: syntax error", "fallback.frag", no_source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, r"C:\shaders\triangle.frag");
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert_eq!(diagnostics[0].column, Some(9));
+    }
+
+    #[test]
+    fn generated_summary_lines_are_dropped()
+    {
+        let messages = "shaders/triangle.frag:4: error: syntax error\nshaders/triangle.frag:9: warning: unused\n1 error generated.\n\n1 warning generated.\n2 errors generated.\n";
+        let diagnostics = parse_shaderc_messages(messages, "shaders/triangle.frag", no_source);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].message, "syntax error");
+        assert_eq!(diagnostics[1].message, "unused");
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::utility::{file_system::*, shader_include::*, shader_error::ShaderError, shader_cache::ShaderCache};
+
+pub const SHADER_WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+pub struct ShaderReloadResult
+{
+    pub path: String,
+    pub shader_type: ShaderType,
+    pub result: Result<CompiledShader, ShaderError>,
+}
+
+//Polls Watched Shaders And Everything They Include On A Background Thread,
+//Recompiles The Ones Affected By A Change There, And Queues The Results For The Render Thread
+pub struct ShaderHotReloader
+{
+    watched_shaders: Arc<Mutex<Vec<(String, ShaderType)>>>,
+    results: mpsc::Receiver<Vec<ShaderReloadResult>>,
+    stop_requested: Arc<AtomicBool>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl ShaderHotReloader
+{
+    pub fn new(include_resolver: Arc<ShaderIncludeResolver>, shader_cache: Option<Arc<ShaderCache>>) -> ShaderHotReloader
+    {
+        let watched_shaders: Arc<Mutex<Vec<(String, ShaderType)>>> = Arc::new(Mutex::new(vec![]));
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let (result_sender, results) = mpsc::channel();
+
+        let worker = {
+            let watched_shaders = watched_shaders.clone();
+            let stop_requested = stop_requested.clone();
+            thread::Builder::new()
+                .name("Shader Hot Reload".to_owned())
+                .spawn(move || watch_and_compile(&include_resolver, shader_cache.as_deref(), &watched_shaders, &stop_requested, &result_sender))
+                .expect("Failed To Spawn Shader Hot Reload Thread!")
+        };
+
+        ShaderHotReloader{
+            watched_shaders,
+            results,
+            stop_requested,
+            worker: Some(worker),
+        }
+    }
+
+    //The Shader Should Already Have Been Compiled Once, So Its Includes Are Known
+    pub fn watch(&self, path: &str, shader_type: ShaderType)
+    {
+        let mut watched_shaders = self.watched_shaders.lock().expect("Watched Shader List Poisoned!");
+        let path = normalize_shader_path(std::path::Path::new(path));
+        if !watched_shaders.iter().any(|(watched_path, watched_type)| *watched_path == path && *watched_type == shader_type)
+        {
+            watched_shaders.push((path, shader_type));
+        }
+    }
+
+    //Never Blocks, Call Once Per Frame Where Swapping Pipelines Is Safe
+    //Each Batch Holds Every Stage Recompiled For The Same File Change
+    pub fn poll_results(&self) -> Vec<Vec<ShaderReloadResult>>
+    {
+        self.results.try_iter().collect()
+    }
+}
+
+impl Drop for ShaderHotReloader
+{
+    fn drop(&mut self)
+    {
+        self.stop_requested.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take()
+        {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime>
+{
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+//Editors Often Save In Several Writes, So A Change Only Counts Once The Timestamp Holds Still For A Whole Poll
+#[derive(Default)]
+struct ShaderChangeTracker
+{
+    last_modified: HashMap<String, Option<SystemTime>>,
+    settling: HashSet<String>,
+}
+
+impl ShaderChangeTracker
+{
+    fn settled_changes(&mut self, observed: impl IntoIterator<Item = (String, Option<SystemTime>)>) -> Vec<String>
+    {
+        //Includes Shared By Several Roots Show Up Once Per Root
+        let observed: BTreeMap<String, Option<SystemTime>> = observed.into_iter().collect();
+        let mut changed_files = vec![];
+        for (file, modified) in observed
+        {
+            match self.last_modified.insert(file.clone(), modified)
+            {
+                //First Sighting Only Records The Timestamp
+                None => {},
+                Some(previous) if previous != modified => { self.settling.insert(file); },
+                Some(_) if self.settling.remove(&file) => changed_files.push(file),
+                Some(_) => {},
+            }
+        }
+        changed_files
+    }
+}
+
+//Every Watched Stage Whose Root Is changed Or Includes Something That Is, One Entry Per Stage Even When Stages Share A File
+fn dirty_shaders(
+    shaders: &[(String, ShaderType)]
+    , changed_files: &[String]
+    , dependents_of: impl Fn(&str) -> Vec<String>) -> Vec<(String, ShaderType)>
+{
+    let mut dirty_roots: Vec<String> = changed_files
+        .iter()
+        .flat_map(|changed_file| dependents_of(changed_file))
+        .collect();
+    dirty_roots.sort();
+    dirty_roots.dedup();
+
+    shaders
+        .iter()
+        .filter(|(root_path, _)| dirty_roots.contains(root_path))
+        .cloned()
+        .collect()
+}
+
+fn watch_and_compile(
+    include_resolver: &ShaderIncludeResolver
+    , shader_cache: Option<&ShaderCache>
+    , watched_shaders: &Mutex<Vec<(String, ShaderType)>>
+    , stop_requested: &AtomicBool
+    , result_sender: &mpsc::Sender<Vec<ShaderReloadResult>>)
+{
+    let mut change_tracker = ShaderChangeTracker::default();
+
+    while !stop_requested.load(Ordering::Relaxed)
+    {
+        thread::sleep(SHADER_WATCH_INTERVAL);
+
+        let shaders = watched_shaders.lock().expect("Watched Shader List Poisoned!").clone();
+        let observed = shaders.iter().flat_map(|(root_path, _)| {
+            let mut files = include_resolver.includes_of(root_path);
+            files.push(root_path.clone());
+            files
+        })
+        .map(|file| {
+            let modified = modified_time(&file);
+            (file, modified)
+        });
+        let changed_files = change_tracker.settled_changes(observed);
+        if changed_files.is_empty()
+        {
+            continue;
+        }
+
+        //Sent As One Batch So A Pipeline Never Gets Rebuilt With Only Half Of A Change
+        let reload_batch: Vec<ShaderReloadResult> = dirty_shaders(&shaders, &changed_files, |changed_file| include_resolver.dependents_of(changed_file))
+            .into_iter()
+            .map(|(root_path, shader_type)| {
+                let result = compile_shader_file(&root_path, shader_type, include_resolver, shader_cache);
+                ShaderReloadResult{
+                    path: root_path,
+                    shader_type,
+                    result,
+                }
+            })
+            .collect();
+        if !reload_batch.is_empty() && result_sender.send(reload_batch).is_err()
+        {
+            return;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn at(seconds: u64) -> Option<SystemTime>
+    {
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    fn observe(tracker: &mut ShaderChangeTracker, observed: &[(&str, Option<SystemTime>)]) -> Vec<String>
+    {
+        tracker.settled_changes(observed.iter().map(|(file, modified)| (file.to_string(), *modified)))
+    }
+
+    #[test]
+    fn changes_are_reported_once_the_timestamp_settles()
+    {
+        let mut tracker = ShaderChangeTracker::default();
+        assert!(observe(&mut tracker, &[("a.frag", at(1)), ("common.glsl", at(1))]).is_empty());
+        assert!(observe(&mut tracker, &[("a.frag", at(1)), ("common.glsl", at(1))]).is_empty());
+
+        //Still Being Written
+        assert!(observe(&mut tracker, &[("a.frag", at(2)), ("common.glsl", at(1))]).is_empty());
+        assert!(observe(&mut tracker, &[("a.frag", at(3)), ("common.glsl", at(1))]).is_empty());
+
+        assert_eq!(observe(&mut tracker, &[("a.frag", at(3)), ("common.glsl", at(1))]), vec!["a.frag".to_string()]);
+        assert!(observe(&mut tracker, &[("a.frag", at(3)), ("common.glsl", at(1))]).is_empty());
+    }
+
+    #[test]
+    fn a_file_seen_through_several_roots_is_reported_once()
+    {
+        let mut tracker = ShaderChangeTracker::default();
+        let shared = [("common.glsl", at(1)), ("common.glsl", at(1))];
+        observe(&mut tracker, &shared);
+        observe(&mut tracker, &[("common.glsl", at(2)), ("common.glsl", at(2))]);
+        assert_eq!(observe(&mut tracker, &[("common.glsl", at(2)), ("common.glsl", at(2))]), vec!["common.glsl".to_string()]);
+    }
+
+    #[test]
+    fn deleted_files_count_as_changed()
+    {
+        let mut tracker = ShaderChangeTracker::default();
+        observe(&mut tracker, &[("a.frag", at(1))]);
+        observe(&mut tracker, &[("a.frag", None)]);
+        assert_eq!(observe(&mut tracker, &[("a.frag", None)]), vec!["a.frag".to_string()]);
+    }
+
+    #[test]
+    fn changed_includes_dirty_every_stage_of_their_roots()
+    {
+        let shaders = vec![
+            ("shaders/triangle.glsl".to_string(), ShaderType::Vertex),
+            ("shaders/triangle.glsl".to_string(), ShaderType::Fragment),
+            ("shaders/post.frag".to_string(), ShaderType::Fragment),
+        ];
+        let dependents_of = |changed_file: &str| match changed_file
+        {
+            "shaders/common.glsl" => vec!["shaders/post.frag".to_string(), "shaders/triangle.glsl".to_string()],
+            "shaders/triangle.glsl" => vec!["shaders/triangle.glsl".to_string()],
+            _ => vec![],
+        };
+
+        assert_eq!(dirty_shaders(&shaders, &["shaders/triangle.glsl".to_string()], dependents_of), shaders[..2].to_vec());
+        assert_eq!(dirty_shaders(&shaders, &["shaders/common.glsl".to_string(), "shaders/triangle.glsl".to_string()], dependents_of), shaders);
+        assert!(dirty_shaders(&shaders, &["shaders/unrelated.glsl".to_string()], dependents_of).is_empty());
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use shaderc::{CompileOptions, IncludeType, ResolvedInclude};
+
+pub const DEFAULT_SHADER_INCLUDE_PATHS: [&str; 1] = ["shaders/include"];
+
+//Nested Includes Deeper Than This Are Treated As A Cycle
+const MAX_INCLUDE_DEPTH: usize = 32;
+
+//Lexically Resolves . And .., And Always Uses / So Disk And Virtual Paths Share One Key Space
+pub fn normalize_shader_path(path: &Path) -> String
+{
+    let mut parts: Vec<String> = vec![];
+    let mut prefix = String::new();
+    for component in path.components()
+    {
+        match component
+        {
+            Component::Prefix(drive) => prefix = drive.as_os_str().to_string_lossy().into_owned(),
+            Component::RootDir => prefix.push('/'),
+            Component::CurDir => {},
+            Component::ParentDir =>
+            {
+                if parts.last().map_or(true, |part| part == "..")
+                {
+                    parts.push("..".to_owned());
+                }
+                else
+                {
+                    parts.pop();
+                }
+            }
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+        }
+    }
+    prefix + &parts.join("/")
+}
+
+//Which Shaders Pulled In Which Files, Filled In While Compiling
+#[derive(Debug, Default)]
+pub struct ShaderDependencyGraph
+{
+    //Root Shader -> Every File It Includes, Directly Or Not
+    includes: HashMap<String, BTreeSet<String>>,
+}
+
+impl ShaderDependencyGraph
+{
+    pub fn includes_of(&self, shader_path: &str) -> Vec<String>
+    {
+        self.includes
+            .get(&normalize_shader_path(Path::new(shader_path)))
+            .map(|includes| includes.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    //Root Shaders That Need A Recompile When changed_path Changes, Including changed_path Itself If It Is A Root
+    pub fn dependents_of(&self, changed_path: &str) -> Vec<String>
+    {
+        let changed_path = normalize_shader_path(Path::new(changed_path));
+        let mut dependents: Vec<String> = self.includes
+            .iter()
+            .filter(|(root, includes)| **root == changed_path || includes.contains(&changed_path))
+            .map(|(root, _)| root.clone())
+            .collect();
+        dependents.sort();
+        dependents
+    }
+}
+
+//Resolves #include "..." Relative To The Including File Then The Search Paths, And #include <...> Against The Search Paths Only
+//Virtual Files Shadow Files On Disk With The Same Normalized Path
+pub struct ShaderIncludeResolver
+{
+    search_paths: Vec<PathBuf>,
+    virtual_files: HashMap<String, String>,
+    dependency_graph: Mutex<ShaderDependencyGraph>,
+}
+
+impl ShaderIncludeResolver
+{
+    pub fn new() -> ShaderIncludeResolver
+    {
+        ShaderIncludeResolver{
+            search_paths: vec![],
+            virtual_files: HashMap::new(),
+            dependency_graph: Mutex::new(ShaderDependencyGraph::default()),
+        }
+    }
+
+    pub fn with_default_search_paths() -> ShaderIncludeResolver
+    {
+        let mut resolver = ShaderIncludeResolver::new();
+        for search_path in DEFAULT_SHADER_INCLUDE_PATHS.iter()
+        {
+            resolver.add_search_path(search_path);
+        }
+        resolver
+    }
+
+    pub fn add_search_path<P: AsRef<Path>>(&mut self, search_path: P) -> &mut Self
+    {
+        self.search_paths.push(search_path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn search_paths(&self) -> &[PathBuf]
+    {
+        &self.search_paths
+    }
+
+    pub fn add_virtual_file<P: AsRef<Path>>(&mut self, path: P, source: &str) -> &mut Self
+    {
+        self.virtual_files.insert(normalize_shader_path(path.as_ref()), source.to_owned());
+        self
+    }
+
+    pub fn remove_virtual_file<P: AsRef<Path>>(&mut self, path: P) -> Option<String>
+    {
+        self.virtual_files.remove(&normalize_shader_path(path.as_ref()))
+    }
+
+    //Reads A Root Shader, Virtual Files First
+    pub fn load_source(&self, path: &str) -> io::Result<String>
+    {
+        match self.virtual_files.get(&normalize_shader_path(Path::new(path)))
+        {
+            Some(source) => Ok(source.clone()),
+            None => fs::read_to_string(path),
+        }
+    }
+
+    fn read_candidate(&self, candidate: &Path) -> Option<(String, String)>
+    {
+        let resolved_name = normalize_shader_path(candidate);
+        if let Some(source) = self.virtual_files.get(&resolved_name)
+        {
+            return Some((resolved_name, source.clone()));
+        }
+        fs::read_to_string(candidate)
+            .ok()
+            .map(|source| (resolved_name, source))
+    }
+
+    pub fn resolve(&self, requested_source: &str, include_type: IncludeType, requesting_source: &str, include_depth: usize) -> Result<ResolvedInclude, String>
+    {
+        if include_depth > MAX_INCLUDE_DEPTH
+        {
+            return Err(format!("Include Depth Exceeds {} While Including {} From {}, Probably An Include Cycle", MAX_INCLUDE_DEPTH, requested_source, requesting_source));
+        }
+
+        let mut candidates = vec![];
+        if let IncludeType::Relative = include_type
+        {
+            let requesting_directory = Path::new(requesting_source).parent().unwrap_or_else(|| Path::new(""));
+            candidates.push(requesting_directory.join(requested_source));
+        }
+        candidates.extend(self.search_paths.iter().map(|search_path| search_path.join(requested_source)));
+
+        candidates
+            .iter()
+            .find_map(|candidate| self.read_candidate(candidate))
+            .map(|(resolved_name, content)| ResolvedInclude{ resolved_name, content })
+            .ok_or_else(|| format!("Cannot Find Include {} Requested By {}, Searched: {}",
+                requested_source,
+                requesting_source,
+                candidates.iter().map(|candidate| normalize_shader_path(candidate)).collect::<Vec<_>>().join(", ")))
+    }
+
+    //Hooks This Resolver Into options, Every File Included While Compiling root_shader_path Is Recorded As Its Dependency
+    pub fn install<'a>(&'a self, options: &mut CompileOptions<'a>, root_shader_path: &str)
+    {
+        let root_shader_path = normalize_shader_path(Path::new(root_shader_path));
+        self.dependency_graph
+            .lock()
+            .expect("Shader Dependency Graph Poisoned!")
+            .includes
+            .insert(root_shader_path.clone(), BTreeSet::new());
+
+        options.set_include_callback(move |requested_source, include_type, requesting_source, include_depth| {
+            let resolved_include = self.resolve(requested_source, include_type, requesting_source, include_depth)?;
+            self.dependency_graph
+                .lock()
+                .expect("Shader Dependency Graph Poisoned!")
+                .includes
+                .entry(root_shader_path.clone())
+                .or_default()
+                .insert(resolved_include.resolved_name.clone());
+            Ok(resolved_include)
+        });
+    }
+
+    pub fn includes_of(&self, shader_path: &str) -> Vec<String>
+    {
+        self.dependency_graph.lock().expect("Shader Dependency Graph Poisoned!").includes_of(shader_path)
+    }
+
+    pub fn dependents_of(&self, changed_path: &str) -> Vec<String>
+    {
+        self.dependency_graph.lock().expect("Shader Dependency Graph Poisoned!").dependents_of(changed_path)
+    }
+}
+
+impl Default for ShaderIncludeResolver
+{
+    fn default() -> Self
+    {
+        ShaderIncludeResolver::with_default_search_paths()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    //Everything Lives Under vfs/, Which Does Not Exist On Disk
+    fn test_resolver() -> ShaderIncludeResolver
+    {
+        let mut resolver = ShaderIncludeResolver::new();
+        resolver
+            .add_search_path("vfs/include")
+            .add_search_path("vfs/fallback")
+            .add_virtual_file("vfs/include/common.glsl", "//Search Path Common")
+            .add_virtual_file("vfs/fallback/common.glsl", "//Fallback Common")
+            .add_virtual_file("vfs/fallback/only_in_fallback.glsl", "//Only In Fallback")
+            .add_virtual_file("vfs/lighting/common.glsl", "//Lighting Common")
+            .add_virtual_file("vfs/shared/brdf.glsl", "//Shared Brdf");
+        resolver
+    }
+
+    fn resolved_name(resolver: &ShaderIncludeResolver, requested_source: &str, include_type: IncludeType, requesting_source: &str) -> String
+    {
+        resolver
+            .resolve(requested_source, include_type, requesting_source, 1)
+            .expect("Include Should Resolve")
+            .resolved_name
+    }
+
+    #[test]
+    fn relative_includes_prefer_the_including_directory()
+    {
+        let resolver = test_resolver();
+        assert_eq!(resolved_name(&resolver, "common.glsl", IncludeType::Relative, "vfs/lighting/pbr.frag"), "vfs/lighting/common.glsl");
+        assert_eq!(resolved_name(&resolver, "../shared/brdf.glsl", IncludeType::Relative, "vfs/lighting/pbr.frag"), "vfs/shared/brdf.glsl");
+
+        let resolved_include = resolver.resolve("common.glsl", IncludeType::Relative, "vfs/lighting/pbr.frag", 1).unwrap();
+        assert_eq!(resolved_include.content, "//Lighting Common");
+    }
+
+    #[test]
+    fn standard_includes_only_use_the_search_paths()
+    {
+        let resolver = test_resolver();
+        assert_eq!(resolved_name(&resolver, "common.glsl", IncludeType::Standard, "vfs/lighting/pbr.frag"), "vfs/include/common.glsl");
+        assert!(resolver.resolve("brdf.glsl", IncludeType::Standard, "vfs/shared/main.frag", 1).is_err());
+    }
+
+    #[test]
+    fn search_paths_are_tried_in_order()
+    {
+        let resolver = test_resolver();
+        assert_eq!(resolved_name(&resolver, "common.glsl", IncludeType::Standard, "vfs/main.frag"), "vfs/include/common.glsl");
+        assert_eq!(resolved_name(&resolver, "only_in_fallback.glsl", IncludeType::Standard, "vfs/main.frag"), "vfs/fallback/only_in_fallback.glsl");
+
+        //Relative Includes Fall Back To The Search Paths When The Including Directory Has No Match
+        assert_eq!(resolved_name(&resolver, "common.glsl", IncludeType::Relative, "vfs/main.frag"), "vfs/include/common.glsl");
+    }
+
+    #[test]
+    fn missing_includes_list_every_searched_path()
+    {
+        let resolver = test_resolver();
+        let error = resolver
+            .resolve("missing.glsl", IncludeType::Relative, "vfs/lighting/pbr.frag", 1)
+            .err()
+            .expect("Missing Include Should Fail");
+        assert!(error.contains("missing.glsl"));
+        assert!(error.contains("vfs/lighting/pbr.frag"));
+        assert!(error.contains("vfs/lighting/missing.glsl, vfs/include/missing.glsl, vfs/fallback/missing.glsl"));
+    }
+
+    #[test]
+    fn include_cycles_stop_at_the_depth_limit()
+    {
+        let resolver = test_resolver();
+        assert!(resolver.resolve("common.glsl", IncludeType::Standard, "vfs/main.frag", MAX_INCLUDE_DEPTH).is_ok());
+        assert!(resolver.resolve("common.glsl", IncludeType::Standard, "vfs/main.frag", MAX_INCLUDE_DEPTH + 1).is_err());
+    }
+
+    #[test]
+    fn compiling_records_the_dependency_graph()
+    {
+        let mut resolver = test_resolver();
+        resolver
+            .add_virtual_file("vfs/lighting/pbr.frag", "#version 450\n#include \"../shared/brdf.glsl\"\n#include <common.glsl>\nvoid main() {}\n");
+
+        let compiler = shaderc::Compiler::new().expect("Failed To Create Compiler!");
+        let mut options = CompileOptions::new().expect("Failed To Create Compile Options!");
+        resolver.install(&mut options, "vfs/lighting/pbr.frag");
+        let source = resolver.load_source("vfs/lighting/pbr.frag").unwrap();
+        compiler
+            .compile_into_spirv(&source, shaderc::ShaderKind::Fragment, "vfs/lighting/pbr.frag", "main", Some(&options))
+            .expect("Failed To Compile Test Shader!");
+
+        assert_eq!(resolver.includes_of("vfs/lighting/pbr.frag"), vec!["vfs/include/common.glsl".to_string(), "vfs/shared/brdf.glsl".to_string()]);
+        assert_eq!(resolver.dependents_of("vfs/shared/brdf.glsl"), vec!["vfs/lighting/pbr.frag".to_string()]);
+        assert!(resolver.dependents_of("vfs/fallback/common.glsl").is_empty());
+    }
+}
@@ -0,0 +1,501 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::utility::{file_system::*, shader_include::ShaderIncludeResolver, shader_error::ShaderError, shader_cache::ShaderCache};
+
+//#pragma shader_keyword USE_TEXTURE Declares A Boolean Keyword
+//#pragma shader_keyword LIGHTING_MODEL LAMBERT PHONG PBR Declares An Enum Keyword, The First Value Is The Default
+//Compilers Ignore Pragmas They Do Not Know, So Declarations Cost Nothing At Compile Time
+pub const SHADER_KEYWORD_PRAGMA: &str = "shader_keyword";
+
+//Names file_system Already Defines For Every Shader
+const RESERVED_MACRO_PREFIXES: [&str; 2] = ["SHADER_FREQUENCY_", "ENTRY_POINT"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShaderKeywordValues
+{
+    //Defined As NAME 0 Or NAME 1
+    Boolean,
+    //Every Value Is Defined As NAME_VALUE <Index> And NAME Is The Selected Index,
+    //So Shaders Test #if LIGHTING_MODEL == LIGHTING_MODEL_PBR
+    Enum(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderKeyword
+{
+    pub name: String,
+    pub values: ShaderKeywordValues,
+}
+
+impl ShaderKeyword
+{
+    pub fn boolean(name: &str) -> ShaderKeyword
+    {
+        ShaderKeyword{ name: name.to_owned(), values: ShaderKeywordValues::Boolean }
+    }
+
+    pub fn enumeration(name: &str, values: &[&str]) -> ShaderKeyword
+    {
+        ShaderKeyword{ name: name.to_owned(), values: ShaderKeywordValues::Enum(values.iter().map(|value| (*value).to_owned()).collect()) }
+    }
+
+    pub fn value_count(&self) -> usize
+    {
+        match &self.values
+        {
+            ShaderKeywordValues::Boolean => 2,
+            ShaderKeywordValues::Enum(values) => values.len(),
+        }
+    }
+
+    fn value_name(&self, index: usize) -> String
+    {
+        match &self.values
+        {
+            ShaderKeywordValues::Boolean => if index == 0 { "0".to_owned() } else { "1".to_owned() },
+            ShaderKeywordValues::Enum(values) => values[index].clone(),
+        }
+    }
+
+    fn value_index(&self, value: &str) -> Option<usize>
+    {
+        match &self.values
+        {
+            ShaderKeywordValues::Boolean => match value
+            {
+                "0" => Some(0),
+                "1" => Some(1),
+                _ => None,
+            },
+            ShaderKeywordValues::Enum(values) => values.iter().position(|candidate| candidate == value),
+        }
+    }
+
+    fn value_macro_name(&self, value: &str) -> String
+    {
+        format!("{}_{}", self.name, value)
+    }
+
+    //Every Macro macro_definitions Defines For This Keyword
+    fn macro_names(&self) -> Vec<String>
+    {
+        let mut macro_names = vec![self.name.clone()];
+        if let ShaderKeywordValues::Enum(values) = &self.values
+        {
+            macro_names.extend(values.iter().map(|value| self.value_macro_name(value)));
+        }
+        macro_names
+    }
+}
+
+fn is_macro_identifier(name: &str) -> bool
+{
+    let mut characters = name.chars();
+    characters.next().map_or(false, |first| first.is_ascii_alphabetic() || first == '_')
+        && characters.all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
+//One Selected Value Per Keyword, Kept Sorted So Equal Selections Hash The Same
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ShaderKeywordSet
+{
+    selections: BTreeMap<String, String>,
+}
+
+impl ShaderKeywordSet
+{
+    pub fn new() -> ShaderKeywordSet
+    {
+        ShaderKeywordSet{ selections: BTreeMap::new() }
+    }
+
+    pub fn with_bool(mut self, name: &str, enabled: bool) -> ShaderKeywordSet
+    {
+        self.selections.insert(name.to_owned(), if enabled { "1".to_owned() } else { "0".to_owned() });
+        self
+    }
+
+    pub fn with_enum(mut self, name: &str, value: &str) -> ShaderKeywordSet
+    {
+        self.selections.insert(name.to_owned(), value.to_owned());
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str>
+    {
+        self.selections.get(name).map(|value| value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)>
+    {
+        self.selections.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+impl fmt::Display for ShaderKeywordSet
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if self.selections.is_empty()
+        {
+            return write!(f, "<default>");
+        }
+        let selections: Vec<String> = self.selections.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+        write!(f, "{}", selections.join(" "))
+    }
+}
+
+//The Keywords One Shader Declares, Every Combination Of Their Values Is A Variant
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShaderKeywordSpace
+{
+    keywords: Vec<ShaderKeyword>,
+}
+
+impl ShaderKeywordSpace
+{
+    pub fn new() -> ShaderKeywordSpace
+    {
+        ShaderKeywordSpace{ keywords: vec![] }
+    }
+
+    pub fn add_keyword(&mut self, keyword: ShaderKeyword) -> Result<&mut Self, String>
+    {
+        if !is_macro_identifier(&keyword.name)
+        {
+            return Err(format!("Keyword {} Is Not A Valid Macro Name", keyword.name));
+        }
+        if RESERVED_MACRO_PREFIXES.iter().any(|prefix| keyword.name.starts_with(prefix))
+        {
+            return Err(format!("Keyword {} Collides With A Built In Macro", keyword.name));
+        }
+        if self.keywords.iter().any(|existing| existing.name == keyword.name)
+        {
+            return Err(format!("Keyword {} Is Declared Twice", keyword.name));
+        }
+        if let ShaderKeywordValues::Enum(values) = &keyword.values
+        {
+            if values.is_empty()
+            {
+                return Err(format!("Enum Keyword {} Has No Values", keyword.name));
+            }
+            for (index, value) in values.iter().enumerate()
+            {
+                if !is_macro_identifier(value)
+                {
+                    return Err(format!("Value {} Of Keyword {} Is Not A Valid Macro Name", value, keyword.name));
+                }
+                if values[..index].contains(value)
+                {
+                    return Err(format!("Value {} Of Keyword {} Is Declared Twice", value, keyword.name));
+                }
+            }
+        }
+        //Enum Values Expand To NAME_VALUE, Which Can Land On Another Keyword's Macros Or A Built In One
+        let existing_macro_names: Vec<String> = self.keywords.iter().flat_map(|existing| existing.macro_names()).collect();
+        for macro_name in keyword.macro_names()
+        {
+            if RESERVED_MACRO_PREFIXES.iter().any(|prefix| macro_name.starts_with(prefix))
+            {
+                return Err(format!("Keyword {} Defines {}, Which Collides With A Built In Macro", keyword.name, macro_name));
+            }
+            if existing_macro_names.contains(&macro_name)
+            {
+                return Err(format!("Keyword {} Defines {}, Which Another Keyword Already Defines", keyword.name, macro_name));
+            }
+        }
+        self.keywords.push(keyword);
+        Ok(self)
+    }
+
+    //Reads #pragma shader_keyword Lines, Only From The Root Source, Not From Its Includes
+    pub fn from_source(source: &str) -> Result<ShaderKeywordSpace, String>
+    {
+        let mut keyword_space = ShaderKeywordSpace::new();
+        for (line_index, line) in source.lines().enumerate()
+        {
+            let mut tokens = line.split_whitespace();
+            let is_keyword_pragma = match (tokens.next(), tokens.next())
+            {
+                (Some("#pragma"), Some(pragma)) => pragma == SHADER_KEYWORD_PRAGMA,
+                _ => false,
+            };
+            if !is_keyword_pragma
+            {
+                continue;
+            }
+
+            let name = tokens
+                .next()
+                .ok_or_else(|| format!("Line {}: #pragma {} Without A Keyword Name", line_index + 1, SHADER_KEYWORD_PRAGMA))?;
+            let values: Vec<&str> = tokens.collect();
+            let keyword = if values.is_empty() { ShaderKeyword::boolean(name) } else { ShaderKeyword::enumeration(name, &values) };
+            keyword_space
+                .add_keyword(keyword)
+                .map_err(|message| format!("Line {}: {}", line_index + 1, message))?;
+        }
+        Ok(keyword_space)
+    }
+
+    pub fn keywords(&self) -> &[ShaderKeyword]
+    {
+        &self.keywords
+    }
+
+    pub fn variant_count(&self) -> usize
+    {
+        self.keywords.iter().map(|keyword| keyword.value_count()).product()
+    }
+
+    //Fills In Defaults For Unset Keywords And Rejects Unknown Keywords Or Values
+    pub fn resolve(&self, keyword_set: &ShaderKeywordSet) -> Result<ShaderKeywordSet, String>
+    {
+        if let Some((name, _)) = keyword_set.iter().find(|(name, _)| !self.keywords.iter().any(|keyword| keyword.name == *name))
+        {
+            return Err(format!("Unknown Keyword {}", name));
+        }
+
+        let mut resolved = ShaderKeywordSet::new();
+        for keyword in self.keywords.iter()
+        {
+            let value = match keyword_set.get(&keyword.name)
+            {
+                Some(value) =>
+                {
+                    let index = keyword
+                        .value_index(value)
+                        .ok_or_else(|| format!("Keyword {} Has No Value {}", keyword.name, value))?;
+                    keyword.value_name(index)
+                }
+                None => keyword.value_name(0),
+            };
+            resolved.selections.insert(keyword.name.clone(), value);
+        }
+        Ok(resolved)
+    }
+
+    //Every Keyword Is Defined, Unselected Booleans As 0, So Shaders Can Use #if Instead Of #ifdef
+    pub fn macro_definitions(&self, keyword_set: &ShaderKeywordSet) -> Result<Vec<(String, String)>, String>
+    {
+        let resolved = self.resolve(keyword_set)?;
+        let mut macros = vec![];
+        for keyword in self.keywords.iter()
+        {
+            let value = resolved.get(&keyword.name).expect("Resolved Keyword Set Is Missing A Keyword!");
+            let index = keyword.value_index(value).expect("Resolved Keyword Set Has An Unknown Value!");
+            if let ShaderKeywordValues::Enum(values) = &keyword.values
+            {
+                for (value_index, value) in values.iter().enumerate()
+                {
+                    macros.push((keyword.value_macro_name(value), value_index.to_string()));
+                }
+            }
+            macros.push((keyword.name.clone(), index.to_string()));
+        }
+        Ok(macros)
+    }
+
+    //Every Combination, First Keyword Varying Slowest
+    pub fn all_variants(&self) -> Vec<ShaderKeywordSet>
+    {
+        let mut variants = vec![ShaderKeywordSet::new()];
+        for keyword in self.keywords.iter()
+        {
+            variants = variants
+                .into_iter()
+                .flat_map(|variant| (0..keyword.value_count()).map(move |index| {
+                    let mut variant = variant.clone();
+                    variant.selections.insert(keyword.name.clone(), keyword.value_name(index));
+                    variant
+                }))
+                .collect();
+        }
+        variants
+    }
+}
+
+//Compiles Variants Of One Shader On Demand And Keeps Them, The Disk Cache Makes Repeat Runs Cheap
+pub struct ShaderPermutations
+{
+    path: String,
+    shader_type: ShaderType,
+    keyword_space: ShaderKeywordSpace,
+    variants: HashMap<ShaderKeywordSet, Arc<CompiledShader>>,
+}
+
+impl ShaderPermutations
+{
+    pub fn new(path: &str, shader_type: ShaderType, keyword_space: ShaderKeywordSpace) -> ShaderPermutations
+    {
+        ShaderPermutations{
+            path: path.to_owned(),
+            shader_type,
+            keyword_space,
+            variants: HashMap::new(),
+        }
+    }
+
+    //Keywords Come From The Shader's Own #pragma shader_keyword Declarations
+    pub fn from_declarations(path: &str, shader_type: ShaderType, include_resolver: &ShaderIncludeResolver) -> Result<ShaderPermutations, ShaderError>
+    {
+        let source = include_resolver
+            .load_source(path)
+            .map_err(|error| ShaderError::Io{ path: path.to_owned(), error })?;
+        let keyword_space = ShaderKeywordSpace::from_source(&source)
+            .map_err(|message| ShaderError::InvalidPermutation{ path: path.to_owned(), message })?;
+        Ok(ShaderPermutations::new(path, shader_type, keyword_space))
+    }
+
+    pub fn path(&self) -> &str
+    {
+        &self.path
+    }
+
+    pub fn keyword_space(&self) -> &ShaderKeywordSpace
+    {
+        &self.keyword_space
+    }
+
+    pub fn compiled_variant_count(&self) -> usize
+    {
+        self.variants.len()
+    }
+
+    pub fn variant(
+        &mut self
+        , keyword_set: &ShaderKeywordSet
+        , include_resolver: &ShaderIncludeResolver
+        , shader_cache: Option<&ShaderCache>) -> Result<Arc<CompiledShader>, ShaderError>
+    {
+        let invalid_permutation = |message| ShaderError::InvalidPermutation{ path: self.path.clone(), message };
+        let resolved = self.keyword_space.resolve(keyword_set).map_err(invalid_permutation)?;
+        if let Some(compiled_shader) = self.variants.get(&resolved)
+        {
+            return Ok(compiled_shader.clone());
+        }
+
+        let macros = self.keyword_space.macro_definitions(&resolved).map_err(invalid_permutation)?;
+        let compiled_shader = Arc::new(compile_shader_file_with_macros(&self.path, self.shader_type, &macros, include_resolver, shader_cache)?);
+        self.variants.insert(resolved, compiled_shader.clone());
+        Ok(compiled_shader)
+    }
+
+    //Compiles Every Variant, Failures Do Not Stop The Remaining Ones
+    pub fn precompile_all(
+        &mut self
+        , include_resolver: &ShaderIncludeResolver
+        , shader_cache: Option<&ShaderCache>) -> Vec<(ShaderKeywordSet, Result<(), ShaderError>)>
+    {
+        self.keyword_space
+            .all_variants()
+            .into_iter()
+            .map(|keyword_set| {
+                let result = self.variant(&keyword_set, include_resolver, shader_cache).map(|_| ());
+                (keyword_set, result)
+            })
+            .collect()
+    }
+
+    //Drops Compiled Variants, E.g. After The Source Changed
+    pub fn clear(&mut self)
+    {
+        self.variants.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn test_space() -> ShaderKeywordSpace
+    {
+        let mut keyword_space = ShaderKeywordSpace::new();
+        keyword_space
+            .add_keyword(ShaderKeyword::boolean("USE_TEXTURE")).unwrap()
+            .add_keyword(ShaderKeyword::enumeration("LIGHTING_MODEL", &["LAMBERT", "PHONG", "PBR"])).unwrap();
+        keyword_space
+    }
+
+    #[test]
+    fn every_combination_is_enumerated_first_keyword_slowest()
+    {
+        let keyword_space = test_space();
+        let variants: Vec<String> = keyword_space.all_variants().iter().map(|variant| variant.to_string()).collect();
+
+        assert_eq!(keyword_space.variant_count(), 6);
+        assert_eq!(variants, vec![
+            "LIGHTING_MODEL=LAMBERT USE_TEXTURE=0",
+            "LIGHTING_MODEL=PHONG USE_TEXTURE=0",
+            "LIGHTING_MODEL=PBR USE_TEXTURE=0",
+            "LIGHTING_MODEL=LAMBERT USE_TEXTURE=1",
+            "LIGHTING_MODEL=PHONG USE_TEXTURE=1",
+            "LIGHTING_MODEL=PBR USE_TEXTURE=1",
+        ]);
+        assert_eq!(ShaderKeywordSpace::new().all_variants(), vec![ShaderKeywordSet::new()]);
+    }
+
+    #[test]
+    fn macros_define_every_keyword_and_enum_value()
+    {
+        let keyword_space = test_space();
+        let macros = keyword_space
+            .macro_definitions(&ShaderKeywordSet::new().with_enum("LIGHTING_MODEL", "PBR"))
+            .unwrap();
+
+        let expected: Vec<(String, String)> = [
+            ("USE_TEXTURE", "0"),
+            ("LIGHTING_MODEL_LAMBERT", "0"),
+            ("LIGHTING_MODEL_PHONG", "1"),
+            ("LIGHTING_MODEL_PBR", "2"),
+            ("LIGHTING_MODEL", "2"),
+        ].iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        assert_eq!(macros, expected);
+    }
+
+    #[test]
+    fn unknown_keywords_and_values_are_rejected()
+    {
+        let keyword_space = test_space();
+        assert!(keyword_space.resolve(&ShaderKeywordSet::new().with_bool("USE_SHADOWS", true)).is_err());
+        assert!(keyword_space.resolve(&ShaderKeywordSet::new().with_enum("LIGHTING_MODEL", "TOON")).is_err());
+        assert_eq!(
+            keyword_space.resolve(&ShaderKeywordSet::new()).unwrap()
+            , ShaderKeywordSet::new().with_bool("USE_TEXTURE", false).with_enum("LIGHTING_MODEL", "LAMBERT"));
+    }
+
+    #[test]
+    fn declarations_are_read_from_pragmas()
+    {
+        let source = "#version 450\n#pragma shader_keyword USE_TEXTURE\n#pragma shader_keyword LIGHTING_MODEL LAMBERT PHONG PBR\nvoid main() {}\n";
+        assert_eq!(ShaderKeywordSpace::from_source(source).unwrap(), test_space());
+
+        let error = ShaderKeywordSpace::from_source("#pragma shader_keyword\n").unwrap_err();
+        assert!(error.starts_with("Line 1:"));
+    }
+
+    #[test]
+    fn generated_macro_collisions_are_rejected()
+    {
+        //SHADER_FREQUENCY_VERTEX Is Already Defined For Every Vertex Shader
+        let mut keyword_space = ShaderKeywordSpace::new();
+        assert!(keyword_space.add_keyword(ShaderKeyword::enumeration("SHADER", &["FREQUENCY_VERTEX"])).is_err());
+
+        //QUALITY With Value HIGH Defines QUALITY_HIGH In Either Declaration Order
+        let mut keyword_space = ShaderKeywordSpace::new();
+        keyword_space.add_keyword(ShaderKeyword::boolean("QUALITY_HIGH")).unwrap();
+        assert!(keyword_space.add_keyword(ShaderKeyword::enumeration("QUALITY", &["LOW", "HIGH"])).is_err());
+
+        let mut keyword_space = ShaderKeywordSpace::new();
+        keyword_space.add_keyword(ShaderKeyword::enumeration("QUALITY", &["LOW", "HIGH"])).unwrap();
+        assert!(keyword_space.add_keyword(ShaderKeyword::boolean("QUALITY_HIGH")).is_err());
+
+        //Two Enums Whose Names And Values Concatenate To The Same Macro
+        let mut keyword_space = ShaderKeywordSpace::new();
+        keyword_space.add_keyword(ShaderKeyword::enumeration("FOG", &["MODE_LINEAR"])).unwrap();
+        assert!(keyword_space.add_keyword(ShaderKeyword::enumeration("FOG_MODE", &["LINEAR"])).is_err());
+        assert_eq!(keyword_space.keywords().len(), 1);
+    }
+}
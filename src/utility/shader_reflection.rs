@@ -0,0 +1,1179 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use ash::vk;
+
+use crate::utility::descriptors::*;
+
+pub const SPIRV_MAGIC_NUMBER: u32 = 0x0723_0203;
+const SPIRV_HEADER_WORDS: usize = 5;
+
+//Opcodes
+const OP_NAME: u32 = 5;
+const OP_ENTRY_POINT: u32 = 15;
+const OP_EXECUTION_MODE: u32 = 16;
+const OP_TYPE_VOID: u32 = 19;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_CONSTANT_COMPOSITE: u32 = 44;
+const OP_SPEC_CONSTANT: u32 = 50;
+const OP_SPEC_CONSTANT_COMPOSITE: u32 = 51;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+const OP_EXECUTION_MODE_ID: u32 = 331;
+const OP_TYPE_ACCELERATION_STRUCTURE_KHR: u32 = 5341;
+
+//Decorations
+const DECORATION_BLOCK: u32 = 2;
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_BUILT_IN: u32 = 11;
+const DECORATION_LOCATION: u32 = 30;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+const DECORATION_OFFSET: u32 = 35;
+
+const BUILT_IN_WORKGROUP_SIZE: u32 = 25;
+
+//Storage Classes
+const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_CLASS_INPUT: u32 = 1;
+const STORAGE_CLASS_UNIFORM: u32 = 2;
+const STORAGE_CLASS_OUTPUT: u32 = 3;
+const STORAGE_CLASS_PUSH_CONSTANT: u32 = 9;
+const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+const EXECUTION_MODE_LOCAL_SIZE: u32 = 17;
+const EXECUTION_MODE_LOCAL_SIZE_ID: u32 = 38;
+
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+#[derive(Debug)]
+pub enum ShaderReflectionError
+{
+    InvalidSpirv(String),
+    EntryPointNotFound(String),
+    BindingConflict(String),
+    InterfaceMismatch(Vec<String>),
+}
+
+impl fmt::Display for ShaderReflectionError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            ShaderReflectionError::InvalidSpirv(message) => write!(f, "Invalid SPIR-V: {}", message),
+            ShaderReflectionError::EntryPointNotFound(name) => write!(f, "Entry Point \"{}\" Not Found In SPIR-V Module", name),
+            ShaderReflectionError::BindingConflict(message) => write!(f, "Conflicting Shader Bindings: {}", message),
+            ShaderReflectionError::InterfaceMismatch(mismatches) =>
+            {
+                write!(f, "Shader Interface Mismatch:")?;
+                for mismatch in mismatches.iter()
+                {
+                    write!(f, "\n    {}", mismatch)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl std::error::Error for ShaderReflectionError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflectedDescriptorBinding
+{
+    pub set: u32,
+    pub binding: u32,
+    pub descriptor_type: vk::DescriptorType,
+    //0 For Runtime Sized Arrays
+    pub descriptor_count: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflectedPushConstantBlock
+{
+    pub name: String,
+    pub offset: u32,
+    pub size: u32,
+}
+
+//Matrices And Arrays Are Split Into One Entry Per Location They Occupy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflectedInterfaceVariable
+{
+    pub location: u32,
+    pub format: vk::Format,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShaderReflection
+{
+    pub stage: vk::ShaderStageFlags,
+    pub entry_point: String,
+    pub descriptor_bindings: Vec<ReflectedDescriptorBinding>,
+    pub push_constant_block: Option<ReflectedPushConstantBlock>,
+    pub inputs: Vec<ReflectedInterfaceVariable>,
+    pub outputs: Vec<ReflectedInterfaceVariable>,
+    pub workgroup_size: Option<[u32; 3]>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentKind
+{
+    Float,
+    SignedInt,
+    UnsignedInt,
+    Double,
+}
+
+//Kind, Component Count And Size In Bytes Of The Formats Usable As Vertex Attributes Or Stage Interfaces
+pub fn format_component_info(format: vk::Format) -> Option<(ComponentKind, u32, u32)>
+{
+    let info = match format
+    {
+        vk::Format::R32_SFLOAT => (ComponentKind::Float, 1, 4),
+        vk::Format::R32G32_SFLOAT => (ComponentKind::Float, 2, 8),
+        vk::Format::R32G32B32_SFLOAT => (ComponentKind::Float, 3, 12),
+        vk::Format::R32G32B32A32_SFLOAT => (ComponentKind::Float, 4, 16),
+        vk::Format::R32_SINT => (ComponentKind::SignedInt, 1, 4),
+        vk::Format::R32G32_SINT => (ComponentKind::SignedInt, 2, 8),
+        vk::Format::R32G32B32_SINT => (ComponentKind::SignedInt, 3, 12),
+        vk::Format::R32G32B32A32_SINT => (ComponentKind::SignedInt, 4, 16),
+        vk::Format::R32_UINT => (ComponentKind::UnsignedInt, 1, 4),
+        vk::Format::R32G32_UINT => (ComponentKind::UnsignedInt, 2, 8),
+        vk::Format::R32G32B32_UINT => (ComponentKind::UnsignedInt, 3, 12),
+        vk::Format::R32G32B32A32_UINT => (ComponentKind::UnsignedInt, 4, 16),
+        vk::Format::R64_SFLOAT => (ComponentKind::Double, 1, 8),
+        vk::Format::R64G64_SFLOAT => (ComponentKind::Double, 2, 16),
+        vk::Format::R64G64B64_SFLOAT => (ComponentKind::Double, 3, 24),
+        vk::Format::R64G64B64A64_SFLOAT => (ComponentKind::Double, 4, 32),
+        vk::Format::R16_SFLOAT | vk::Format::R16_UNORM | vk::Format::R16_SNORM => (ComponentKind::Float, 1, 2),
+        vk::Format::R16G16_SFLOAT | vk::Format::R16G16_UNORM | vk::Format::R16G16_SNORM => (ComponentKind::Float, 2, 4),
+        vk::Format::R16G16B16A16_SFLOAT | vk::Format::R16G16B16A16_UNORM | vk::Format::R16G16B16A16_SNORM => (ComponentKind::Float, 4, 8),
+        vk::Format::R16_SINT => (ComponentKind::SignedInt, 1, 2),
+        vk::Format::R16G16_SINT => (ComponentKind::SignedInt, 2, 4),
+        vk::Format::R16G16B16A16_SINT => (ComponentKind::SignedInt, 4, 8),
+        vk::Format::R16_UINT => (ComponentKind::UnsignedInt, 1, 2),
+        vk::Format::R16G16_UINT => (ComponentKind::UnsignedInt, 2, 4),
+        vk::Format::R16G16B16A16_UINT => (ComponentKind::UnsignedInt, 4, 8),
+        vk::Format::R8_UNORM | vk::Format::R8_SNORM => (ComponentKind::Float, 1, 1),
+        vk::Format::R8G8_UNORM | vk::Format::R8G8_SNORM => (ComponentKind::Float, 2, 2),
+        vk::Format::R8G8B8A8_UNORM | vk::Format::R8G8B8A8_SNORM | vk::Format::B8G8R8A8_UNORM => (ComponentKind::Float, 4, 4),
+        vk::Format::A2B10G10R10_UNORM_PACK32 => (ComponentKind::Float, 4, 4),
+        vk::Format::R8_SINT => (ComponentKind::SignedInt, 1, 1),
+        vk::Format::R8G8_SINT => (ComponentKind::SignedInt, 2, 2),
+        vk::Format::R8G8B8A8_SINT => (ComponentKind::SignedInt, 4, 4),
+        vk::Format::R8_UINT => (ComponentKind::UnsignedInt, 1, 1),
+        vk::Format::R8G8_UINT => (ComponentKind::UnsignedInt, 2, 2),
+        vk::Format::R8G8B8A8_UINT => (ComponentKind::UnsignedInt, 4, 4),
+        _ => return None,
+    };
+    Some(info)
+}
+
+fn component_format(kind: ComponentKind, count: u32) -> vk::Format
+{
+    match (kind, count)
+    {
+        (ComponentKind::Float, 1) => vk::Format::R32_SFLOAT,
+        (ComponentKind::Float, 2) => vk::Format::R32G32_SFLOAT,
+        (ComponentKind::Float, 3) => vk::Format::R32G32B32_SFLOAT,
+        (ComponentKind::Float, 4) => vk::Format::R32G32B32A32_SFLOAT,
+        (ComponentKind::SignedInt, 1) => vk::Format::R32_SINT,
+        (ComponentKind::SignedInt, 2) => vk::Format::R32G32_SINT,
+        (ComponentKind::SignedInt, 3) => vk::Format::R32G32B32_SINT,
+        (ComponentKind::SignedInt, 4) => vk::Format::R32G32B32A32_SINT,
+        (ComponentKind::UnsignedInt, 1) => vk::Format::R32_UINT,
+        (ComponentKind::UnsignedInt, 2) => vk::Format::R32G32_UINT,
+        (ComponentKind::UnsignedInt, 3) => vk::Format::R32G32B32_UINT,
+        (ComponentKind::UnsignedInt, 4) => vk::Format::R32G32B32A32_UINT,
+        (ComponentKind::Double, 1) => vk::Format::R64_SFLOAT,
+        (ComponentKind::Double, 2) => vk::Format::R64G64_SFLOAT,
+        (ComponentKind::Double, 3) => vk::Format::R64G64B64_SFLOAT,
+        (ComponentKind::Double, 4) => vk::Format::R64G64B64A64_SFLOAT,
+        _ => vk::Format::UNDEFINED,
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SpirvType
+{
+    Void,
+    Bool,
+    Int{ width: u32, signed: bool },
+    Float{ width: u32 },
+    Vector{ component_type: u32, component_count: u32 },
+    Matrix{ column_type: u32, column_count: u32 },
+    Image{ dim: u32, sampled: u32 },
+    Sampler,
+    SampledImage{ image_type: u32 },
+    Array{ element_type: u32, length_id: u32 },
+    RuntimeArray{ element_type: u32 },
+    Struct{ member_types: Vec<u32> },
+    Pointer{ pointee_type: u32 },
+    AccelerationStructure,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SpirvDecorations
+{
+    set: Option<u32>,
+    binding: Option<u32>,
+    location: Option<u32>,
+    built_in: Option<u32>,
+    array_stride: Option<u32>,
+    block: bool,
+    buffer_block: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SpirvMemberDecorations
+{
+    offset: Option<u32>,
+    matrix_stride: Option<u32>,
+}
+
+struct SpirvEntryPoint
+{
+    execution_model: u32,
+    function_id: u32,
+    name: String,
+    interface_ids: Vec<u32>,
+}
+
+//Just The Parts Of A Module Reflection Needs: Names, Decorations, Types, Constants And Globals
+#[derive(Default)]
+struct SpirvModule
+{
+    entry_points: Vec<SpirvEntryPoint>,
+    execution_modes: Vec<(u32, u32, Vec<u32>)>,
+    names: HashMap<u32, String>,
+    decorations: HashMap<u32, SpirvDecorations>,
+    member_decorations: HashMap<(u32, u32), SpirvMemberDecorations>,
+    types: HashMap<u32, SpirvType>,
+    constants: HashMap<u32, u32>,
+    composites: HashMap<u32, Vec<u32>>,
+    variables: Vec<(u32, u32, u32)>,
+}
+
+//Literal Strings Are Nul Terminated And Padded To A Whole Word
+fn decode_literal_string(words: &[u32]) -> (String, usize)
+{
+    let mut bytes = vec![];
+    for (word_index, word) in words.iter().enumerate()
+    {
+        for byte in word.to_le_bytes()
+        {
+            if byte == 0
+            {
+                return (String::from_utf8_lossy(&bytes).into_owned(), word_index + 1);
+            }
+            bytes.push(byte);
+        }
+    }
+    (String::from_utf8_lossy(&bytes).into_owned(), words.len())
+}
+
+impl SpirvModule
+{
+    fn parse(spirv: &[u32]) -> Result<SpirvModule, ShaderReflectionError>
+    {
+        if spirv.len() < SPIRV_HEADER_WORDS
+        {
+            return Err(ShaderReflectionError::InvalidSpirv("Module Is Shorter Than Its Header".to_string()));
+        }
+        if spirv[0] != SPIRV_MAGIC_NUMBER
+        {
+            return Err(ShaderReflectionError::InvalidSpirv(format!("Bad Magic Number {:#010x}", spirv[0])));
+        }
+
+        let mut module = SpirvModule::default();
+        let mut cursor = SPIRV_HEADER_WORDS;
+        while cursor < spirv.len()
+        {
+            let word_count = (spirv[cursor] >> 16) as usize;
+            let opcode = spirv[cursor] & 0xffff;
+            if word_count == 0 || cursor + word_count > spirv.len()
+            {
+                return Err(ShaderReflectionError::InvalidSpirv(format!("Truncated Instruction (Opcode {}) At Word {}", opcode, cursor)));
+            }
+            let operands = &spirv[cursor + 1..cursor + word_count];
+            module.parse_instruction(opcode, operands)?;
+            cursor += word_count;
+        }
+        Ok(module)
+    }
+
+    fn parse_instruction(&mut self, opcode: u32, operands: &[u32]) -> Result<(), ShaderReflectionError>
+    {
+        let required = match opcode
+        {
+            OP_NAME | OP_TYPE_VECTOR | OP_TYPE_MATRIX | OP_TYPE_SAMPLED_IMAGE | OP_TYPE_RUNTIME_ARRAY | OP_DECORATE | OP_EXECUTION_MODE | OP_EXECUTION_MODE_ID => 2,
+            OP_ENTRY_POINT | OP_TYPE_INT | OP_TYPE_ARRAY | OP_TYPE_POINTER | OP_CONSTANT | OP_SPEC_CONSTANT | OP_VARIABLE | OP_MEMBER_DECORATE => 3,
+            OP_TYPE_IMAGE => 7,
+            OP_TYPE_VOID | OP_TYPE_BOOL | OP_TYPE_FLOAT | OP_TYPE_SAMPLER | OP_TYPE_STRUCT | OP_TYPE_ACCELERATION_STRUCTURE_KHR | OP_CONSTANT_COMPOSITE | OP_SPEC_CONSTANT_COMPOSITE => 1,
+            //Everything Else Is Skipped, OpReturn And OpFunctionEnd Have No Operands At All
+            _ => 0,
+        };
+        if operands.len() < required
+        {
+            return Err(ShaderReflectionError::InvalidSpirv(format!("Opcode {} Has {} Operands, Expected At Least {}", opcode, operands.len(), required)));
+        }
+
+        match opcode
+        {
+            OP_NAME =>
+            {
+                self.names.insert(operands[0], decode_literal_string(&operands[1..]).0);
+            },
+            OP_ENTRY_POINT =>
+            {
+                let (name, name_words) = decode_literal_string(&operands[2..]);
+                self.entry_points.push(SpirvEntryPoint{
+                    execution_model: operands[0],
+                    function_id: operands[1],
+                    name,
+                    interface_ids: operands[2 + name_words..].to_vec(),
+                });
+            },
+            OP_EXECUTION_MODE | OP_EXECUTION_MODE_ID =>
+            {
+                self.execution_modes.push((operands[0], operands[1], operands[2..].to_vec()));
+            },
+            OP_TYPE_VOID => { self.types.insert(operands[0], SpirvType::Void); },
+            OP_TYPE_BOOL => { self.types.insert(operands[0], SpirvType::Bool); },
+            OP_TYPE_INT => { self.types.insert(operands[0], SpirvType::Int{ width: operands[1], signed: operands[2] != 0 }); },
+            OP_TYPE_FLOAT if operands.len() >= 2 => { self.types.insert(operands[0], SpirvType::Float{ width: operands[1] }); },
+            OP_TYPE_VECTOR => { self.types.insert(operands[0], SpirvType::Vector{ component_type: operands[1], component_count: operands[2] }); },
+            OP_TYPE_MATRIX => { self.types.insert(operands[0], SpirvType::Matrix{ column_type: operands[1], column_count: operands[2] }); },
+            OP_TYPE_IMAGE => { self.types.insert(operands[0], SpirvType::Image{ dim: operands[2], sampled: operands[6] }); },
+            OP_TYPE_SAMPLER => { self.types.insert(operands[0], SpirvType::Sampler); },
+            OP_TYPE_SAMPLED_IMAGE => { self.types.insert(operands[0], SpirvType::SampledImage{ image_type: operands[1] }); },
+            OP_TYPE_ARRAY => { self.types.insert(operands[0], SpirvType::Array{ element_type: operands[1], length_id: operands[2] }); },
+            OP_TYPE_RUNTIME_ARRAY => { self.types.insert(operands[0], SpirvType::RuntimeArray{ element_type: operands[1] }); },
+            OP_TYPE_STRUCT => { self.types.insert(operands[0], SpirvType::Struct{ member_types: operands[1..].to_vec() }); },
+            OP_TYPE_POINTER => { self.types.insert(operands[0], SpirvType::Pointer{ pointee_type: operands[2] }); },
+            OP_TYPE_ACCELERATION_STRUCTURE_KHR => { self.types.insert(operands[0], SpirvType::AccelerationStructure); },
+            //Only The Low Word Matters, Array Lengths And Workgroup Sizes Are 32 Bit
+            OP_CONSTANT | OP_SPEC_CONSTANT =>
+            {
+                self.constants.insert(operands[1], operands[2]);
+            },
+            OP_CONSTANT_COMPOSITE | OP_SPEC_CONSTANT_COMPOSITE if operands.len() >= 2 =>
+            {
+                self.composites.insert(operands[1], operands[2..].to_vec());
+            },
+            OP_VARIABLE =>
+            {
+                self.variables.push((operands[1], operands[0], operands[2]));
+            },
+            OP_DECORATE =>
+            {
+                let decorations = self.decorations.entry(operands[0]).or_default();
+                let literal = operands.get(2).copied();
+                match operands[1]
+                {
+                    DECORATION_BLOCK => decorations.block = true,
+                    DECORATION_BUFFER_BLOCK => decorations.buffer_block = true,
+                    DECORATION_ARRAY_STRIDE => decorations.array_stride = literal,
+                    DECORATION_BUILT_IN => decorations.built_in = literal,
+                    DECORATION_LOCATION => decorations.location = literal,
+                    DECORATION_BINDING => decorations.binding = literal,
+                    DECORATION_DESCRIPTOR_SET => decorations.set = literal,
+                    _ => {},
+                }
+            },
+            OP_MEMBER_DECORATE =>
+            {
+                let decorations = self.member_decorations.entry((operands[0], operands[1])).or_default();
+                let literal = operands.get(3).copied();
+                match operands[2]
+                {
+                    DECORATION_OFFSET => decorations.offset = literal,
+                    DECORATION_MATRIX_STRIDE => decorations.matrix_stride = literal,
+                    _ => {},
+                }
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    fn name_of(&self, id: u32) -> String
+    {
+        self.names.get(&id).cloned().unwrap_or_default()
+    }
+
+    fn component_kind(&self, type_id: u32) -> Option<ComponentKind>
+    {
+        match self.types.get(&type_id)?
+        {
+            SpirvType::Float{ width: 64 } => Some(ComponentKind::Double),
+            SpirvType::Float{ .. } => Some(ComponentKind::Float),
+            SpirvType::Int{ signed: true, .. } => Some(ComponentKind::SignedInt),
+            SpirvType::Int{ signed: false, .. } => Some(ComponentKind::UnsignedInt),
+            _ => None,
+        }
+    }
+
+    //Format Of One Location Of type_id, And How Many Consecutive Locations It Takes
+    fn location_format(&self, type_id: u32) -> Option<(vk::Format, u32)>
+    {
+        match self.types.get(&type_id)?
+        {
+            SpirvType::Float{ .. } | SpirvType::Int{ .. } => Some((component_format(self.component_kind(type_id)?, 1), 1)),
+            SpirvType::Vector{ component_type, component_count } =>
+            {
+                Some((component_format(self.component_kind(*component_type)?, *component_count), 1))
+            },
+            SpirvType::Matrix{ column_type, column_count } =>
+            {
+                let (column_format, _) = self.location_format(*column_type)?;
+                Some((column_format, *column_count))
+            },
+            SpirvType::Array{ element_type, length_id } =>
+            {
+                let (element_format, element_locations) = self.location_format(*element_type)?;
+                Some((element_format, element_locations * self.constants.get(length_id).copied().unwrap_or(1)))
+            },
+            _ => None,
+        }
+    }
+
+    //Byte Size Of type_id Under The Explicit Offsets And Strides The Compiler Emitted
+    fn type_size(&self, type_id: u32) -> u32
+    {
+        match self.types.get(&type_id)
+        {
+            Some(SpirvType::Bool) => 4,
+            Some(SpirvType::Int{ width, .. }) | Some(SpirvType::Float{ width }) => width / 8,
+            Some(SpirvType::Vector{ component_type, component_count }) => self.type_size(*component_type) * component_count,
+            Some(SpirvType::Matrix{ column_type, column_count }) => self.type_size(*column_type) * column_count,
+            Some(SpirvType::Array{ element_type, length_id }) =>
+            {
+                let length = self.constants.get(length_id).copied().unwrap_or(1);
+                let stride = self.decorations
+                    .get(&type_id)
+                    .and_then(|decorations| decorations.array_stride)
+                    .unwrap_or_else(|| self.type_size(*element_type));
+                stride * length
+            },
+            Some(SpirvType::Struct{ member_types }) =>
+            {
+                (0..member_types.len() as u32)
+                    .map(|member_index| {
+                        let (offset, size) = self.member_range(type_id, member_index);
+                        offset + size
+                    })
+                    .max()
+                    .unwrap_or(0)
+            },
+            _ => 0,
+        }
+    }
+
+    fn member_range(&self, struct_id: u32, member_index: u32) -> (u32, u32)
+    {
+        let member_type = match self.types.get(&struct_id)
+        {
+            Some(SpirvType::Struct{ member_types }) => member_types[member_index as usize],
+            _ => return (0, 0),
+        };
+        let member_decorations = self.member_decorations.get(&(struct_id, member_index));
+        let offset = member_decorations.and_then(|decorations| decorations.offset).unwrap_or(0);
+        let size = match (self.types.get(&member_type), member_decorations.and_then(|decorations| decorations.matrix_stride))
+        {
+            //std140 Pads Matrix Columns, So The Stride Decides The Size, Not The Column Type
+            (Some(SpirvType::Matrix{ column_count, .. }), Some(matrix_stride)) => matrix_stride * column_count,
+            _ => self.type_size(member_type),
+        };
+        (offset, size)
+    }
+
+    fn descriptor_type_and_count(&self, type_id: u32, storage_class: u32) -> Option<(vk::DescriptorType, u32)>
+    {
+        match self.types.get(&type_id)?
+        {
+            SpirvType::Array{ element_type, length_id } =>
+            {
+                let (descriptor_type, count) = self.descriptor_type_and_count(*element_type, storage_class)?;
+                Some((descriptor_type, count * self.constants.get(length_id).copied().unwrap_or(1)))
+            },
+            SpirvType::RuntimeArray{ element_type } =>
+            {
+                let (descriptor_type, _) = self.descriptor_type_and_count(*element_type, storage_class)?;
+                Some((descriptor_type, 0))
+            },
+            SpirvType::Sampler => Some((vk::DescriptorType::SAMPLER, 1)),
+            SpirvType::SampledImage{ image_type } => match self.types.get(image_type)?
+            {
+                SpirvType::Image{ dim: DIM_BUFFER, .. } => Some((vk::DescriptorType::UNIFORM_TEXEL_BUFFER, 1)),
+                _ => Some((vk::DescriptorType::COMBINED_IMAGE_SAMPLER, 1)),
+            },
+            SpirvType::Image{ dim, sampled } => match (*dim, *sampled)
+            {
+                (DIM_BUFFER, 2) => Some((vk::DescriptorType::STORAGE_TEXEL_BUFFER, 1)),
+                (DIM_BUFFER, _) => Some((vk::DescriptorType::UNIFORM_TEXEL_BUFFER, 1)),
+                (DIM_SUBPASS_DATA, _) => Some((vk::DescriptorType::INPUT_ATTACHMENT, 1)),
+                (_, 2) => Some((vk::DescriptorType::STORAGE_IMAGE, 1)),
+                _ => Some((vk::DescriptorType::SAMPLED_IMAGE, 1)),
+            },
+            SpirvType::AccelerationStructure => Some((vk::DescriptorType::ACCELERATION_STRUCTURE_KHR, 1)),
+            SpirvType::Struct{ .. } =>
+            {
+                let buffer_block = self.decorations
+                    .get(&type_id)
+                    .map_or(false, |decorations| decorations.buffer_block);
+                if storage_class == STORAGE_CLASS_STORAGE_BUFFER || buffer_block
+                {
+                    Some((vk::DescriptorType::STORAGE_BUFFER, 1))
+                }
+                else
+                {
+                    Some((vk::DescriptorType::UNIFORM_BUFFER, 1))
+                }
+            },
+            _ => None,
+        }
+    }
+
+    fn interface_variables(&self, variable_id: u32, pointee_type: u32) -> Vec<ReflectedInterfaceVariable>
+    {
+        let decorations = match self.decorations.get(&variable_id)
+        {
+            Some(decorations) if decorations.built_in.is_none() => decorations,
+            _ => return vec![],
+        };
+        let (location, (format, location_count)) = match (decorations.location, self.location_format(pointee_type))
+        {
+            (Some(location), Some(format)) => (location, format),
+            _ => return vec![],
+        };
+
+        let name = self.name_of(variable_id);
+        (0..location_count)
+            .map(|location_offset| ReflectedInterfaceVariable{
+                location: location + location_offset,
+                format,
+                name: name.clone(),
+            })
+            .collect()
+    }
+
+    fn workgroup_size(&self, function_id: u32) -> Option<[u32; 3]>
+    {
+        //A WorkgroupSize Built-In Constant Takes Precedence Over The Execution Mode
+        for (&composite_id, constituents) in self.composites.iter()
+        {
+            let is_workgroup_size = self.decorations
+                .get(&composite_id)
+                .map_or(false, |decorations| decorations.built_in == Some(BUILT_IN_WORKGROUP_SIZE));
+            if is_workgroup_size && constituents.len() == 3
+            {
+                let size: Vec<u32> = constituents.iter().map(|id| self.constants.get(id).copied().unwrap_or(1)).collect();
+                return Some([size[0], size[1], size[2]]);
+            }
+        }
+
+        self.execution_modes
+            .iter()
+            .filter(|(target, _, operands)| *target == function_id && operands.len() >= 3)
+            .find_map(|(_, mode, operands)| match *mode
+            {
+                EXECUTION_MODE_LOCAL_SIZE => Some([operands[0], operands[1], operands[2]]),
+                EXECUTION_MODE_LOCAL_SIZE_ID => Some([
+                    self.constants.get(&operands[0]).copied().unwrap_or(1),
+                    self.constants.get(&operands[1]).copied().unwrap_or(1),
+                    self.constants.get(&operands[2]).copied().unwrap_or(1),
+                ]),
+                _ => None,
+            })
+    }
+}
+
+fn execution_model_to_stage(execution_model: u32) -> vk::ShaderStageFlags
+{
+    match execution_model
+    {
+        0 => vk::ShaderStageFlags::VERTEX,
+        1 => vk::ShaderStageFlags::TESSELLATION_CONTROL,
+        2 => vk::ShaderStageFlags::TESSELLATION_EVALUATION,
+        3 => vk::ShaderStageFlags::GEOMETRY,
+        4 => vk::ShaderStageFlags::FRAGMENT,
+        5 => vk::ShaderStageFlags::COMPUTE,
+        5267 | 5364 => vk::ShaderStageFlags::TASK_EXT,
+        5268 | 5365 => vk::ShaderStageFlags::MESH_EXT,
+        5313 => vk::ShaderStageFlags::RAYGEN_KHR,
+        5314 => vk::ShaderStageFlags::INTERSECTION_KHR,
+        5315 => vk::ShaderStageFlags::ANY_HIT_KHR,
+        5316 => vk::ShaderStageFlags::CLOSEST_HIT_KHR,
+        5317 => vk::ShaderStageFlags::MISS_KHR,
+        5318 => vk::ShaderStageFlags::CALLABLE_KHR,
+        _ => vk::ShaderStageFlags::empty(),
+    }
+}
+
+//Reflects The Resources, Push Constants And Stage Interface Of One Entry Point Of A Compiled Module
+pub fn reflect_spirv(spirv: &[u32], entry_point: &str) -> Result<ShaderReflection, ShaderReflectionError>
+{
+    let module = SpirvModule::parse(spirv)?;
+
+    let spirv_entry_point = module.entry_points
+        .iter()
+        .find(|spirv_entry_point| spirv_entry_point.name == entry_point)
+        .ok_or_else(|| ShaderReflectionError::EntryPointNotFound(entry_point.to_string()))?;
+
+    let mut reflection = ShaderReflection{
+        stage: execution_model_to_stage(spirv_entry_point.execution_model),
+        entry_point: spirv_entry_point.name.clone(),
+        descriptor_bindings: vec![],
+        push_constant_block: None,
+        inputs: vec![],
+        outputs: vec![],
+        workgroup_size: module.workgroup_size(spirv_entry_point.function_id),
+    };
+
+    for &(variable_id, pointer_type, storage_class) in module.variables.iter()
+    {
+        let pointee_type = match module.types.get(&pointer_type)
+        {
+            Some(SpirvType::Pointer{ pointee_type }) => *pointee_type,
+            _ => continue,
+        };
+
+        match storage_class
+        {
+            //Before SPIR-V 1.4 Only Inputs And Outputs Are Listed On The Entry Point
+            STORAGE_CLASS_INPUT | STORAGE_CLASS_OUTPUT if spirv_entry_point.interface_ids.contains(&variable_id) =>
+            {
+                let variables = module.interface_variables(variable_id, pointee_type);
+                if storage_class == STORAGE_CLASS_INPUT
+                {
+                    reflection.inputs.extend(variables);
+                }
+                else
+                {
+                    reflection.outputs.extend(variables);
+                }
+            },
+            STORAGE_CLASS_PUSH_CONSTANT =>
+            {
+                let member_count = match module.types.get(&pointee_type)
+                {
+                    Some(SpirvType::Struct{ member_types }) => member_types.len() as u32,
+                    _ => continue,
+                };
+                let (offset, end) = (0..member_count)
+                    .map(|member_index| module.member_range(pointee_type, member_index))
+                    .fold((u32::MAX, 0), |(offset, end), (member_offset, member_size)| (offset.min(member_offset), end.max(member_offset + member_size)));
+                if member_count > 0
+                {
+                    reflection.push_constant_block = Some(ReflectedPushConstantBlock{
+                        name: module.name_of(variable_id),
+                        offset,
+                        size: end - offset,
+                    });
+                }
+            },
+            STORAGE_CLASS_UNIFORM_CONSTANT | STORAGE_CLASS_UNIFORM | STORAGE_CLASS_STORAGE_BUFFER =>
+            {
+                let decorations = match module.decorations.get(&variable_id)
+                {
+                    Some(decorations) => decorations,
+                    None => continue,
+                };
+                let (set, binding) = match (decorations.set, decorations.binding)
+                {
+                    (Some(set), Some(binding)) => (set, binding),
+                    _ => continue,
+                };
+                if let Some((descriptor_type, descriptor_count)) = module.descriptor_type_and_count(pointee_type, storage_class)
+                {
+                    //Blocks Are Usually Anonymous Instances, Fall Back To The Block Type Name
+                    let mut name = module.name_of(variable_id);
+                    if name.is_empty()
+                    {
+                        name = module.name_of(pointee_type);
+                    }
+                    reflection.descriptor_bindings.push(ReflectedDescriptorBinding{
+                        set,
+                        binding,
+                        descriptor_type,
+                        descriptor_count,
+                        name,
+                    });
+                }
+            },
+            _ => {},
+        }
+    }
+
+    reflection.descriptor_bindings.sort_by_key(|binding| (binding.set, binding.binding));
+    reflection.inputs.sort_by_key(|variable| variable.location);
+    reflection.outputs.sort_by_key(|variable| variable.location);
+    Ok(reflection)
+}
+
+//vk::PushConstantRange Has No Hash Or Eq
+type PushConstantRangeKey = (vk::ShaderStageFlags, u32, u32);
+
+//Descriptor Set Layouts And Push Constant Ranges Merged Across Every Stage Of A Pipeline
+#[derive(Debug, Clone, Default)]
+pub struct PipelineLayoutDesc
+{
+    //Indexed By Set Number, Sets No Stage Uses Stay Empty
+    pub set_bindings: Vec<Vec<DescriptorBindingDesc>>,
+    pub push_constant_ranges: Vec<vk::PushConstantRange>,
+}
+
+impl PipelineLayoutDesc
+{
+    pub fn from_reflections(reflections: &[&ShaderReflection]) -> Result<PipelineLayoutDesc, ShaderReflectionError>
+    {
+        let mut layout_desc = PipelineLayoutDesc::default();
+        let mut push_constant_range: Option<vk::PushConstantRange> = None;
+
+        for reflection in reflections.iter()
+        {
+            for reflected_binding in reflection.descriptor_bindings.iter()
+            {
+                let set_index = reflected_binding.set as usize;
+                if layout_desc.set_bindings.len() <= set_index
+                {
+                    layout_desc.set_bindings.resize(set_index + 1, vec![]);
+                }
+
+                let set_bindings = &mut layout_desc.set_bindings[set_index];
+                match set_bindings.iter_mut().find(|binding| binding.binding == reflected_binding.binding)
+                {
+                    Some(binding) =>
+                    {
+                        if binding.descriptor_type != reflected_binding.descriptor_type || binding.descriptor_count != reflected_binding.descriptor_count
+                        {
+                            return Err(ShaderReflectionError::BindingConflict(format!(
+                                "Set {} Binding {} ({}) Is {:?}[{}] In One Stage And {:?}[{}] In {:?}",
+                                reflected_binding.set, reflected_binding.binding, reflected_binding.name,
+                                binding.descriptor_type, binding.descriptor_count,
+                                reflected_binding.descriptor_type, reflected_binding.descriptor_count, reflection.stage)));
+                        }
+                        binding.stage_flags |= reflection.stage;
+                    },
+                    None => set_bindings.push(DescriptorBindingDesc{
+                        binding: reflected_binding.binding,
+                        descriptor_type: reflected_binding.descriptor_type,
+                        descriptor_count: reflected_binding.descriptor_count,
+                        stage_flags: reflection.stage,
+                    }),
+                }
+            }
+
+            //A Stage May Only Appear In One Range, So All Stages Share A Single Covering Range
+            if let Some(block) = &reflection.push_constant_block
+            {
+                push_constant_range = Some(match push_constant_range
+                {
+                    Some(range) =>
+                    {
+                        let offset = range.offset.min(block.offset);
+                        let end = (range.offset + range.size).max(block.offset + block.size);
+                        vk::PushConstantRange{
+                            stage_flags: range.stage_flags | reflection.stage,
+                            offset,
+                            size: end - offset,
+                        }
+                    },
+                    None => vk::PushConstantRange{
+                        stage_flags: reflection.stage,
+                        offset: block.offset,
+                        size: block.size,
+                    },
+                });
+            }
+        }
+
+        layout_desc.push_constant_ranges.extend(push_constant_range);
+        Ok(layout_desc)
+    }
+
+    //Reflection Cannot Tell Dynamic Buffers From Plain Ones, The Caller Has To Say So
+    pub fn override_descriptor_type(&mut self, set: u32, binding: u32, descriptor_type: vk::DescriptorType) -> &mut Self
+    {
+        if let Some(binding_desc) = self.set_bindings
+            .get_mut(set as usize)
+            .and_then(|set_bindings| set_bindings.iter_mut().find(|binding_desc| binding_desc.binding == binding))
+        {
+            binding_desc.descriptor_type = descriptor_type;
+        }
+        self
+    }
+
+    //Set Layouts Come From The Cache And Stay Owned By It, Only The Pipeline Layout Is The Caller's
+    pub fn create_pipeline_layout(&self, device: &ash::Device, layout_cache: &mut DescriptorLayoutCache) -> (vk::PipelineLayout, Vec<vk::DescriptorSetLayout>)
+    {
+        let set_layouts: Vec<vk::DescriptorSetLayout> = self.set_bindings
+            .iter()
+            .map(|set_bindings| layout_cache.get_layout(device, set_bindings))
+            .collect();
+
+        let pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::builder()
+            .set_layouts(&set_layouts)
+            .push_constant_ranges(&self.push_constant_ranges);
+
+        let pipeline_layout = unsafe{
+            device
+                .create_pipeline_layout(&pipeline_layout_create_info, None)
+                .expect("Failed To Create Pipeline Layout")
+        };
+
+        (pipeline_layout, set_layouts)
+    }
+
+    fn key(&self) -> (&Vec<Vec<DescriptorBindingDesc>>, Vec<PushConstantRangeKey>)
+    {
+        let push_constant_ranges = self.push_constant_ranges
+            .iter()
+            .map(|range| (range.stage_flags, range.offset, range.size))
+            .collect();
+        (&self.set_bindings, push_constant_ranges)
+    }
+}
+
+//Lets Pipeline Layouts Be Cached By What They Describe Rather Than By Handle
+impl PartialEq for PipelineLayoutDesc
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for PipelineLayoutDesc {}
+
+impl Hash for PipelineLayoutDesc
+{
+    fn hash<H: Hasher>(&self, state: &mut H)
+    {
+        self.key().hash(state);
+    }
+}
+
+//Implemented By Rust Vertex Types So Pipelines Can Check Them Against The Vertex Shader
+pub trait VertexInputLayout
+{
+    fn get_binding_descriptions() -> Vec<vk::VertexInputBindingDescription>;
+    fn get_attribute_descriptions() -> Vec<vk::VertexInputAttributeDescription>;
+}
+
+pub fn validate_vertex_input(
+    vertex_reflection: &ShaderReflection
+    , binding_descriptions: &[vk::VertexInputBindingDescription]
+    , attribute_descriptions: &[vk::VertexInputAttributeDescription]) -> Result<(), ShaderReflectionError>
+{
+    let mut mismatches = vec![];
+
+    for input in vertex_reflection.inputs.iter()
+    {
+        let attribute = match attribute_descriptions.iter().find(|attribute| attribute.location == input.location)
+        {
+            Some(attribute) => attribute,
+            None =>
+            {
+                mismatches.push(format!("Location {} ({}) Is Not Provided By The Vertex Type", input.location, input.name));
+                continue;
+            },
+        };
+
+        let shader_kind = match format_component_info(input.format)
+        {
+            Some((shader_kind, _, _)) => shader_kind,
+            None => continue,
+        };
+        match format_component_info(attribute.format)
+        {
+            Some((attribute_kind, _, _)) if attribute_kind != shader_kind =>
+            {
+                mismatches.push(format!("Location {} ({}) Expects {:?} Data, But The Vertex Type Provides {:?}", input.location, input.name, input.format, attribute.format));
+            },
+            None =>
+            {
+                mismatches.push(format!("Location {} ({}) Uses {:?}, Which Is Not A Supported Vertex Format", input.location, input.name, attribute.format));
+            },
+            _ => {},
+        }
+    }
+
+    for attribute in attribute_descriptions.iter()
+    {
+        let binding = match binding_descriptions.iter().find(|binding| binding.binding == attribute.binding)
+        {
+            Some(binding) => binding,
+            None =>
+            {
+                mismatches.push(format!("Location {} Reads From Binding {}, Which The Vertex Type Does Not Describe", attribute.location, attribute.binding));
+                continue;
+            },
+        };
+
+        if let Some((_, _, attribute_size)) = format_component_info(attribute.format)
+        {
+            if attribute.offset + attribute_size > binding.stride
+            {
+                mismatches.push(format!("Location {} Reads {} Bytes At Offset {}, Past The Vertex Stride Of {}", attribute.location, attribute_size, attribute.offset, binding.stride));
+            }
+        }
+    }
+
+    if mismatches.is_empty()
+    {
+        Ok(())
+    }
+    else
+    {
+        Err(ShaderReflectionError::InterfaceMismatch(mismatches))
+    }
+}
+
+//Checks A Rust Vertex Type Against What The Vertex Shader Actually Reads
+pub fn validate_vertex_type<V: VertexInputLayout>(vertex_reflection: &ShaderReflection) -> Result<(), ShaderReflectionError>
+{
+    validate_vertex_input(vertex_reflection, &V::get_binding_descriptions(), &V::get_attribute_descriptions())
+}
+
+//Every Input Of The Later Stage Must Be Written By The Earlier One With The Same Component Type
+pub fn validate_stage_interface(producer: &ShaderReflection, consumer: &ShaderReflection) -> Result<(), ShaderReflectionError>
+{
+    let mut mismatches = vec![];
+
+    for input in consumer.inputs.iter()
+    {
+        match producer.outputs.iter().find(|output| output.location == input.location)
+        {
+            Some(output) =>
+            {
+                let (output_kind, output_count, _) = format_component_info(output.format).unwrap_or((ComponentKind::Float, 0, 0));
+                let (input_kind, input_count, _) = format_component_info(input.format).unwrap_or((ComponentKind::Float, 0, 0));
+                if output_kind != input_kind || output_count < input_count
+                {
+                    mismatches.push(format!("{:?} Input {} ({}) Is {:?}, But {:?} Writes {:?}", consumer.stage, input.location, input.name, input.format, producer.stage, output.format));
+                }
+            },
+            None => mismatches.push(format!("{:?} Input {} ({}) Is Never Written By {:?}", consumer.stage, input.location, input.name, producer.stage)),
+        }
+    }
+
+    if mismatches.is_empty()
+    {
+        Ok(())
+    }
+    else
+    {
+        Err(ShaderReflectionError::InterfaceMismatch(mismatches))
+    }
+}
+
+//Vertex Input For Shaders Fed From One Tightly Packed Buffer, Attributes In Location Order
+pub fn reflected_vertex_input(vertex_reflection: &ShaderReflection) -> (Vec<vk::VertexInputBindingDescription>, Vec<vk::VertexInputAttributeDescription>)
+{
+    let mut offset = 0;
+    let attribute_descriptions: Vec<vk::VertexInputAttributeDescription> = vertex_reflection.inputs
+        .iter()
+        .map(|input| {
+            let attribute = vk::VertexInputAttributeDescription{
+                binding: 0,
+                location: input.location,
+                format: input.format,
+                offset,
+            };
+            offset += format_component_info(input.format).map_or(0, |(_, _, size)| size);
+            attribute
+        })
+        .collect();
+
+    let binding_descriptions = if attribute_descriptions.is_empty()
+    {
+        vec![]
+    }
+    else
+    {
+        vec![vk::VertexInputBindingDescription{
+            binding: 0,
+            stride: offset,
+            input_rate: vk::VertexInputRate::VERTEX,
+        }]
+    };
+
+    (binding_descriptions, attribute_descriptions)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const VERTEX_SOURCE: &str = r#"
+        #version 450
+        layout(set = 0, binding = 0) uniform Camera { mat4 view_projection; } camera;
+        layout(set = 1, binding = 2) uniform sampler2D textures[4];
+        layout(push_constant) uniform DrawConstants { mat4 model; vec4 tint; } draw;
+        layout(location = 0) in vec3 in_position;
+        layout(location = 1) in vec2 in_uv;
+        layout(location = 0) out vec2 out_uv;
+        void main()
+        {
+            out_uv = in_uv * texture(textures[1], in_uv).xy * draw.tint.x;
+            gl_Position = camera.view_projection * draw.model * vec4(in_position, 1.0);
+        }
+    "#;
+
+    const COMPUTE_SOURCE: &str = r#"
+        #version 450
+        layout(local_size_x = 8, local_size_y = 4) in;
+        layout(set = 0, binding = 1) buffer Values { uint values[]; };
+        void main()
+        {
+            values[gl_GlobalInvocationID.x] = gl_GlobalInvocationID.y;
+        }
+    "#;
+
+    fn compile_glsl(source: &str, shader_kind: shaderc::ShaderKind) -> Vec<u32>
+    {
+        let compiler = shaderc::Compiler::new().expect("Failed To Create Compiler!");
+        compiler
+            .compile_into_spirv(source, shader_kind, "test.glsl", "main", None)
+            .expect("Failed To Compile Test Shader!")
+            .as_binary()
+            .to_vec()
+    }
+
+    #[test]
+    fn reflects_bindings_push_constants_and_interface()
+    {
+        let spirv = compile_glsl(VERTEX_SOURCE, shaderc::ShaderKind::Vertex);
+        let reflection = reflect_spirv(&spirv, "main").unwrap();
+
+        assert_eq!(reflection.stage, vk::ShaderStageFlags::VERTEX);
+        assert_eq!(reflection.descriptor_bindings, vec![
+            ReflectedDescriptorBinding{ set: 0, binding: 0, descriptor_type: vk::DescriptorType::UNIFORM_BUFFER, descriptor_count: 1, name: "camera".to_string() },
+            ReflectedDescriptorBinding{ set: 1, binding: 2, descriptor_type: vk::DescriptorType::COMBINED_IMAGE_SAMPLER, descriptor_count: 4, name: "textures".to_string() },
+        ]);
+        assert_eq!(reflection.push_constant_block, Some(ReflectedPushConstantBlock{ name: "draw".to_string(), offset: 0, size: 80 }));
+        assert_eq!(reflection.inputs, vec![
+            ReflectedInterfaceVariable{ location: 0, format: vk::Format::R32G32B32_SFLOAT, name: "in_position".to_string() },
+            ReflectedInterfaceVariable{ location: 1, format: vk::Format::R32G32_SFLOAT, name: "in_uv".to_string() },
+        ]);
+        //gl_Position Is A Built-In And Has No Location
+        assert_eq!(reflection.outputs, vec![
+            ReflectedInterfaceVariable{ location: 0, format: vk::Format::R32G32_SFLOAT, name: "out_uv".to_string() },
+        ]);
+        assert_eq!(reflection.workgroup_size, None);
+
+        let layout_desc = PipelineLayoutDesc::from_reflections(&[&reflection]).unwrap();
+        assert_eq!(layout_desc.set_bindings.len(), 2);
+        assert_eq!(layout_desc.set_bindings[1][0].descriptor_count, 4);
+        assert_eq!(layout_desc.push_constant_ranges.len(), 1);
+        assert_eq!(layout_desc.push_constant_ranges[0].size, 80);
+        assert_eq!(layout_desc.push_constant_ranges[0].stage_flags, vk::ShaderStageFlags::VERTEX);
+    }
+
+    #[test]
+    fn layout_descs_compare_by_content()
+    {
+        let spirv = compile_glsl(VERTEX_SOURCE, shaderc::ShaderKind::Vertex);
+        let reflection = reflect_spirv(&spirv, "main").unwrap();
+        let layout_desc = PipelineLayoutDesc::from_reflections(&[&reflection]).unwrap();
+
+        let mut layouts = HashMap::new();
+        layouts.insert(layout_desc.clone(), 0);
+        assert_eq!(layouts.get(&PipelineLayoutDesc::from_reflections(&[&reflection]).unwrap()), Some(&0));
+
+        let mut dynamic_desc = layout_desc.clone();
+        dynamic_desc.override_descriptor_type(0, 0, vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC);
+        assert_ne!(dynamic_desc, layout_desc);
+
+        let mut wider_push_desc = layout_desc.clone();
+        wider_push_desc.push_constant_ranges[0].size = 128;
+        assert_ne!(wider_push_desc, layout_desc);
+        assert!(!layouts.contains_key(&wider_push_desc));
+    }
+
+    #[test]
+    fn reflects_storage_buffers_and_workgroup_size()
+    {
+        let spirv = compile_glsl(COMPUTE_SOURCE, shaderc::ShaderKind::Compute);
+        let reflection = reflect_spirv(&spirv, "main").unwrap();
+
+        assert_eq!(reflection.stage, vk::ShaderStageFlags::COMPUTE);
+        assert_eq!(reflection.workgroup_size, Some([8, 4, 1]));
+        assert_eq!(reflection.descriptor_bindings.len(), 1);
+        assert_eq!(reflection.descriptor_bindings[0].binding, 1);
+        assert_eq!(reflection.descriptor_bindings[0].descriptor_type, vk::DescriptorType::STORAGE_BUFFER);
+        assert_eq!(reflection.push_constant_block, None);
+    }
+
+    #[test]
+    fn missing_entry_point_is_reported()
+    {
+        let spirv = compile_glsl(COMPUTE_SOURCE, shaderc::ShaderKind::Compute);
+        assert!(matches!(reflect_spirv(&spirv, "other"), Err(ShaderReflectionError::EntryPointNotFound(_))));
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected()
+    {
+        assert!(matches!(reflect_spirv(&[], "main"), Err(ShaderReflectionError::InvalidSpirv(_))));
+        assert!(matches!(reflect_spirv(&[SPIRV_MAGIC_NUMBER, 0x0001_0000], "main"), Err(ShaderReflectionError::InvalidSpirv(_))));
+        assert!(matches!(reflect_spirv(&[0xdead_beef, 0x0001_0000, 0, 16, 0], "main"), Err(ShaderReflectionError::InvalidSpirv(_))));
+    }
+
+    #[test]
+    fn malformed_instructions_are_rejected()
+    {
+        let header = [SPIRV_MAGIC_NUMBER, 0x0001_0000, 0, 16, 0];
+
+        //Zero Word Count Would Never Advance
+        let zero_word_count: Vec<u32> = header.iter().copied().chain([OP_NAME]).collect();
+        assert!(matches!(reflect_spirv(&zero_word_count, "main"), Err(ShaderReflectionError::InvalidSpirv(_))));
+
+        //Word Count Runs Past The End Of The Module
+        let past_the_end: Vec<u32> = header.iter().copied().chain([(4 << 16) | OP_DECORATE, 1]).collect();
+        assert!(matches!(reflect_spirv(&past_the_end, "main"), Err(ShaderReflectionError::InvalidSpirv(_))));
+
+        //OpTypeImage With Fewer Operands Than Its Fields
+        let short_operands: Vec<u32> = header.iter().copied().chain([(3 << 16) | OP_TYPE_IMAGE, 1, 2]).collect();
+        assert!(matches!(reflect_spirv(&short_operands, "main"), Err(ShaderReflectionError::InvalidSpirv(_))));
+    }
+
+    #[test]
+    fn truncated_modules_never_panic()
+    {
+        let spirv = compile_glsl(VERTEX_SOURCE, shaderc::ShaderKind::Vertex);
+        for length in 0..spirv.len()
+        {
+            //Cutting At An Instruction Boundary Can Still Parse, Anything Else Must Be An Error
+            let _ = reflect_spirv(&spirv[..length], "main");
+        }
+
+        let mut cut_mid_instruction = spirv.clone();
+        cut_mid_instruction.truncate(SPIRV_HEADER_WORDS + 1);
+        assert!(matches!(reflect_spirv(&cut_mid_instruction, "main"), Err(ShaderReflectionError::InvalidSpirv(_))));
+    }
+}
@@ -7,6 +7,8 @@ pub struct QueueFamilyIndices
 {
     pub graphics_family: Option<u32>,
     pub present_family: Option<u32>,
+    //Only Set For A Transfer Family Separate From Graphics, Uploads Fall Back To Graphics Otherwise
+    pub transfer_family: Option<u32>,
 }
 
 impl QueueFamilyIndices 
@@ -16,6 +18,7 @@ impl QueueFamilyIndices
         QueueFamilyIndices{
             graphics_family: None,
             present_family: None,
+            transfer_family: None,
         }
     }
 
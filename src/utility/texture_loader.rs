@@ -0,0 +1,515 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+
+use ash::vk;
+
+use crate::utility::{context, commandbuffers::OneTimeSubmitCommandGroup, gpubuffer::GPUBufferError, gpuimage::*, format::*, memory_report::TrackedAllocator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureColorSpace
+{
+    //Color Data (Albedo, Emissive...), Sampled Through An *_SRGB Format
+    Srgb,
+    //Non Color Data (Normals, Roughness...), Sampled Through An *_UNORM Format
+    Linear,
+}
+
+#[derive(Debug)]
+pub enum TextureLoadError
+{
+    Io(io::Error),
+    Decode(String),
+    UnsupportedFormat(String),
+    Upload(GPUBufferError),
+}
+
+impl fmt::Display for TextureLoadError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            TextureLoadError::Io(error) => write!(f, "Failed To Read Texture File: {}", error),
+            TextureLoadError::Decode(message) => write!(f, "Failed To Decode Texture: {}", message),
+            TextureLoadError::UnsupportedFormat(message) => write!(f, "Unsupported Texture Format: {}", message),
+            TextureLoadError::Upload(error) => write!(f, "Failed To Upload Texture: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for TextureLoadError {}
+
+impl From<io::Error> for TextureLoadError
+{
+    fn from(error: io::Error) -> Self
+    {
+        TextureLoadError::Io(error)
+    }
+}
+
+impl From<GPUBufferError> for TextureLoadError
+{
+    fn from(error: GPUBufferError) -> Self
+    {
+        TextureLoadError::Upload(error)
+    }
+}
+
+//Everything Needed To Create A Texture And Push Its Pixels Through A Staging Buffer
+pub struct TextureUploadContext<'a>
+{
+    pub instance: &'a ash::Instance,
+    pub physical_device: vk::PhysicalDevice,
+    pub device: &'a ash::Device,
+    pub allocator: &'a mut TrackedAllocator,
+    pub command_group: &'a mut OneTimeSubmitCommandGroup,
+    pub queue: vk::Queue,
+}
+
+//Texel Data Of Every Level/Layer The File Provides, Laid Out Back To Back
+struct DecodedTexture
+{
+    dimension: GPUImageDimension,
+    format: vk::Format,
+    extent: vk::Extent3D,
+    mip_levels: u32,
+    array_layers: u32,
+    pixel_data: Vec<u8>,
+    copy_regions: Vec<vk::BufferImageCopy>,
+}
+
+//Format Is Chosen From The File Extension: png, jpg/jpeg, ktx2, dds
+pub fn load_texture_from_file(context: &mut TextureUploadContext, path: &Path, color_space: TextureColorSpace) -> Result<GPUImage, TextureLoadError>
+{
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let decoded_texture = match extension.as_str()
+    {
+        "png" | "jpg" | "jpeg" => decode_image_file(path, color_space)?,
+        "ktx2" => decode_ktx2_file(path, color_space)?,
+        "dds" => decode_dds_file(path, color_space)?,
+        _ => return Err(TextureLoadError::UnsupportedFormat(format!("Unknown Extension \"{}\"", extension))),
+    };
+
+    upload_decoded_texture(context, &path.to_string_lossy(), decoded_texture)
+}
+
+fn decode_image_file(path: &Path, color_space: TextureColorSpace) -> Result<DecodedTexture, TextureLoadError>
+{
+    let decoded_image = image::open(path)
+        .map_err(|error| TextureLoadError::Decode(error.to_string()))?
+        .to_rgba8();
+
+    let (width, height) = decoded_image.dimensions();
+    let extent = vk::Extent3D{ width, height, depth: 1 };
+
+    Ok(DecodedTexture{
+        dimension: GPUImageDimension::Texture2D,
+        format: apply_color_space(vk::Format::R8G8B8A8_UNORM, color_space),
+        extent,
+        mip_levels: 1,
+        array_layers: 1,
+        pixel_data: decoded_image.into_raw(),
+        copy_regions: vec![mip_copy_region(0, 0, 1, extent, 0)],
+    })
+}
+
+fn decode_ktx2_file(path: &Path, color_space: TextureColorSpace) -> Result<DecodedTexture, TextureLoadError>
+{
+    let file_data = fs::read(path)?;
+    let reader = ktx2::Reader::new(file_data.as_slice())
+        .map_err(|error| TextureLoadError::Decode(format!("{:?}", error)))?;
+    let header = reader.header();
+
+    //Basis Universal Payloads Have No Vulkan Format And Would Need Transcoding
+    let file_format = header.format
+        .ok_or_else(|| TextureLoadError::UnsupportedFormat("KTX2 Without VkFormat (Basis Universal)".to_owned()))?;
+    let format = apply_color_space(vk::Format::from_raw(file_format.0.get() as i32), color_space);
+
+    let extent = vk::Extent3D{
+        width: header.pixel_width,
+        height: header.pixel_height.max(1),
+        depth: header.pixel_depth.max(1),
+    };
+    let array_layers = header.layer_count.max(1) * header.face_count;
+    let dimension = texture_dimension(extent, header.layer_count.max(1), header.face_count == 6);
+
+    let mut pixel_data = vec![];
+    let mut copy_regions = vec![];
+    for (mip_level, level_data) in reader.levels().enumerate()
+    {
+        let buffer_offset = pixel_data.len() as u64;
+        match header.supercompression_scheme
+        {
+            None => pixel_data.extend_from_slice(level_data),
+            Some(ktx2::SupercompressionScheme::Zstandard) =>
+            {
+                let mut decoder = ruzstd::StreamingDecoder::new(level_data)
+                    .map_err(|error| TextureLoadError::Decode(error.to_string()))?;
+                decoder.read_to_end(&mut pixel_data)?;
+            },
+            Some(scheme) => return Err(TextureLoadError::UnsupportedFormat(format!("KTX2 Supercompression {:?}", scheme))),
+        }
+
+        //Each KTX2 Level Stores Layers And Faces Tightly Packed, Which Matches Vulkan Layer Order
+        let mip_level = mip_level as u32;
+        copy_regions.push(mip_copy_region(mip_level, 0, array_layers, mip_level_extent(extent, mip_level), buffer_offset));
+    }
+
+    Ok(DecodedTexture{
+        dimension,
+        format,
+        extent,
+        mip_levels: copy_regions.len() as u32,
+        array_layers,
+        pixel_data,
+        copy_regions,
+    })
+}
+
+fn decode_dds_file(path: &Path, color_space: TextureColorSpace) -> Result<DecodedTexture, TextureLoadError>
+{
+    let file = fs::File::open(path)?;
+    let dds = ddsfile::Dds::read(io::BufReader::new(file))
+        .map_err(|error| TextureLoadError::Decode(error.to_string()))?;
+
+    let file_format = dds_format_to_vk(&dds)
+        .ok_or_else(|| TextureLoadError::UnsupportedFormat(format!("DDS {:?} / {:?}", dds.get_dxgi_format(), dds.get_d3d_format())))?;
+    let format = apply_color_space(file_format, color_space);
+
+    let extent = vk::Extent3D{
+        width: dds.get_width(),
+        height: dds.get_height().max(1),
+        depth: dds.get_depth().max(1),
+    };
+
+    let is_cube = dds.header10.as_ref()
+        .map(|header10| header10.misc_flag.contains(ddsfile::MiscFlag::TEXTURECUBE))
+        .unwrap_or(false)
+        || dds.header.caps2.contains(ddsfile::Caps2::CUBEMAP);
+
+    //DX10 Cube Headers Count Cubes, Legacy Cube Headers Already Report 6 Faces
+    let array_layers = match (&dds.header10, is_cube)
+    {
+        (Some(header10), true) => header10.array_size.max(1) * 6,
+        _ => dds.get_num_array_layers().max(1),
+    };
+    let mip_levels = dds.get_num_mipmap_levels().max(1);
+    let dimension = texture_dimension(extent, if is_cube {array_layers / 6} else {array_layers}, is_cube);
+
+    //DDS Stores Every Mip Of Layer 0, Then Every Mip Of Layer 1...
+    let layer_stride = dds.get_array_stride()
+        .map_err(|error| TextureLoadError::Decode(error.to_string()))? as u64;
+    let mut copy_regions = vec![];
+    for array_layer in 0..array_layers
+    {
+        let mut buffer_offset = array_layer as u64 * layer_stride;
+        for mip_level in 0..mip_levels
+        {
+            let level_extent = mip_level_extent(extent, mip_level);
+            copy_regions.push(mip_copy_region(mip_level, array_layer, 1, level_extent, buffer_offset));
+            buffer_offset += format_mip_size(format, level_extent)
+                .ok_or_else(|| TextureLoadError::UnsupportedFormat(format!("{:?}", format)))?;
+        }
+    }
+
+    Ok(DecodedTexture{
+        dimension,
+        format,
+        extent,
+        mip_levels,
+        array_layers,
+        pixel_data: dds.data,
+        copy_regions,
+    })
+}
+
+fn upload_decoded_texture(context: &mut TextureUploadContext, name: &str, decoded_texture: DecodedTexture) -> Result<GPUImage, TextureLoadError>
+{
+    //Block Compressed Formats Can Never Be Blit Destinations, So They Keep Whatever Mips The File Has
+    let generate_mips = decoded_texture.mip_levels == 1
+        && context::find_supported_format(
+            context.instance
+            , context.physical_device
+            , &[decoded_texture.format]
+            , vk::ImageTiling::OPTIMAL
+            , vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR | vk::FormatFeatureFlags::BLIT_SRC | vk::FormatFeatureFlags::BLIT_DST)
+            .is_some();
+
+    let mut image_desc = GPUImageDesc{
+        name,
+        dimension: decoded_texture.dimension,
+        format: decoded_texture.format,
+        extent: decoded_texture.extent,
+        mip_levels: decoded_texture.mip_levels,
+        array_layers: decoded_texture.array_layers,
+        samples: vk::SampleCountFlags::TYPE_1,
+        usage: vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_DST,
+    };
+
+    if generate_mips
+    {
+        image_desc.mip_levels = full_mip_levels(decoded_texture.extent.width, decoded_texture.extent.height);
+        image_desc.usage |= vk::ImageUsageFlags::TRANSFER_SRC;
+    }
+
+    let mut gpu_image = GPUImage::create_gpu_image(context.device, context.allocator, &image_desc);
+
+    let upload_result = if generate_mips
+    {
+        gpu_image.upload_pixel_data_and_generate_mips(
+            context.device
+            , context.allocator
+            , context.command_group
+            , context.queue
+            , &decoded_texture.pixel_data
+            , vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+    }
+    else
+    {
+        gpu_image.upload_pixel_data_regions(
+            context.device
+            , context.allocator
+            , context.command_group
+            , context.queue
+            , &decoded_texture.pixel_data
+            , &decoded_texture.copy_regions
+            , vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+    };
+
+    //Nothing Was Submitted On Failure, The Image Can Go Straight Away
+    if let Err(error) = upload_result
+    {
+        GPUImage::destroy_gpu_image(&mut gpu_image, context.device, context.allocator);
+        return Err(error.into());
+    }
+
+    Ok(gpu_image)
+}
+
+fn texture_dimension(extent: vk::Extent3D, layer_count: u32, is_cube: bool) -> GPUImageDimension
+{
+    match (is_cube, layer_count > 1)
+    {
+        (true, false) => GPUImageDimension::Cube,
+        (true, true) => GPUImageDimension::CubeArray,
+        (false, true) => GPUImageDimension::Texture2DArray,
+        (false, false) if extent.depth > 1 => GPUImageDimension::Texture3D,
+        (false, false) => GPUImageDimension::Texture2D,
+    }
+}
+
+fn mip_copy_region(mip_level: u32, base_array_layer: u32, layer_count: u32, extent: vk::Extent3D, buffer_offset: u64) -> vk::BufferImageCopy
+{
+    vk::BufferImageCopy{
+        buffer_offset,
+        buffer_row_length: 0,
+        buffer_image_height: 0,
+        image_subresource: vk::ImageSubresourceLayers{
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level,
+            base_array_layer,
+            layer_count,
+        },
+        image_offset: vk::Offset3D{ x: 0, y: 0, z: 0 },
+        image_extent: extent,
+    }
+}
+
+//Swaps Between The *_UNORM And *_SRGB Variant Of A Format, Formats Without A Pair Are Returned As Is
+pub fn apply_color_space(format: vk::Format, color_space: TextureColorSpace) -> vk::Format
+{
+    const FORMAT_PAIRS: [(vk::Format, vk::Format); 7] = [
+        (vk::Format::R8G8B8A8_UNORM, vk::Format::R8G8B8A8_SRGB),
+        (vk::Format::B8G8R8A8_UNORM, vk::Format::B8G8R8A8_SRGB),
+        (vk::Format::BC1_RGB_UNORM_BLOCK, vk::Format::BC1_RGB_SRGB_BLOCK),
+        (vk::Format::BC1_RGBA_UNORM_BLOCK, vk::Format::BC1_RGBA_SRGB_BLOCK),
+        (vk::Format::BC2_UNORM_BLOCK, vk::Format::BC2_SRGB_BLOCK),
+        (vk::Format::BC3_UNORM_BLOCK, vk::Format::BC3_SRGB_BLOCK),
+        (vk::Format::BC7_UNORM_BLOCK, vk::Format::BC7_SRGB_BLOCK),
+    ];
+
+    for &(unorm_format, srgb_format) in FORMAT_PAIRS.iter()
+    {
+        if format == unorm_format || format == srgb_format
+        {
+            return match color_space
+            {
+                TextureColorSpace::Srgb => srgb_format,
+                TextureColorSpace::Linear => unorm_format,
+            };
+        }
+    }
+
+    format
+}
+
+fn dds_format_to_vk(dds: &ddsfile::Dds) -> Option<vk::Format>
+{
+    use ddsfile::{DxgiFormat, D3DFormat};
+
+    //get_dxgi_format Also Guesses From Legacy Headers, Reading DXT1-5 As sRGB, So Only Trust An Actual DX10 Header
+    if let Some(header10) = dds.header10.as_ref()
+    {
+        return match header10.dxgi_format
+        {
+            DxgiFormat::R8_UNorm => Some(vk::Format::R8_UNORM),
+            DxgiFormat::R8G8_UNorm => Some(vk::Format::R8G8_UNORM),
+            DxgiFormat::R8G8B8A8_UNorm => Some(vk::Format::R8G8B8A8_UNORM),
+            DxgiFormat::R8G8B8A8_UNorm_sRGB => Some(vk::Format::R8G8B8A8_SRGB),
+            DxgiFormat::B8G8R8A8_UNorm => Some(vk::Format::B8G8R8A8_UNORM),
+            DxgiFormat::B8G8R8A8_UNorm_sRGB => Some(vk::Format::B8G8R8A8_SRGB),
+            DxgiFormat::R16G16B16A16_Float => Some(vk::Format::R16G16B16A16_SFLOAT),
+            DxgiFormat::R32G32B32A32_Float => Some(vk::Format::R32G32B32A32_SFLOAT),
+            DxgiFormat::BC1_UNorm => Some(vk::Format::BC1_RGBA_UNORM_BLOCK),
+            DxgiFormat::BC1_UNorm_sRGB => Some(vk::Format::BC1_RGBA_SRGB_BLOCK),
+            DxgiFormat::BC2_UNorm => Some(vk::Format::BC2_UNORM_BLOCK),
+            DxgiFormat::BC2_UNorm_sRGB => Some(vk::Format::BC2_SRGB_BLOCK),
+            DxgiFormat::BC3_UNorm => Some(vk::Format::BC3_UNORM_BLOCK),
+            DxgiFormat::BC3_UNorm_sRGB => Some(vk::Format::BC3_SRGB_BLOCK),
+            DxgiFormat::BC4_UNorm => Some(vk::Format::BC4_UNORM_BLOCK),
+            DxgiFormat::BC4_SNorm => Some(vk::Format::BC4_SNORM_BLOCK),
+            DxgiFormat::BC5_UNorm => Some(vk::Format::BC5_UNORM_BLOCK),
+            DxgiFormat::BC5_SNorm => Some(vk::Format::BC5_SNORM_BLOCK),
+            DxgiFormat::BC6H_UF16 => Some(vk::Format::BC6H_UFLOAT_BLOCK),
+            DxgiFormat::BC6H_SF16 => Some(vk::Format::BC6H_SFLOAT_BLOCK),
+            DxgiFormat::BC7_UNorm => Some(vk::Format::BC7_UNORM_BLOCK),
+            DxgiFormat::BC7_UNorm_sRGB => Some(vk::Format::BC7_SRGB_BLOCK),
+            _ => None,
+        };
+    }
+
+    //Legacy D3D9 Names Describe Channels From The Most Significant Bit
+    match dds.get_d3d_format()?
+    {
+        D3DFormat::A8B8G8R8 => Some(vk::Format::R8G8B8A8_UNORM),
+        D3DFormat::A8R8G8B8 => Some(vk::Format::B8G8R8A8_UNORM),
+        D3DFormat::L8 => Some(vk::Format::R8_UNORM),
+        D3DFormat::DXT1 => Some(vk::Format::BC1_RGBA_UNORM_BLOCK),
+        D3DFormat::DXT3 => Some(vk::Format::BC2_UNORM_BLOCK),
+        D3DFormat::DXT5 => Some(vk::Format::BC3_UNORM_BLOCK),
+        D3DFormat::A16B16G16R16F => Some(vk::Format::R16G16B16A16_SFLOAT),
+        D3DFormat::A32B32G32R32F => Some(vk::Format::R32G32B32A32_SFLOAT),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use ddsfile::{Dds, DxgiFormat, D3DFormat, NewDxgiParams, NewD3dParams, D3D10ResourceDimension, AlphaMode, DataFormat, PixelFormatFlags};
+
+    fn dxgi_dds(format: DxgiFormat) -> Dds
+    {
+        Dds::new_dxgi(NewDxgiParams{
+            height: 4,
+            width: 4,
+            depth: None,
+            format,
+            mipmap_levels: None,
+            array_layers: None,
+            caps2: None,
+            is_cubemap: false,
+            resource_dimension: D3D10ResourceDimension::Texture2D,
+            alpha_mode: AlphaMode::Unknown,
+        }).expect("Failed To Create Test DDS!")
+    }
+
+    fn d3d_dds(format: D3DFormat) -> Dds
+    {
+        let mut dds = Dds::new_d3d(NewD3dParams{
+            height: 4,
+            width: 4,
+            depth: None,
+            format,
+            mipmap_levels: None,
+            caps2: None,
+        }).expect("Failed To Create Test DDS!");
+        //ddsfile Writes Luminance And Float Formats With The RGB Flag, Files From Other Tools Use The Luminance Flag Or A FourCC
+        if format == D3DFormat::L8
+        {
+            dds.header.spf.flags = PixelFormatFlags::LUMINANCE;
+        }
+        else if let Some(fourcc) = format.get_fourcc()
+        {
+            dds.header.spf.flags = PixelFormatFlags::FOURCC;
+            dds.header.spf.fourcc = Some(fourcc);
+            dds.header.spf.rgb_bit_count = None;
+        }
+        dds
+    }
+
+    #[test]
+    fn dxgi_formats_map_to_vulkan()
+    {
+        let cases = [
+            (DxgiFormat::R8_UNorm, Some(vk::Format::R8_UNORM)),
+            (DxgiFormat::R8G8B8A8_UNorm, Some(vk::Format::R8G8B8A8_UNORM)),
+            (DxgiFormat::R8G8B8A8_UNorm_sRGB, Some(vk::Format::R8G8B8A8_SRGB)),
+            (DxgiFormat::B8G8R8A8_UNorm_sRGB, Some(vk::Format::B8G8R8A8_SRGB)),
+            (DxgiFormat::R16G16B16A16_Float, Some(vk::Format::R16G16B16A16_SFLOAT)),
+            (DxgiFormat::BC1_UNorm, Some(vk::Format::BC1_RGBA_UNORM_BLOCK)),
+            (DxgiFormat::BC1_UNorm_sRGB, Some(vk::Format::BC1_RGBA_SRGB_BLOCK)),
+            (DxgiFormat::BC3_UNorm, Some(vk::Format::BC3_UNORM_BLOCK)),
+            (DxgiFormat::BC4_SNorm, Some(vk::Format::BC4_SNORM_BLOCK)),
+            (DxgiFormat::BC5_UNorm, Some(vk::Format::BC5_UNORM_BLOCK)),
+            (DxgiFormat::BC6H_UF16, Some(vk::Format::BC6H_UFLOAT_BLOCK)),
+            (DxgiFormat::BC7_UNorm_sRGB, Some(vk::Format::BC7_SRGB_BLOCK)),
+            (DxgiFormat::R10G10B10A2_UNorm, None),
+        ];
+        for (dxgi_format, expected) in cases
+        {
+            assert_eq!(dds_format_to_vk(&dxgi_dds(dxgi_format)), expected, "{:?}", dxgi_format);
+        }
+    }
+
+    #[test]
+    fn legacy_d3d_formats_map_to_vulkan()
+    {
+        let cases = [
+            (D3DFormat::A8B8G8R8, Some(vk::Format::R8G8B8A8_UNORM)),
+            (D3DFormat::A8R8G8B8, Some(vk::Format::B8G8R8A8_UNORM)),
+            (D3DFormat::L8, Some(vk::Format::R8_UNORM)),
+            (D3DFormat::DXT1, Some(vk::Format::BC1_RGBA_UNORM_BLOCK)),
+            (D3DFormat::DXT3, Some(vk::Format::BC2_UNORM_BLOCK)),
+            (D3DFormat::DXT5, Some(vk::Format::BC3_UNORM_BLOCK)),
+            (D3DFormat::A32B32G32R32F, Some(vk::Format::R32G32B32A32_SFLOAT)),
+            (D3DFormat::R5G6B5, None),
+        ];
+        for (d3d_format, expected) in cases
+        {
+            assert_eq!(dds_format_to_vk(&d3d_dds(d3d_format)), expected, "{:?}", d3d_format);
+        }
+    }
+
+    #[test]
+    fn color_space_picks_the_matching_variant()
+    {
+        let cases = [
+            (vk::Format::R8G8B8A8_UNORM, vk::Format::R8G8B8A8_SRGB, vk::Format::R8G8B8A8_UNORM),
+            (vk::Format::R8G8B8A8_SRGB, vk::Format::R8G8B8A8_SRGB, vk::Format::R8G8B8A8_UNORM),
+            (vk::Format::B8G8R8A8_UNORM, vk::Format::B8G8R8A8_SRGB, vk::Format::B8G8R8A8_UNORM),
+            (vk::Format::BC1_RGB_SRGB_BLOCK, vk::Format::BC1_RGB_SRGB_BLOCK, vk::Format::BC1_RGB_UNORM_BLOCK),
+            (vk::Format::BC1_RGBA_UNORM_BLOCK, vk::Format::BC1_RGBA_SRGB_BLOCK, vk::Format::BC1_RGBA_UNORM_BLOCK),
+            (vk::Format::BC3_SRGB_BLOCK, vk::Format::BC3_SRGB_BLOCK, vk::Format::BC3_UNORM_BLOCK),
+            (vk::Format::BC7_UNORM_BLOCK, vk::Format::BC7_SRGB_BLOCK, vk::Format::BC7_UNORM_BLOCK),
+            //No sRGB Variant, Left Alone Either Way
+            (vk::Format::BC5_UNORM_BLOCK, vk::Format::BC5_UNORM_BLOCK, vk::Format::BC5_UNORM_BLOCK),
+            (vk::Format::R16G16B16A16_SFLOAT, vk::Format::R16G16B16A16_SFLOAT, vk::Format::R16G16B16A16_SFLOAT),
+        ];
+        for (format, srgb, linear) in cases
+        {
+            assert_eq!(apply_color_space(format, TextureColorSpace::Srgb), srgb, "{:?}", format);
+            assert_eq!(apply_color_space(format, TextureColorSpace::Linear), linear, "{:?}", format);
+        }
+    }
+}
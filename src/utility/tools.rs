@@ -11,4 +11,10 @@ pub fn char_array_to_string(raw_string_array: &[c_char]) -> String
         .to_str()
         .expect("Failed to convert char array to string.")
         .to_owned()
-}
\ No newline at end of file
+}
+
+//Rounds value Up To The Next Multiple Of alignment
+pub fn align_up(value: u64, alignment: u64) -> u64
+{
+    (value + alignment - 1) / alignment * alignment
+}
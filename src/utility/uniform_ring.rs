@@ -0,0 +1,233 @@
+use ash::vk;
+use bytemuck::Pod;
+
+use crate::utility::{constants::*, structs::FrameSyncContext, tools::align_up, gpubuffer::{GPUBuffer, GPUBufferDesc}, memory_report::TrackedAllocator};
+
+pub const DEFAULT_UNIFORM_BYTES_PER_FRAME: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+pub struct UniformAllocation
+{
+    pub buffer: vk::Buffer,
+    //Offset From The Start Of The Whole Buffer, Pass As The Dynamic Offset When Binding
+    pub offset: u64,
+    pub size: u64,
+}
+
+impl UniformAllocation
+{
+    //UniformRingBuffer::new Refuses Rings Whose Offsets Could Pass u32::MAX
+    pub fn dynamic_offset(&self) -> u32
+    {
+        u32::try_from(self.offset).expect("Uniform Allocation Offset Does Not Fit A Dynamic Offset!")
+    }
+}
+
+//Dynamic Offsets Are u32 And Address The Whole Ring, Not Just One Partition
+fn ring_partition_size(bytes_per_frame: u64, alignment: u64) -> u64
+{
+    let partition_size = align_up(bytes_per_frame, alignment);
+    let ring_size = partition_size.checked_mul(MAX_FRAMES_IN_FLIGHT as u64);
+    assert!(ring_size.is_some_and(|ring_size| ring_size <= u32::MAX as u64 + 1)
+        , "Uniform Ring Of {} Bytes Per Frame Does Not Fit 32 Bit Dynamic Offsets!", bytes_per_frame);
+    partition_size
+}
+
+//Bump Allocator Over The Partitions, Kept Apart From The Buffer So It Needs No Device
+#[derive(Debug, Clone)]
+struct RingCursor
+{
+    partition_size: u64,
+    alignment: u64,
+    max_range: u64,
+    current_partition: usize,
+    head: u64,
+}
+
+impl RingCursor
+{
+    fn begin_frame(&mut self, frame_index: usize)
+    {
+        self.current_partition = frame_index % MAX_FRAMES_IN_FLIGHT;
+        self.head = 0;
+    }
+
+    //Offset From The Start Of The Whole Ring
+    fn allocate(&mut self, size: u64) -> Option<u64>
+    {
+        if size == 0 || size > self.max_range || size > self.partition_size - self.head
+        {
+            return None;
+        }
+
+        let offset = self.current_partition as u64 * self.partition_size + self.head;
+        self.head = align_up(self.head + size, self.alignment).min(self.partition_size);
+        Some(offset)
+    }
+}
+
+//One Persistently Mapped Buffer Split Into MAX_FRAMES_IN_FLIGHT Partitions
+//Each Frame Bumps Through Its Own Partition, Which Is Rewound Once That Frame's Fence Has Signaled
+pub struct UniformRingBuffer
+{
+    buffer: GPUBuffer,
+    cursor: RingCursor,
+}
+
+impl UniformRingBuffer
+{
+    pub fn new(
+        device: &ash::Device
+        , allocator: &mut TrackedAllocator
+        , device_limits: &vk::PhysicalDeviceLimits
+        , bytes_per_frame: u64) -> UniformRingBuffer
+    {
+        let alignment = device_limits.min_uniform_buffer_offset_alignment.max(1);
+        let partition_size = ring_partition_size(bytes_per_frame, alignment);
+
+        let buffer = GPUBuffer::create_gpu_buffer(
+            device
+            , allocator
+            , &GPUBufferDesc::uniform_buffer("Uniform Ring Buffer", partition_size * MAX_FRAMES_IN_FLIGHT as u64));
+
+        UniformRingBuffer{
+            buffer,
+            cursor: RingCursor{
+                partition_size,
+                alignment,
+                max_range: device_limits.max_uniform_buffer_range as u64,
+                current_partition: 0,
+                head: 0,
+            },
+        }
+    }
+
+    pub fn destroy_ring_buffer(&mut self, device: &ash::Device, allocator: &mut TrackedAllocator)
+    {
+        GPUBuffer::destroy_gpu_buffer(&mut self.buffer, device, allocator);
+    }
+
+    //Call Once Per Frame Before Allocating, With The Same frame_index Used For FrameSyncContext
+    //Waits On That Frame's In Flight Fence, So Memory Is Never Reused While The GPU Still Reads It
+    pub fn begin_frame(&mut self, device: &ash::Device, frame_sync_context: &FrameSyncContext, frame_index: usize)
+    {
+        let in_flight_fence = [frame_sync_context.in_flight_fences[frame_index]];
+        unsafe
+        {
+            device
+                .wait_for_fences(&in_flight_fence, true, std::u64::MAX)
+                .expect("Waiting Fence Time Out");
+        }
+
+        self.cursor.begin_frame(frame_index);
+    }
+
+    //None When This Frame's Partition Is Full, Or The Size Is Beyond maxUniformBufferRange
+    pub fn allocate(&mut self, size: u64) -> Option<UniformAllocation>
+    {
+        let offset = self.cursor.allocate(size)?;
+        Some(UniformAllocation{
+            buffer: self.buffer.buffer,
+            offset,
+            size,
+        })
+    }
+
+    //Copies data Into A Fresh Chunk Of This Frame's Partition
+    pub fn push<T: Pod>(&mut self, data: &T) -> Option<UniformAllocation>
+    {
+        let size = std::mem::size_of::<T>();
+        let allocation = self.allocate(size as u64)?;
+
+        let mapped_memory = self.buffer.allocation
+            .mapped_slice_mut()
+            .expect("Uniform Ring Buffer Is Not Host Visible!");
+        let start = allocation.offset as usize;
+        mapped_memory[start..start + size].copy_from_slice(bytemuck::bytes_of(data));
+
+        Some(allocation)
+    }
+
+    pub fn buffer(&self) -> vk::Buffer
+    {
+        self.buffer.buffer
+    }
+
+    pub fn partition_size(&self) -> u64
+    {
+        self.cursor.partition_size
+    }
+
+    pub fn used_bytes(&self) -> u64
+    {
+        self.cursor.head
+    }
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn cursor(partition_size: u64, alignment: u64) -> RingCursor
+    {
+        RingCursor{ partition_size, alignment, max_range: 16384, current_partition: 0, head: 0 }
+    }
+
+    #[test]
+    fn allocations_start_aligned()
+    {
+        let mut ring_cursor = cursor(1024, 256);
+        assert_eq!(ring_cursor.allocate(64), Some(0));
+        assert_eq!(ring_cursor.allocate(1), Some(256));
+        assert_eq!(ring_cursor.allocate(256), Some(512));
+        assert_eq!(ring_cursor.allocate(200), Some(768));
+        assert_eq!(ring_cursor.head, 1024);
+    }
+
+    #[test]
+    fn full_partitions_refuse_allocations()
+    {
+        let mut ring_cursor = cursor(512, 256);
+        assert_eq!(ring_cursor.allocate(300), Some(0));
+        assert_eq!(ring_cursor.allocate(256), None);
+        assert_eq!(ring_cursor.allocate(0), None);
+
+        //An Unaligned Partition End Clamps The Head Instead Of Running Past It
+        let mut unaligned = cursor(300, 256);
+        assert_eq!(unaligned.allocate(10), Some(0));
+        assert_eq!(unaligned.allocate(44), Some(256));
+        assert_eq!(unaligned.head, 300);
+        assert_eq!(unaligned.allocate(1), None);
+
+        let mut limited = RingCursor{ max_range: 128, ..cursor(1024, 256) };
+        assert_eq!(limited.allocate(129), None);
+        assert_eq!(limited.allocate(128), Some(0));
+    }
+
+    #[test]
+    fn frames_wrap_around_the_partitions()
+    {
+        let mut ring_cursor = cursor(1024, 256);
+        for frame_index in 0..MAX_FRAMES_IN_FLIGHT * 2 + 1
+        {
+            ring_cursor.begin_frame(frame_index);
+            let partition_start = (frame_index % MAX_FRAMES_IN_FLIGHT) as u64 * 1024;
+            assert_eq!(ring_cursor.allocate(64), Some(partition_start), "Frame {}", frame_index);
+            assert_eq!(ring_cursor.allocate(64), Some(partition_start + 256), "Frame {}", frame_index);
+        }
+    }
+
+    #[test]
+    fn rings_must_fit_dynamic_offsets()
+    {
+        assert_eq!(ring_partition_size(1000, 256), 1024);
+        let largest = (u32::MAX as u64 + 1) / MAX_FRAMES_IN_FLIGHT as u64;
+        assert_eq!(ring_partition_size(largest, 256), largest);
+        assert!(std::panic::catch_unwind(|| ring_partition_size(largest + 1, 256)).is_err());
+
+        let last_offset = UniformAllocation{ buffer: vk::Buffer::null(), offset: u32::MAX as u64, size: 1 };
+        assert_eq!(last_offset.dynamic_offset(), u32::MAX);
+    }
+}
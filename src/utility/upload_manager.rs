@@ -0,0 +1,645 @@
+use std::collections::VecDeque;
+
+use ash::vk;
+
+use crate::utility::{structs::QueueFamilyIndices, tools::align_up, gpubuffer::{GPUBuffer, GPUBufferDesc, GPUBufferError}, gpuimage::*, memory_report::TrackedAllocator};
+
+pub const DEFAULT_STAGING_RING_SIZE: u64 = 32 * 1024 * 1024;
+const MIN_STAGING_ALIGNMENT: u64 = 16;
+
+//Identifies The Batch An Upload Was Recorded Into, Batches Complete In Submission Order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UploadHandle
+{
+    batch_id: u64,
+}
+
+impl UploadHandle
+{
+    pub fn batch_id(&self) -> u64
+    {
+        self.batch_id
+    }
+}
+
+//What The Graphics Queue Has To Acquire Once An Upload Made On The Transfer Queue Completes
+#[derive(Debug, Clone, Copy)]
+enum UploadTarget
+{
+    Buffer{ buffer: vk::Buffer, offset: u64, size: u64 },
+    Image{ image: vk::Image, subresource_range: vk::ImageSubresourceRange, final_layout: vk::ImageLayout },
+}
+
+struct StagedData
+{
+    buffer: vk::Buffer,
+    offset: u64,
+    ring_bytes: u64,
+    dedicated_buffer: Option<GPUBuffer>,
+}
+
+struct UploadBatch
+{
+    id: u64,
+    command_buffer: vk::CommandBuffer,
+    fence: vk::Fence,
+    //Ring Bytes Released When The Batch Retires, Including Alignment And Wrap Padding
+    ring_bytes: u64,
+    //Uploads Too Large For The Ring Get Their Own Staging Buffer
+    dedicated_staging: Vec<GPUBuffer>,
+    targets: Vec<UploadTarget>,
+}
+
+//Batches Buffer And Image Uploads Through A Reusable Staging Ring, One Submission Per flush
+//Runs On A Dedicated Transfer Queue When The Device Has One, Otherwise On The Graphics Queue
+pub struct UploadManager
+{
+    queue: vk::Queue,
+    queue_family: u32,
+    graphics_family: u32,
+    command_pool: vk::CommandPool,
+    free_submissions: Vec<(vk::CommandBuffer, vk::Fence)>,
+
+    staging_ring: GPUBuffer,
+    ring_capacity: u64,
+    ring_head: u64,
+    ring_used: u64,
+    staging_alignment: u64,
+
+    recording: Option<UploadBatch>,
+    in_flight: VecDeque<UploadBatch>,
+    next_batch_id: u64,
+    completed_batch_id: u64,
+
+    unacquired_targets: Vec<UploadTarget>,
+    has_unacquired_writes: bool,
+}
+
+impl UploadManager
+{
+    pub fn new(
+        device: &ash::Device
+        , allocator: &mut TrackedAllocator
+        , queue_family_indices: &QueueFamilyIndices
+        , device_limits: &vk::PhysicalDeviceLimits
+        , ring_capacity: u64) -> UploadManager
+    {
+        let graphics_family = queue_family_indices.graphics_family.unwrap();
+        let queue_family = queue_family_indices.transfer_family.unwrap_or(graphics_family);
+
+        let queue = unsafe {
+            device.get_device_queue(queue_family, 0)
+        };
+
+        let command_pool_create_info = vk::CommandPoolCreateInfo::builder()
+            .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER | vk::CommandPoolCreateFlags::TRANSIENT)
+            .queue_family_index(queue_family);
+
+        let command_pool = unsafe {
+            device
+                .create_command_pool(&command_pool_create_info, None)
+                .expect("Failed To Create Upload Command Pool")
+        };
+
+        let staging_ring = GPUBuffer::create_gpu_buffer(
+            device
+            , allocator
+            , &GPUBufferDesc::staging_buffer("Upload Staging Ring", ring_capacity));
+
+        UploadManager{
+            queue,
+            queue_family,
+            graphics_family,
+            command_pool,
+            free_submissions: vec![],
+            staging_ring,
+            ring_capacity,
+            ring_head: 0,
+            ring_used: 0,
+            staging_alignment: device_limits.optimal_buffer_copy_offset_alignment.max(MIN_STAGING_ALIGNMENT),
+            recording: None,
+            in_flight: VecDeque::new(),
+            next_batch_id: 1,
+            completed_batch_id: 0,
+            unacquired_targets: vec![],
+            has_unacquired_writes: false,
+        }
+    }
+
+    pub fn destroy_upload_manager(&mut self, device: &ash::Device, allocator: &mut TrackedAllocator)
+    {
+        self.wait_idle(device, allocator);
+        unsafe
+        {
+            for &(_, fence) in self.free_submissions.iter()
+            {
+                device.destroy_fence(fence, None);
+            }
+            device.destroy_command_pool(self.command_pool, None);
+        }
+        self.free_submissions.clear();
+        GPUBuffer::destroy_gpu_buffer(&mut self.staging_ring, device, allocator);
+    }
+
+    pub fn uses_dedicated_transfer_queue(&self) -> bool
+    {
+        self.queue_family != self.graphics_family
+    }
+
+    //Copies data Into dst_buffer At dst_offset, dst_buffer Needs TRANSFER_DST Usage
+    pub fn upload_buffer(
+        &mut self
+        , device: &ash::Device
+        , allocator: &mut TrackedAllocator
+        , dst_buffer: vk::Buffer
+        , dst_offset: u64
+        , data: &[u8]) -> UploadHandle
+    {
+        //Zero Sized Copies Are Invalid, Nothing Is Staged And Batch 0 Counts As Already Complete
+        if data.is_empty()
+        {
+            return UploadHandle{ batch_id: 0 };
+        }
+
+        let staged_data = self.stage_data(device, allocator, data);
+        let uses_dedicated_transfer_queue = self.uses_dedicated_transfer_queue();
+        let (queue_family, graphics_family) = (self.queue_family, self.graphics_family);
+        let batch = self.begin_recording(device, staged_data.ring_bytes, staged_data.dedicated_buffer);
+
+        let copy_regions = [vk::BufferCopy{
+            src_offset: staged_data.offset,
+            dst_offset,
+            size: data.len() as u64,
+        }];
+
+        unsafe
+        {
+            device.cmd_copy_buffer(batch.command_buffer, staged_data.buffer, dst_buffer, &copy_regions);
+
+            if uses_dedicated_transfer_queue
+            {
+                let release_barriers = [vk::BufferMemoryBarrier::builder()
+                    .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                    .dst_access_mask(vk::AccessFlags::empty())
+                    .src_queue_family_index(queue_family)
+                    .dst_queue_family_index(graphics_family)
+                    .buffer(dst_buffer)
+                    .offset(dst_offset)
+                    .size(data.len() as u64)
+                    .build()];
+
+                device.cmd_pipeline_barrier(
+                    batch.command_buffer
+                    , vk::PipelineStageFlags::TRANSFER
+                    , vk::PipelineStageFlags::BOTTOM_OF_PIPE
+                    , vk::DependencyFlags::empty()
+                    , &[]
+                    , &release_barriers
+                    , &[]);
+            }
+        }
+
+        batch.targets.push(UploadTarget::Buffer{ buffer: dst_buffer, offset: dst_offset, size: data.len() as u64 });
+        UploadHandle{ batch_id: batch.id }
+    }
+
+    //Region Buffer Offsets Are Relative To pixel_data, The Image Ends Up In final_layout
+    //On A Dedicated Transfer Queue The Previous Contents Are Discarded, Since The Graphics Queue Still Owns Them
+    //Regions Are Checked Before Anything Is Staged, A Region Reading Past pixel_data Would Copy Another Upload's Bytes
+    pub fn upload_image(
+        &mut self
+        , device: &ash::Device
+        , allocator: &mut TrackedAllocator
+        , image: &mut GPUImage
+        , pixel_data: &[u8]
+        , copy_regions: &[vk::BufferImageCopy]
+        , final_layout: vk::ImageLayout) -> Result<UploadHandle, GPUBufferError>
+    {
+        image.check_upload_regions(pixel_data.len() as u64, copy_regions)?;
+
+        let staged_data = self.stage_data(device, allocator, pixel_data);
+        let uses_dedicated_transfer_queue = self.uses_dedicated_transfer_queue();
+        let (queue_family, graphics_family) = (self.queue_family, self.graphics_family);
+        let batch = self.begin_recording(device, staged_data.ring_bytes, staged_data.dedicated_buffer);
+
+        let staged_regions: Vec<vk::BufferImageCopy> = copy_regions
+            .iter()
+            .map(|region| vk::BufferImageCopy{
+                buffer_offset: region.buffer_offset + staged_data.offset,
+                ..*region
+            })
+            .collect();
+        let subresource_range = image.full_subresource_range();
+
+        unsafe
+        {
+            if uses_dedicated_transfer_queue
+            {
+                let transfer_barriers = [vk::ImageMemoryBarrier::builder()
+                    .src_access_mask(vk::AccessFlags::empty())
+                    .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                    .old_layout(vk::ImageLayout::UNDEFINED)
+                    .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                    .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .image(image.image)
+                    .subresource_range(subresource_range)
+                    .build()];
+
+                device.cmd_pipeline_barrier(
+                    batch.command_buffer
+                    , vk::PipelineStageFlags::TOP_OF_PIPE
+                    , vk::PipelineStageFlags::TRANSFER
+                    , vk::DependencyFlags::empty()
+                    , &[]
+                    , &[]
+                    , &transfer_barriers);
+            }
+            else
+            {
+                cmd_transition_image_layout(device, batch.command_buffer, image.image, subresource_range, image.layout, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+            }
+
+            device.cmd_copy_buffer_to_image(
+                batch.command_buffer
+                , staged_data.buffer
+                , image.image
+                , vk::ImageLayout::TRANSFER_DST_OPTIMAL
+                , &staged_regions);
+
+            if uses_dedicated_transfer_queue
+            {
+                //Release Half Of The Ownership Transfer, Also Performs The Layout Transition
+                let release_barriers = [vk::ImageMemoryBarrier::builder()
+                    .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                    .dst_access_mask(vk::AccessFlags::empty())
+                    .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                    .new_layout(final_layout)
+                    .src_queue_family_index(queue_family)
+                    .dst_queue_family_index(graphics_family)
+                    .image(image.image)
+                    .subresource_range(subresource_range)
+                    .build()];
+
+                device.cmd_pipeline_barrier(
+                    batch.command_buffer
+                    , vk::PipelineStageFlags::TRANSFER
+                    , vk::PipelineStageFlags::BOTTOM_OF_PIPE
+                    , vk::DependencyFlags::empty()
+                    , &[]
+                    , &[]
+                    , &release_barriers);
+            }
+            else
+            {
+                cmd_transition_image_layout(device, batch.command_buffer, image.image, subresource_range, vk::ImageLayout::TRANSFER_DST_OPTIMAL, final_layout);
+            }
+        }
+
+        image.layout = final_layout;
+        batch.targets.push(UploadTarget::Image{ image: image.image, subresource_range, final_layout });
+        Ok(UploadHandle{ batch_id: batch.id })
+    }
+
+    //Submits Everything Recorded Since The Last Flush, Call Once Per Frame
+    pub fn flush(&mut self, device: &ash::Device) -> Option<UploadHandle>
+    {
+        let batch = self.recording.take()?;
+        let handle = UploadHandle{ batch_id: batch.id };
+
+        let command_buffers = [batch.command_buffer];
+        let submit_infos = [vk::SubmitInfo::builder()
+            .command_buffers(&command_buffers)
+            .build()];
+
+        unsafe
+        {
+            device
+                .end_command_buffer(batch.command_buffer)
+                .expect("Failed To End Upload Command Buffer");
+            device
+                .queue_submit(self.queue, &submit_infos, batch.fence)
+                .expect("Failed To Submit Upload Batch");
+        }
+
+        self.in_flight.push_back(batch);
+        Some(handle)
+    }
+
+    //Retires Batches Whose Fence Has Signaled, Releasing Their Staging Memory
+    pub fn poll(&mut self, device: &ash::Device, allocator: &mut TrackedAllocator)
+    {
+        while let Some(batch) = self.in_flight.front()
+        {
+            let is_signaled = unsafe {
+                device
+                    .get_fence_status(batch.fence)
+                    .expect("Failed To Query Upload Fence")
+            };
+            if !is_signaled
+            {
+                break;
+            }
+            self.retire_oldest_batch(device, allocator);
+        }
+    }
+
+    //Reflects The Last poll, Completed Uploads Still Need cmd_acquire_completed_uploads Before Use
+    pub fn is_complete(&self, handle: UploadHandle) -> bool
+    {
+        handle.batch_id <= self.completed_batch_id
+    }
+
+    pub fn wait(&mut self, device: &ash::Device, allocator: &mut TrackedAllocator, handle: UploadHandle)
+    {
+        if self.recording.as_ref().map_or(false, |batch| batch.id <= handle.batch_id)
+        {
+            self.flush(device);
+        }
+
+        while !self.is_complete(handle)
+        {
+            let oldest_fence = match self.in_flight.front()
+            {
+                Some(batch) => [batch.fence],
+                None => break,
+            };
+            unsafe
+            {
+                device
+                    .wait_for_fences(&oldest_fence, true, std::u64::MAX)
+                    .expect("Waiting Upload Fence Time Out");
+            }
+            self.retire_oldest_batch(device, allocator);
+        }
+    }
+
+    pub fn wait_idle(&mut self, device: &ash::Device, allocator: &mut TrackedAllocator)
+    {
+        if let Some(handle) = self.flush(device)
+        {
+            self.wait(device, allocator, handle);
+        }
+        else if let Some(batch) = self.in_flight.back()
+        {
+            let handle = UploadHandle{ batch_id: batch.id };
+            self.wait(device, allocator, handle);
+        }
+    }
+
+    //Records Into A Graphics Command Buffer, Outside Of A Render Pass, Whatever Makes Completed Uploads Visible:
+    //Queue Family Acquire Barriers On A Dedicated Transfer Queue, Otherwise A Single Transfer Write Barrier
+    pub unsafe fn cmd_acquire_completed_uploads(&mut self, device: &ash::Device, command_buffer: vk::CommandBuffer)
+    {
+        if self.uses_dedicated_transfer_queue()
+        {
+            if self.unacquired_targets.is_empty()
+            {
+                return;
+            }
+
+            let mut buffer_barriers = vec![];
+            let mut image_barriers = vec![];
+            let mut dst_stage_mask = vk::PipelineStageFlags::empty();
+            for target in self.unacquired_targets.drain(..)
+            {
+                match target
+                {
+                    UploadTarget::Buffer{ buffer, offset, size } =>
+                    {
+                        buffer_barriers.push(vk::BufferMemoryBarrier::builder()
+                            .src_access_mask(vk::AccessFlags::empty())
+                            .dst_access_mask(vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE)
+                            .src_queue_family_index(self.queue_family)
+                            .dst_queue_family_index(self.graphics_family)
+                            .buffer(buffer)
+                            .offset(offset)
+                            .size(size)
+                            .build());
+                        dst_stage_mask |= vk::PipelineStageFlags::ALL_COMMANDS;
+                    },
+                    UploadTarget::Image{ image, subresource_range, final_layout } =>
+                    {
+                        let (dst_access_mask, layout_stage_mask) = layout_access_and_stage(final_layout);
+                        image_barriers.push(vk::ImageMemoryBarrier::builder()
+                            .src_access_mask(vk::AccessFlags::empty())
+                            .dst_access_mask(dst_access_mask)
+                            .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                            .new_layout(final_layout)
+                            .src_queue_family_index(self.queue_family)
+                            .dst_queue_family_index(self.graphics_family)
+                            .image(image)
+                            .subresource_range(subresource_range)
+                            .build());
+                        dst_stage_mask |= layout_stage_mask;
+                    },
+                }
+            }
+
+            device.cmd_pipeline_barrier(
+                command_buffer
+                , vk::PipelineStageFlags::TOP_OF_PIPE
+                , dst_stage_mask
+                , vk::DependencyFlags::empty()
+                , &[]
+                , &buffer_barriers
+                , &image_barriers);
+        }
+        else if self.has_unacquired_writes
+        {
+            let memory_barriers = [vk::MemoryBarrier::builder()
+                .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                .dst_access_mask(vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE)
+                .build()];
+
+            device.cmd_pipeline_barrier(
+                command_buffer
+                , vk::PipelineStageFlags::TRANSFER
+                , vk::PipelineStageFlags::ALL_COMMANDS
+                , vk::DependencyFlags::empty()
+                , &memory_barriers
+                , &[]
+                , &[]);
+        }
+        self.unacquired_targets.clear();
+        self.has_unacquired_writes = false;
+    }
+
+    pub fn staging_ring_used(&self) -> u64
+    {
+        self.ring_used
+    }
+
+    //Copies data Into The Ring, Making Room By Submitting Or Retiring Batches When It Is Full
+    fn stage_data(&mut self, device: &ash::Device, allocator: &mut TrackedAllocator, data: &[u8]) -> StagedData
+    {
+        let size = data.len() as u64;
+        if size > self.ring_capacity
+        {
+            let mut dedicated_buffer = GPUBuffer::create_gpu_buffer(
+                device
+                , allocator
+                , &GPUBufferDesc::staging_buffer("Oversized Upload Staging Buffer", size));
+            dedicated_buffer.allocation
+                .mapped_slice_mut()
+                .expect("Staging Buffer Is Not Host Visible!")[..data.len()]
+                .copy_from_slice(data);
+
+            return StagedData{
+                buffer: dedicated_buffer.buffer,
+                offset: 0,
+                ring_bytes: 0,
+                dedicated_buffer: Some(dedicated_buffer),
+            };
+        }
+
+        loop
+        {
+            if let Some((offset, ring_bytes)) = self.try_allocate_ring(size)
+            {
+                let start = offset as usize;
+                self.staging_ring.allocation
+                    .mapped_slice_mut()
+                    .expect("Staging Ring Is Not Host Visible!")[start..start + data.len()]
+                    .copy_from_slice(data);
+
+                return StagedData{
+                    buffer: self.staging_ring.buffer,
+                    offset,
+                    ring_bytes,
+                    dedicated_buffer: None,
+                };
+            }
+
+            if self.recording.as_ref().map_or(false, |batch| batch.ring_bytes > 0)
+            {
+                self.flush(device);
+            }
+            match self.in_flight.front()
+            {
+                Some(batch) =>
+                {
+                    let oldest_handle = UploadHandle{ batch_id: batch.id };
+                    self.wait(device, allocator, oldest_handle);
+                },
+                None => panic!("Staging Ring Cannot Fit {} Bytes With Nothing In Flight", size),
+            }
+        }
+    }
+
+    //Returns The Offset And How Many Ring Bytes The Allocation Consumed
+    fn try_allocate_ring(&mut self, size: u64) -> Option<(u64, u64)>
+    {
+        if self.ring_used == 0
+        {
+            self.ring_head = 0;
+        }
+
+        let aligned_head = align_up(self.ring_head, self.staging_alignment);
+        let (offset, ring_bytes) = if aligned_head + size <= self.ring_capacity
+        {
+            (aligned_head, aligned_head - self.ring_head + size)
+        }
+        else
+        {
+            //Wrap Around, The Tail End Of The Ring Is Wasted Until This Batch Retires
+            (0, self.ring_capacity - self.ring_head + size)
+        };
+
+        if self.ring_used + ring_bytes > self.ring_capacity
+        {
+            return None;
+        }
+
+        self.ring_head = offset + size;
+        self.ring_used += ring_bytes;
+        Some((offset, ring_bytes))
+    }
+
+    fn begin_recording(&mut self, device: &ash::Device, ring_bytes: u64, dedicated_buffer: Option<GPUBuffer>) -> &mut UploadBatch
+    {
+        if self.recording.is_none()
+        {
+            let (command_buffer, fence) = self.free_submissions.pop().unwrap_or_else(|| {
+                let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::builder()
+                    .command_buffer_count(1)
+                    .command_pool(self.command_pool)
+                    .level(vk::CommandBufferLevel::PRIMARY);
+                let fence_create_info = vk::FenceCreateInfo::builder();
+
+                unsafe
+                {
+                    (
+                        device
+                            .allocate_command_buffers(&command_buffer_allocate_info)
+                            .expect("Failed To Allocate Upload Command Buffer")[0],
+                        device
+                            .create_fence(&fence_create_info, None)
+                            .expect("Failed To Create Upload Fence"),
+                    )
+                }
+            });
+
+            let command_buffer_begin_info = vk::CommandBufferBeginInfo::builder()
+                .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+
+            unsafe
+            {
+                device
+                    .begin_command_buffer(command_buffer, &command_buffer_begin_info)
+                    .expect("Begin Upload Command Buffer Failed!");
+            }
+
+            self.recording = Some(UploadBatch{
+                id: self.next_batch_id,
+                command_buffer,
+                fence,
+                ring_bytes: 0,
+                dedicated_staging: vec![],
+                targets: vec![],
+            });
+            self.next_batch_id += 1;
+        }
+
+        let batch = self.recording.as_mut().unwrap();
+        batch.ring_bytes += ring_bytes;
+        batch.dedicated_staging.extend(dedicated_buffer);
+        batch
+    }
+
+    fn retire_oldest_batch(&mut self, device: &ash::Device, allocator: &mut TrackedAllocator)
+    {
+        let mut batch = match self.in_flight.pop_front()
+        {
+            Some(batch) => batch,
+            None => return,
+        };
+
+        unsafe
+        {
+            device
+                .reset_fences(&[batch.fence])
+                .expect("Failed To Reset Upload Fence");
+            device
+                .reset_command_buffer(batch.command_buffer, vk::CommandBufferResetFlags::empty())
+                .expect("Failed To Reset Upload Command Buffer");
+        }
+
+        for dedicated_buffer in batch.dedicated_staging.iter_mut()
+        {
+            GPUBuffer::destroy_gpu_buffer(dedicated_buffer, device, allocator);
+        }
+
+        self.ring_used -= batch.ring_bytes;
+        self.completed_batch_id = batch.id;
+        if self.uses_dedicated_transfer_queue()
+        {
+            self.unacquired_targets.append(&mut batch.targets);
+        }
+        else
+        {
+            self.has_unacquired_writes = true;
+        }
+        self.free_submissions.push((batch.command_buffer, batch.fence));
+    }
+}
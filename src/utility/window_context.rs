@@ -9,6 +9,12 @@ pub trait WindowLoopContext {
     fn on_drawframe(&mut self){
 
     }
+    fn on_key_pressed(&mut self, _key: VirtualKeyCode) {
+    }
+    //Polled After Every Frame, Some Only When The Title Should Change
+    fn take_window_title(&mut self) -> Option<String> {
+        None
+    }
 }
 
 pub struct WindowContext{
@@ -61,6 +67,10 @@ impl WindowContext{
                                             dbg!();
                                             *control_flow = ControlFlow::Exit;
                                         },
+                                        | (Some(key), ElementState::Pressed) =>
+                                        {
+                                            context.on_key_pressed(key);
+                                        },
                                         | _ => {},
                                     }
                                 },
@@ -77,6 +87,10 @@ impl WindowContext{
                 | Event::RedrawRequested(_window_id) =>
                 {
                     context.on_drawframe();
+                    if let Some(title) = context.take_window_title()
+                    {
+                        self.window.set_title(&title);
+                    }
                 }
                 | _ => {},
             }
@@ -123,6 +137,10 @@ pub fn main_loop<T: WindowLoopContext + 'static>(event_loop: EventLoop<()>, mut
                                         dbg!();
                                         *control_flow = ControlFlow::Exit;
                                     },
+                                    | (Some(key), ElementState::Pressed) =>
+                                    {
+                                        context.on_key_pressed(key);
+                                    },
                                     | _ => {},
                                 }
                             },
@@ -139,6 +157,10 @@ pub fn main_loop<T: WindowLoopContext + 'static>(event_loop: EventLoop<()>, mut
             | Event::RedrawRequested(_window_id) =>
             {
                 context.on_drawframe();
+                if let Some(title) = context.take_window_title()
+                {
+                    window.set_title(&title);
+                }
             }
             | _ => {},
         }
@@ -1,43 +1,46 @@
 use std::ffi::CString;
+use std::sync::Arc;
 
 use crate::utility::{constants::*
     , debug::*, structs::*, context::*, swapchain::*
-    , file_system::*, commandbuffers::*, gpubuffer::*, window_context::*};
+    , file_system::*, commandbuffers::*, gpubuffer::*, gpuimage::*, sampler_cache::*, descriptors::*, bindless::*, shader_reflection::*, push_constants::*, uniform_ring::*, upload_manager::*, deletion_queue::*, memory_report::*, shader_include::*, shader_cache::*, shader_hot_reload::*, pipeline_cache::*, graphics_pipeline::*, gpu_profiler::*, window_context::*};
 
-use ash::vk::{self, PipelineShaderStageCreateFlags, ShaderStageFlags
-    , PrimitiveTopology, Offset2D, CullModeFlags, FrontFace
-    , PolygonMode, SampleCountFlags, AttachmentReference
+use ash::vk::{self, ShaderStageFlags
+    , SampleCountFlags, AttachmentReference
     , CommandBufferUsageFlags, CommandPoolResetFlags, CommandPool, CommandBufferLevel};
 
 use gpu_allocator::vulkan;
 
 use memoffset::offset_of;
 
-use glam::{Vec2, Vec3, Vec4};
+use bytemuck::{Pod, Zeroable};
+
+use glam::{Mat4, Vec2, Vec3, Vec4};
 
 use winit::dpi::PhysicalSize;
+use winit::event::VirtualKeyCode;
 
 #[repr(C)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
 struct Vertex
 {
     pos: Vec2,//[f32; 2],
     color: Vec3,//[f32; 3],
 }
 
-impl Vertex
+impl VertexInputLayout for Vertex
 {
-    fn get_binding_description() -> [vk::VertexInputBindingDescription; 1]
+    fn get_binding_descriptions() -> Vec<vk::VertexInputBindingDescription>
     {
-        [vk::VertexInputBindingDescription{
+        vec![vk::VertexInputBindingDescription{
             binding: 0,
             stride: std::mem::size_of::<Self>() as u32,
             input_rate: vk::VertexInputRate::VERTEX,
         }]
     }
 
-    fn get_attribute_descriptions() -> [vk::VertexInputAttributeDescription; 2]{
-        [vk::VertexInputAttributeDescription{
+    fn get_attribute_descriptions() -> Vec<vk::VertexInputAttributeDescription>{
+        vec![vk::VertexInputAttributeDescription{
             binding: 0,
             location: 0,
             format: vk::Format::R32G32_SFLOAT,
@@ -52,6 +55,32 @@ impl Vertex
     }
 }
 
+//Matches The DrawConstants Push Constant Block In testShader.glsl
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct DrawConstants
+{
+    transform: Mat4,
+}
+
+//Matches The FrameUniforms Block In testShader.glsl, Bound With A Dynamic Offset Into The Uniform Ring
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct FrameUniforms
+{
+    view_projection: Mat4,
+    //x: Seconds Since Start, y: Frame Delta, z: Frame Number
+    time: Vec4,
+}
+
+//Fixed Function State And Shader Paths, Attachment Formats And Sample Count Are Filled In At Runtime
+const TRIANGLE_PIPELINE_PATH: &str = "pipelines/triangle.json";
+
+const FRAME_UNIFORMS_SET: u32 = 0;
+const FRAME_UNIFORMS_BINDING: u32 = 0;
+
+const DRAW_CONSTANTS_RANGE: PushConstantRange<DrawConstants> = PushConstantRange::new(ShaderStageFlags::VERTEX, 0);
+
 const VERTICES_DATA: [Vertex; 3] = 
 [
     Vertex{
@@ -75,6 +104,7 @@ pub struct VulkanApp
     debug_utils_loader: ash::extensions::ext::DebugUtils,
     debug_messenger: vk::DebugUtilsMessengerEXT,
     _physical_device: vk::PhysicalDevice,
+    device_limits: vk::PhysicalDeviceLimits,
     device: ash::Device,
     _graphics_queue: vk::Queue,
     _present_queue: vk::Queue,
@@ -89,6 +119,14 @@ pub struct VulkanApp
     pipeline_layout: vk::PipelineLayout,
     graphics_pipeline: vk::Pipeline,
 
+    msaa_samples: vk::SampleCountFlags,
+    supported_msaa_samples: vk::SampleCountFlags,
+    depth_format: vk::Format,
+    //Only Exists When Multisampling, Otherwise We Render Directly Into The Swapchain Image
+    msaa_color_target: Option<GPUImage>,
+    depth_target: GPUImage,
+    render_targets_dirty: bool,
+
     frame_sync_context: FrameSyncContext,
 
     current_frame: usize,
@@ -100,9 +138,49 @@ pub struct VulkanApp
 
     window_resized : bool,
 
-    memory_allocator : std::mem::ManuallyDrop<vulkan::Allocator>,
-
-    vertex_buffer : GPUBuffer,
+    start_time : std::time::Instant,
+    last_frame_time : std::time::Instant,
+    //Base Title, The FPS Counter Is Appended When IS_PAINT_FPS_COUNTER Is Set
+    window_title : String,
+    //Handed To The Window Loop Through take_window_title
+    pending_window_title : Option<String>,
+    gpu_profiler : GpuProfiler,
+
+    memory_allocator : std::mem::ManuallyDrop<TrackedAllocator>,
+
+    sampler_cache : SamplerCache,
+    //Seeded From PIPELINE_CACHE_PATH, Every Pipeline Is Created Through It
+    pipeline_cache : PipelineCache,
+    //Owns graphics_pipeline, Which Is Removed From It Before Being Retired
+    graphics_pipeline_cache : GraphicsPipelineCache,
+    //As Loaded From TRIANGLE_PIPELINE_PATH
+    triangle_pipeline_desc : GraphicsPipelineDesc,
+    descriptor_layout_cache : DescriptorLayoutCache,
+    //Owns The Include Resolver, Which Knows Which Headers Each Shader Pulled In
+    shader_hot_reloader : ShaderHotReloader,
+    //Last Shaders That Built A Valid Pipeline, Reused When The Pipeline Is Recreated On Resize
+    vertex_shader : CompiledShader,
+    fragment_shader : CompiledShader,
+    //A Reloaded Pipeline Must Keep This Layout, frame_uniforms_set Was Allocated Against It
+    frame_uniforms_set_layout : vk::DescriptorSetLayout,
+    //None When The Device Lacks The Descriptor Indexing Features
+    bindless_heap : Option<BindlessHeap>,
+
+    uniform_ring : UniformRingBuffer,
+    upload_manager : UploadManager,
+    //Long Lived Sets, Unlike The Per Frame Ones Handed Out By FrameBoundCommandGroup
+    persistent_descriptor_allocator : DescriptorAllocator,
+    frame_uniforms_set : vk::DescriptorSet,
+
+    //Destroys Released Resources Once No In Flight Frame Can Still Use Them
+    deletion_queue : DeletionQueue,
+    memory_budget_tracker : MemoryBudgetTracker,
+
+    vertex_buffer : TypedGPUBuffer<Vertex>,
+    //Slot Of vertex_buffer In The Bindless Storage Buffer Array, For Shaders That Pull Vertices Themselves
+    vertex_buffer_bindless : Option<BindlessHandle>,
+    //The Triangle Is Only Drawn Once This Upload Has Completed
+    vertex_upload : UploadHandle,
 }
 
 
@@ -116,12 +194,16 @@ impl VulkanApp
         };
         let instance = create_instance(&entry, title, &VALIDATION);
         
-        let (debug_utils_loader, debug_messenger) = setup_debug_utils(&entry, &instance);
+        let (debug_utils_loader, debug_messenger) = setup_debug_utils(&entry, &instance, &VALIDATION);
 
         let surface_context = create_surface(&entry, &instance, window);
 
         let physical_device = pick_physical_device(&instance, &surface_context);
 
+        let device_limits = unsafe {
+            instance.get_physical_device_properties(physical_device).limits
+        };
+
         let (logical_device, queue_family_indices) = create_logical_device(&instance, physical_device, &surface_context, &VALIDATION);
 
         let graphics_queue = unsafe {
@@ -145,23 +227,76 @@ impl VulkanApp
             swapchain_context.swapchain_format,
             &swapchain_context.swapchain_images);
 
+        let mut memory_allocator = TrackedAllocator::new(vulkan::Allocator::new(
+            &vulkan::AllocatorCreateDesc{
+                instance: instance.clone(),
+                device: logical_device.clone(),
+                physical_device: physical_device,
+                debug_settings: Default::default(),
+                buffer_device_address: true,
+            }
+        ).expect("Failed To Create GPU Memory Allocator!"));
+
+        //Multisample Render Targets
+        let supported_msaa_samples = get_usable_sample_counts(&instance, physical_device);
+        let msaa_samples = snap_sample_count(supported_msaa_samples, SampleCountFlags::TYPE_4);
+        let depth_format = find_depth_format(&instance, physical_device);
+
+        let (msaa_color_target, depth_target) = VulkanApp::create_render_targets(
+            &logical_device
+            , &mut memory_allocator
+            , &swapchain_context
+            , depth_format
+            , msaa_samples);
+
         //Render Pass
-        let render_pass = VulkanApp::create_render_pass(&logical_device, swapchain_context.swapchain_format);
+        let render_pass = VulkanApp::create_render_pass(&logical_device, swapchain_context.swapchain_format, depth_format, msaa_samples);
 
         //Swapchain Framebuffers
-        let mut swapchain_framebuffers = vec![];
-        for &image_view in swapchain_image_views.iter()
+        let swapchain_framebuffers = VulkanApp::create_swapchain_framebuffers(
+            &logical_device
+            , render_pass
+            , &swapchain_image_views
+            , msaa_color_target.as_ref()
+            , &depth_target
+            , &swapchain_context.swapchain_extent);
+
+        //Graphics Pipeline
+        let mut descriptor_layout_cache = DescriptorLayoutCache::new();
+        let shader_include_resolver = Arc::new(ShaderIncludeResolver::with_default_search_paths());
+        let shader_cache = match ShaderCache::new(SHADER_CACHE_DIRECTORY)
         {
-            let frame_buffer = create_framebuffer(
-                &logical_device
-                , render_pass
-                , &[image_view]
-                , &swapchain_context.swapchain_extent);
-            swapchain_framebuffers.push(frame_buffer);
+            Ok(shader_cache) => Some(Arc::new(shader_cache)),
+            Err(error) =>
+            {
+                println!("Failed To Open Shader Cache {}, Shaders Will Always Be Compiled: {}", SHADER_CACHE_DIRECTORY, error);
+                None
+            }
+        };
+        let triangle_pipeline_desc = GraphicsPipelineDesc::load(TRIANGLE_PIPELINE_PATH)
+            .unwrap_or_else(|error| panic!("{}: {}", TRIANGLE_PIPELINE_PATH, error));
+        if triangle_pipeline_desc.vertex_layout != VertexLayoutDesc::from_type::<Vertex>()
+        {
+            panic!("{}: Vertex Layout Does Not Match The Vertex Struct", TRIANGLE_PIPELINE_PATH);
         }
+        let (vertex_shader, fragment_shader) = VulkanApp::compile_triangle_shaders(&triangle_pipeline_desc, &shader_include_resolver, shader_cache.as_deref());
+        let pipeline_cache = PipelineCache::new(&instance, physical_device, &logical_device, PIPELINE_CACHE_PATH);
+        let mut graphics_pipeline_cache = GraphicsPipelineCache::new();
+        let (graphics_pipeline, pipeline_layout, set_layouts) = VulkanApp::create_graphics_pipeline(
+            &logical_device
+            , &device_limits
+            , render_pass
+            , pipeline_cache.handle()
+            , &mut graphics_pipeline_cache
+            , &mut descriptor_layout_cache
+            , &VulkanApp::pipeline_desc_for_targets(&triangle_pipeline_desc, swapchain_context.swapchain_format, depth_format, msaa_samples)
+            , &vertex_shader
+            , &fragment_shader)
+            .unwrap_or_else(|error| panic!("{} Does Not Match The Triangle Pipeline: {}", TRIANGLE_PIPELINE_PATH, error));
 
-        //Graphics Pipeline
-        let (graphics_pipeline, pipeline_layout) = VulkanApp::create_graphics_pipeline(&logical_device, &swapchain_context, render_pass);
+        let shader_hot_reloader = ShaderHotReloader::new(shader_include_resolver.clone(), shader_cache);
+        shader_hot_reloader.watch(&triangle_pipeline_desc.vertex_shader, ShaderType::Vertex);
+        shader_hot_reloader.watch(&triangle_pipeline_desc.fragment_shader, ShaderType::Fragment);
 
 
         let frame_sync_context = FrameSyncContext::new(&logical_device);
@@ -175,26 +310,55 @@ impl VulkanApp
 
         let onetime_command_group = OneTimeSubmitCommandGroup::new(&logical_device, &queue_family_indices, CommandBufferLevel::PRIMARY);
 
-        let mut memory_allocator = vulkan::Allocator::new(
-            &vulkan::AllocatorCreateDesc{
-                instance: instance.clone(),
-                device: logical_device.clone(),
-                physical_device: physical_device,
-                debug_settings: Default::default(),
-                buffer_device_address: true,
-            }
-        ).expect("Failed To Create GPU Memory Allocator!");
+        let sampler_cache = SamplerCache::new(&instance, physical_device);
+        let mut bindless_heap = if BindlessHeap::is_supported(&instance, physical_device)
+        {
+            Some(BindlessHeap::new(&instance, physical_device, &logical_device))
+        }
+        else
+        {
+            println!("Descriptor Indexing Not Supported, Bindless Mode Disabled");
+            None
+        };
 
-        let vertex_buffer = GPUBuffer::create_gpu_buffer(
+        let vertex_buffer_desc = GPUBufferDesc::vertex_buffer("Triangle Vertex Buffer", 0);
+        let vertex_buffer = TypedGPUBuffer::<Vertex>::new(
             &logical_device
             , &mut memory_allocator
-            , std::mem::size_of_val(&VERTICES_DATA) as u64
-            , vk::BufferUsageFlags::VERTEX_BUFFER | vk::BufferUsageFlags::TRANSFER_DST
-            , gpu_allocator::MemoryLocation::GpuOnly);
-
-
-
-
+            , VERTICES_DATA.len()
+            , &GPUBufferDesc{ usage: vertex_buffer_desc.usage | vk::BufferUsageFlags::STORAGE_BUFFER, ..vertex_buffer_desc });
+        let vertex_buffer_bindless = bindless_heap
+            .as_mut()
+            .map(|bindless_heap| bindless_heap.register_storage_buffer(&logical_device, &vertex_buffer.gpu_buffer));
+
+        let mut upload_manager = UploadManager::new(&logical_device, &mut memory_allocator, &queue_family_indices, &device_limits, DEFAULT_STAGING_RING_SIZE);
+        let vertex_upload = upload_manager.upload_buffer(&logical_device, &mut memory_allocator, vertex_buffer.buffer(), 0, bytemuck::cast_slice(&VERTICES_DATA));
+
+        //Per Frame Uniforms, One Set Addresses Every Partition Through Its Dynamic Offset
+        let uniform_ring = UniformRingBuffer::new(&logical_device, &mut memory_allocator, &device_limits, DEFAULT_UNIFORM_BYTES_PER_FRAME);
+        let mut persistent_descriptor_allocator = DescriptorAllocator::new();
+        let frame_uniforms_set = persistent_descriptor_allocator.allocate(&logical_device, set_layouts[FRAME_UNIFORMS_SET as usize]);
+        DescriptorWriter::new()
+            .write_buffer(
+                FRAME_UNIFORMS_BINDING
+                , vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC
+                , uniform_ring.buffer()
+                , 0
+                , std::mem::size_of::<FrameUniforms>() as u64)
+            .update_set(&logical_device, frame_uniforms_set);
+
+        let memory_budget_tracker = MemoryBudgetTracker::new(physical_device, is_memory_budget_supported(&instance, physical_device));
+
+        let gpu_profiler = GpuProfiler::new(
+            &instance
+            , physical_device
+            , &logical_device
+            , queue_family_indices.graphics_family.unwrap()
+            , MAX_FRAMES_IN_FLIGHT
+            , GPU_PROFILER_MAX_SCOPES
+            , GPU_PROFILER_PIPELINE_STATISTICS);
+
+        let start_time = std::time::Instant::now();
 
         VulkanApp 
         { 
@@ -203,6 +367,7 @@ impl VulkanApp
             debug_utils_loader,
             debug_messenger,
             _physical_device: physical_device,
+            device_limits,
             device: logical_device,
             _graphics_queue: graphics_queue,
             _present_queue: present_queue,
@@ -218,6 +383,13 @@ impl VulkanApp
             pipeline_layout,
             graphics_pipeline,
 
+            msaa_samples,
+            supported_msaa_samples,
+            depth_format,
+            msaa_color_target,
+            depth_target,
+            render_targets_dirty: false,
+
             frame_sync_context,
 
             current_frame: 0,
@@ -227,25 +399,48 @@ impl VulkanApp
             onetime_command_group: onetime_command_group,
 
             window_resized: false,
+            start_time,
+            last_frame_time: start_time,
+            window_title: title.to_owned(),
+            pending_window_title: None,
+            gpu_profiler,
 
             memory_allocator: std::mem::ManuallyDrop::new(memory_allocator),
 
+            sampler_cache,
+            pipeline_cache,
+            graphics_pipeline_cache,
+            triangle_pipeline_desc,
+            descriptor_layout_cache,
+            shader_hot_reloader,
+            vertex_shader,
+            fragment_shader,
+            frame_uniforms_set_layout: set_layouts[FRAME_UNIFORMS_SET as usize],
+            bindless_heap,
+
+            uniform_ring,
+            upload_manager,
+            persistent_descriptor_allocator,
+            frame_uniforms_set,
+
+            deletion_queue: DeletionQueue::new(),
+            memory_budget_tracker,
+
             vertex_buffer: vertex_buffer,
+            vertex_buffer_bindless,
+            vertex_upload,
         }
     }
 
     fn draw_frame(&mut self)
     {
-        if(self.current_rendered_frame == 0)
-        {
-            self.upload_vertex_buffer_data_through_tmp_command();
-        }
-
-        if(self.window_resized)
+        if(self.window_resized || self.render_targets_dirty)
         {
             self.window_resized = false;
+            self.render_targets_dirty = false;
             self.on_resize();
         }
+        self.reload_changed_shaders();
         // Do Drawing
         let wait_fences = [self.frame_sync_context.in_flight_fences[self.current_frame]];
 
@@ -266,6 +461,25 @@ impl VulkanApp
                     .expect("Failed To Aquire Next Frame Image")
         };
 
+        //The Fence Wait Above Means This Frame In Flight's Queries Have Results
+        self.gpu_profiler.begin_frame(&self.device, self.current_frame, self.current_rendered_frame as u64);
+        if IS_PAINT_FPS_COUNTER && self.gpu_profiler.fps_updated()
+        {
+            self.pending_window_title = Some(format!(
+                "{} - {:.0} FPS ({:.2} ms)", self.window_title, self.gpu_profiler.fps(), self.gpu_profiler.frame_milliseconds()));
+        }
+
+        self.deletion_queue.begin_frame(&self.device, &mut self.memory_allocator, self.current_rendered_frame as u64);
+        if self.current_rendered_frame as u64 % MEMORY_BUDGET_CHECK_INTERVAL == 0
+        {
+            self.memory_budget_tracker.check_budgets(&self.instance);
+        }
+
+        if let Some(bindless_heap) = self.bindless_heap.as_mut()
+        {
+            bindless_heap.begin_frame(self.current_rendered_frame as u64);
+        }
+
         let wait_semaphores = [self.frame_sync_context.image_available_semaphores[self.current_frame]];
         let wait_stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
         let signal_semaphores = [self.frame_sync_context.render_finished_semaphores[self.current_frame]];
@@ -277,11 +491,43 @@ impl VulkanApp
 
         let cmd_buffer = cmd_group.allocate_command_buffers(&self.device, 1);
 
+        //Uploads Queued Since Last Frame Go Out In One Submission
+        self.upload_manager.poll(&self.device, &mut self.memory_allocator);
+        self.upload_manager.flush(&self.device);
+        let vertex_buffer_ready = self.upload_manager.is_complete(self.vertex_upload);
+
+        //The Fence Wait Above Already Covers This Partition, begin_frame Only Rewinds It
+        self.uniform_ring.begin_frame(&self.device, &self.frame_sync_context, self.current_frame);
+
+        let now = std::time::Instant::now();
+        let elapsed_seconds = (now - self.start_time).as_secs_f32();
+        let extent = self.swapchain_context.swapchain_extent;
+        let aspect_ratio = extent.width as f32 / extent.height.max(1) as f32;
+        let frame_uniforms = FrameUniforms{
+            view_projection: Mat4::orthographic_rh(-aspect_ratio, aspect_ratio, -1.0, 1.0, 0.0, 1.0),
+            time: Vec4::new(elapsed_seconds, (now - self.last_frame_time).as_secs_f32(), self.current_rendered_frame as f32, 0.0),
+        };
+        self.last_frame_time = now;
+        let frame_uniforms_allocation = self.uniform_ring
+            .push(&frame_uniforms)
+            .expect("Uniform Ring Buffer Partition Exhausted!");
+
+        let draw_constants = DrawConstants{
+            transform: Mat4::from_rotation_z(elapsed_seconds),
+        };
+
         VulkanApp::record_command_buffer(
             &self.device
             , cmd_buffer[0]
             , self.graphics_pipeline
+            , self.pipeline_layout
+            , self.frame_uniforms_set
+            , frame_uniforms_allocation.dynamic_offset()
+            , &draw_constants
+            , &mut self.gpu_profiler
+            , &mut self.upload_manager
             , &self.vertex_buffer
+            , vertex_buffer_ready
             , self.swapchain_framebuffers[image_index as usize]
             , self.render_pass
             , self.swapchain_context.swapchain_extent);
@@ -325,38 +571,284 @@ impl VulkanApp
 
     
 
-    fn create_render_pass(device: &ash::Device, attachment_format: vk::Format) -> vk::RenderPass
+    //Runs At The Frame Boundary, Before Anything Is Recorded With The Current Pipeline
+    //The Replaced Pipeline Goes Through The Deletion Queue, In Flight Frames May Still Use It
+    fn reload_changed_shaders(&mut self)
     {
+        for reload_batch in self.shader_hot_reloader.poll_results()
+        {
+            self.apply_shader_reload(reload_batch);
+        }
+    }
+
+    //Both Stages Of One Change Arrive Together, So The Pipeline Is Rebuilt Once With Both
+    fn apply_shader_reload(&mut self, reload_batch: Vec<ShaderReloadResult>)
+    {
+        let mut new_vertex_shader = None;
+        let mut new_fragment_shader = None;
+        let mut reload_failed = false;
+        for reload_result in reload_batch
+        {
+            match reload_result.result
+            {
+                Ok(shader) =>
+                {
+                    for warning in shader.warnings.iter()
+                    {
+                        println!("{}", warning);
+                    }
+                    match reload_result.shader_type
+                    {
+                        ShaderType::Vertex => new_vertex_shader = Some(shader),
+                        ShaderType::Fragment => new_fragment_shader = Some(shader),
+                        _ => {},
+                    }
+                }
+                Err(error) =>
+                {
+                    println!("Shader Reload Failed, Keeping The Last Good Pipeline\n{}", error);
+                    reload_failed = true;
+                }
+            }
+        }
+        if reload_failed || (new_vertex_shader.is_none() && new_fragment_shader.is_none())
+        {
+            return;
+        }
+
+        let vertex_shader = new_vertex_shader.as_ref().unwrap_or(&self.vertex_shader);
+        let fragment_shader = new_fragment_shader.as_ref().unwrap_or(&self.fragment_shader);
+        let pipeline_result = VulkanApp::create_graphics_pipeline(
+            &self.device
+            , &self.device_limits
+            , self.render_pass
+            , self.pipeline_cache.handle()
+            , &mut self.graphics_pipeline_cache
+            , &mut self.descriptor_layout_cache
+            , &VulkanApp::pipeline_desc_for_targets(&self.triangle_pipeline_desc, self.swapchain_context.swapchain_format, self.depth_format, self.msaa_samples)
+            , vertex_shader
+            , fragment_shader);
+
+        match pipeline_result
+        {
+            Ok((graphics_pipeline, pipeline_layout, set_layouts)) =>
+            {
+                if set_layouts.get(FRAME_UNIFORMS_SET as usize) != Some(&self.frame_uniforms_set_layout)
+                {
+                    println!("Shader Reload Rejected, FrameUniforms Layout Changed, Keeping The Last Good Pipeline");
+                    self.graphics_pipeline_cache.remove(graphics_pipeline);
+                    unsafe
+                    {
+                        self.device.destroy_pipeline(graphics_pipeline, None);
+                    }
+                    return;
+                }
+
+                //Unchanged SPIR-V Hits The Cache And Hands Back The Pipeline Already In Use
+                if graphics_pipeline != self.graphics_pipeline
+                {
+                    self.graphics_pipeline_cache.remove(self.graphics_pipeline);
+                    self.deletion_queue.defer(DeferredResource::Pipeline(self.graphics_pipeline));
+                }
+                self.graphics_pipeline = graphics_pipeline;
+                self.pipeline_layout = pipeline_layout;
+                if let Some(shader) = new_vertex_shader
+                {
+                    self.vertex_shader = shader;
+                }
+                if let Some(shader) = new_fragment_shader
+                {
+                    self.fragment_shader = shader;
+                }
+                println!("Reloaded {}", self.triangle_pipeline_desc.name);
+            }
+            Err(error) => println!("Shader Reload Rejected, Keeping The Last Good Pipeline: {}", error),
+        }
+    }
+
+    pub fn memory_report(&self) -> MemoryReport
+    {
+        self.memory_budget_tracker.report(&self.instance, &self.memory_allocator)
+    }
+
+    pub fn msaa_samples(&self) -> vk::SampleCountFlags
+    {
+        self.msaa_samples
+    }
+
+    //None When Bindless Mode Is Disabled
+    pub fn vertex_buffer_bindless(&self) -> Option<BindlessHandle>
+    {
+        self.vertex_buffer_bindless
+    }
+
+    pub fn supported_msaa_samples(&self) -> vk::SampleCountFlags
+    {
+        self.supported_msaa_samples
+    }
+
+    pub fn max_msaa_samples(&self) -> vk::SampleCountFlags
+    {
+        max_sample_count(self.supported_msaa_samples)
+    }
+
+    //Unsupported Counts Snap Down To The Next Supported One
+    //Render Targets And Pipeline Are Rebuilt At The Start Of The Next Frame
+    pub fn set_msaa_samples(&mut self, samples: vk::SampleCountFlags)
+    {
+        let samples = snap_sample_count(self.supported_msaa_samples, samples);
+        if samples != self.msaa_samples
+        {
+            println!("MSAA Sample Count Changed To {:?}", samples);
+            self.msaa_samples = samples;
+            self.render_targets_dirty = true;
+        }
+    }
+
+    //TYPE_1 -> Each Supported Count In Turn -> TYPE_1
+    fn cycle_msaa_samples(&mut self)
+    {
+        self.set_msaa_samples(next_sample_count(self.supported_msaa_samples, self.msaa_samples));
+    }
+
+    fn create_render_targets(
+        device: &ash::Device
+        , allocator: &mut TrackedAllocator
+        , swapchain_context: &SwapchainContext
+        , depth_format: vk::Format
+        , msaa_samples: vk::SampleCountFlags) -> (Option<GPUImage>, GPUImage)
+    {
+        let msaa_color_target = if msaa_samples != SampleCountFlags::TYPE_1
+        {
+            Some(GPUImage::create_gpu_image(
+                device
+                , allocator
+                , &GPUImageDesc::render_target(
+                    "MSAA Color Target"
+                    , swapchain_context.swapchain_format
+                    , swapchain_context.swapchain_extent
+                    , msaa_samples
+                    , vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSIENT_ATTACHMENT)))
+        }
+        else
+        {
+            None
+        };
+
+        let depth_target = GPUImage::create_gpu_image(
+            device
+            , allocator
+            , &GPUImageDesc::render_target(
+                "Depth Target"
+                , depth_format
+                , swapchain_context.swapchain_extent
+                , msaa_samples
+                , vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT));
+
+        (msaa_color_target, depth_target)
+    }
+
+    fn create_swapchain_framebuffers(
+        device: &ash::Device
+        , render_pass: vk::RenderPass
+        , swapchain_image_views: &[vk::ImageView]
+        , msaa_color_target: Option<&GPUImage>
+        , depth_target: &GPUImage
+        , extent: &vk::Extent2D) -> Vec<vk::Framebuffer>
+    {
+        let mut swapchain_framebuffers = vec![];
+        for &image_view in swapchain_image_views.iter()
+        {
+            //Attachment Order Must Match create_render_pass
+            let attachments = match msaa_color_target
+            {
+                Some(color_target) => vec![color_target.image_view, depth_target.image_view, image_view],
+                None => vec![image_view, depth_target.image_view],
+            };
+            let frame_buffer = create_framebuffer(
+                device
+                , render_pass
+                , &attachments
+                , extent);
+            swapchain_framebuffers.push(frame_buffer);
+        }
+        swapchain_framebuffers
+    }
+
+    fn create_render_pass(device: &ash::Device, attachment_format: vk::Format, depth_format: vk::Format, msaa_samples: vk::SampleCountFlags) -> vk::RenderPass
+    {
+        let is_multisampled = msaa_samples != SampleCountFlags::TYPE_1;
+
+        //When Multisampling, The Color Attachment Is Resolved Into The Swapchain Image And Never Stored
         let color_attachment = vk::AttachmentDescription::builder()
             .format(attachment_format)
-            .samples(SampleCountFlags::TYPE_1)
+            .samples(msaa_samples)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(if is_multisampled {vk::AttachmentStoreOp::DONT_CARE} else {vk::AttachmentStoreOp::STORE})
+            .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
+            .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .final_layout(if is_multisampled {vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL} else {vk::ImageLayout::PRESENT_SRC_KHR});
+
+        let depth_attachment = vk::AttachmentDescription::builder()
+            .format(depth_format)
+            .samples(msaa_samples)
             .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::DONT_CARE)
+            .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
+            .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .final_layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+
+        let resolve_attachment = vk::AttachmentDescription::builder()
+            .format(attachment_format)
+            .samples(SampleCountFlags::TYPE_1)
+            .load_op(vk::AttachmentLoadOp::DONT_CARE)
             .store_op(vk::AttachmentStoreOp::STORE)
             .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
             .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
             .initial_layout(vk::ImageLayout::UNDEFINED)
             .final_layout(vk::ImageLayout::PRESENT_SRC_KHR);
 
-        let render_pass_attachments = [color_attachment.build()];
+        let mut render_pass_attachments = vec![color_attachment.build(), depth_attachment.build()];
+        if is_multisampled
+        {
+            render_pass_attachments.push(resolve_attachment.build());
+        }
 
         let color_attachment_refs = [vk::AttachmentReference::builder()
             .attachment(0)
             .layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
             .build()];
 
-        let subpasses = [
-            vk::SubpassDescription::builder()
-                .color_attachments(&color_attachment_refs)
-                .build()];
+        let depth_attachment_ref = vk::AttachmentReference::builder()
+            .attachment(1)
+            .layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL)
+            .build();
+
+        let resolve_attachment_refs = [vk::AttachmentReference::builder()
+            .attachment(2)
+            .layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .build()];
+
+        let mut subpass = vk::SubpassDescription::builder()
+            .color_attachments(&color_attachment_refs)
+            .depth_stencil_attachment(&depth_attachment_ref);
+        if is_multisampled
+        {
+            subpass = subpass.resolve_attachments(&resolve_attachment_refs);
+        }
+
+        let subpasses = [subpass.build()];
 
         let subpass_dependencies = [
             vk::SubpassDependency::builder()
                 .src_subpass(vk::SUBPASS_EXTERNAL)
                 .dst_subpass(0)
-                .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
-                .dst_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
-                .src_access_mask(vk::AccessFlags::empty())
-                .dst_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+                .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS)
+                .dst_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS)
+                .src_access_mask(vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE)
+                .dst_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE)
                 .dependency_flags(vk::DependencyFlags::empty())
                 .build()];
         let renderpass_create_info = vk::RenderPassCreateInfo::builder()
@@ -372,187 +864,57 @@ impl VulkanApp
         }
     }
 
-    fn create_graphics_pipeline(device: &ash::Device, swapchain_context: &SwapchainContext, render_pass: vk::RenderPass) -> (vk::Pipeline, vk::PipelineLayout) {
-        let shader_src_vert = load_and_compile_shader_src("shaders/testShader.glsl", ShaderType::Vertex);
-        let shader_src_frag = load_and_compile_shader_src("shaders/testShader.glsl", ShaderType::Fragment);
-
-        let vertex_module = VulkanApp::create_shader_module(&device, shader_src_vert.compiledData.as_binary());
-        let fragment_module = VulkanApp::create_shader_module(&device, shader_src_frag.compiledData.as_binary());
-
-        let shader_stage = [vk::PipelineShaderStageCreateInfo::builder()
-            .flags(PipelineShaderStageCreateFlags::empty())
-            .module(vertex_module)
-            .name(&shader_src_vert.entryPoint)
-            .stage(ShaderStageFlags::VERTEX).build(),
-            vk::PipelineShaderStageCreateInfo::builder()
-            .flags(PipelineShaderStageCreateFlags::empty())
-            .module(fragment_module)
-            .name(&shader_src_frag.entryPoint)
-            .stage(ShaderStageFlags::FRAGMENT).build()];
-
-        let vertex_binding_description = Vertex::get_binding_description();
-        let vertex_attribute_description = Vertex::get_attribute_descriptions();
-
-        let vertex_input_state_create_info = vk::PipelineVertexInputStateCreateInfo::builder()
-            .vertex_binding_descriptions(&vertex_binding_description)
-            .vertex_attribute_descriptions(&vertex_attribute_description);
-
-        let input_assembly_state_create_info = vk::PipelineInputAssemblyStateCreateInfo::builder()
-            .primitive_restart_enable(false)
-            .topology(PrimitiveTopology::TRIANGLE_LIST);
-
-        let viewport = vk::Viewport::builder()
-            .x(0.0)
-            .y(0.0)
-            .width(swapchain_context.swapchain_extent.width as f32)
-            .height(swapchain_context.swapchain_extent.height as f32)
-            .min_depth(0.0)
-            .max_depth(1.0);
-
-        let scissors = vk::Rect2D::builder()
-            .offset(Offset2D{x: 0, y: 0})
-            .extent(swapchain_context.swapchain_extent);
-
-        let viewport_state_create_info = vk::PipelineViewportStateCreateInfo::builder()
-            .scissors(std::slice::from_ref(&scissors))
-            .viewports(std::slice::from_ref(&viewport));
-
-        let rasterizer_state_create_info = vk::PipelineRasterizationStateCreateInfo::builder()
-            .depth_clamp_enable(false)
-            .cull_mode(CullModeFlags::BACK)
-            .front_face(FrontFace::CLOCKWISE)
-            .line_width(1.0)
-            .polygon_mode(PolygonMode::FILL)
-            .rasterizer_discard_enable(false)
-            .depth_bias_enable(false)
-            .depth_bias_clamp(0.0)
-            .depth_bias_constant_factor(0.0)
-            .depth_bias_slope_factor(0.0);
-
-        let multisample_state_create_info = vk::PipelineMultisampleStateCreateInfo::builder()
-            .rasterization_samples(SampleCountFlags::TYPE_1)
-            .sample_shading_enable(false)
-            .min_sample_shading(0.0)
-            .sample_mask(&[])
-            .alpha_to_one_enable(false)
-            .alpha_to_coverage_enable(false);
-
-        let stencil_state = vk::StencilOpState::builder()
-            .fail_op(vk::StencilOp::KEEP)
-            .pass_op(vk::StencilOp::KEEP)
-            .depth_fail_op(vk::StencilOp::KEEP)
-            .compare_op(vk::CompareOp::ALWAYS)
-            .compare_mask(0)
-            .write_mask(0)
-            .reference(0);
-
-        let depth_state_create_info = vk::PipelineDepthStencilStateCreateInfo::builder()
-            .depth_test_enable(false)
-            .depth_write_enable(false)
-            .depth_compare_op(vk::CompareOp::LESS_OR_EQUAL)
-            .depth_bounds_test_enable(false)
-            .stencil_test_enable(false)
-            .front(*stencil_state)
-            .back(*stencil_state)
-            .max_depth_bounds(1.0)
-            .min_depth_bounds(0.0);
-
-        let color_blend_attachment_states = [vk::PipelineColorBlendAttachmentState::builder()
-            .blend_enable(false)
-            .color_write_mask(vk::ColorComponentFlags::RGBA)
-            .src_color_blend_factor(vk::BlendFactor::ONE)
-            .dst_color_blend_factor(vk::BlendFactor::ZERO)
-            .color_blend_op(vk::BlendOp::ADD)
-            .build()];
-
-        let color_blend_state = vk::PipelineColorBlendStateCreateInfo::builder()
-            .logic_op_enable(false)
-            .logic_op(vk::LogicOp::COPY)
-            .attachments(&color_blend_attachment_states)
-            .blend_constants([0.0, 0.0, 0.0, 0.0]);
-
-        let pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::builder()
-            .set_layouts(&[])
-            .push_constant_ranges(&[]);
-
-        let pipeline_layout = unsafe{
-            device
-                .create_pipeline_layout(&pipeline_layout_create_info, None)
-                .expect("Failed To Create Pipeline Layout")
-        };
-
-
-        let pipeline_create_info = vk::GraphicsPipelineCreateInfo::builder()
-            .stages(&shader_stage)
-            .viewport_state(&viewport_state_create_info)
-            .vertex_input_state(&vertex_input_state_create_info)
-            .input_assembly_state(&input_assembly_state_create_info)
-            .rasterization_state(&rasterizer_state_create_info)
-            .multisample_state(&multisample_state_create_info)
-            .depth_stencil_state(&depth_state_create_info)
-            .color_blend_state(&color_blend_state)
-            .layout(pipeline_layout)
-            .render_pass(render_pass)
-            .subpass(0);
-
-        let graphics_pipeline = unsafe{
-            device
-                .create_graphics_pipelines(
-                    vk::PipelineCache::null(),
-                    std::slice::from_ref(&pipeline_create_info),
-                    None,
-                )
-                .expect("Failed To Create Graphics Pipeline")
-        };
-
-        unsafe
+    //No Pipeline To Fall Back To At Startup, So A Broken Shader Still Stops The App, But With Readable Diagnostics
+    fn compile_triangle_shaders(pipeline_desc: &GraphicsPipelineDesc, shader_include_resolver: &ShaderIncludeResolver, shader_cache: Option<&ShaderCache>) -> (CompiledShader, CompiledShader)
+    {
+        let vertex_shader = compile_shader_file(&pipeline_desc.vertex_shader, ShaderType::Vertex, shader_include_resolver, shader_cache)
+            .unwrap_or_else(|error| panic!("{}", error));
+        let fragment_shader = compile_shader_file(&pipeline_desc.fragment_shader, ShaderType::Fragment, shader_include_resolver, shader_cache)
+            .unwrap_or_else(|error| panic!("{}", error));
+        for warning in vertex_shader.warnings.iter().chain(fragment_shader.warnings.iter())
         {
-            device.destroy_shader_module(vertex_module, None);
-            device.destroy_shader_module(fragment_module, None);
+            println!("{}", warning);
         }
-        (graphics_pipeline[0], pipeline_layout)
+        (vertex_shader, fragment_shader)
     }
 
-    //std::mem::size_of_val(&VERTICES_DATA) as u64
-    fn create_gpu_buffer(
-        device:& ash::Device
-        ,allocator: &mut vulkan::Allocator
-        ,buffer_size: u64
-        ,buffer_usage: vk::BufferUsageFlags
-        ,memory_location: gpu_allocator::MemoryLocation) -> (vk::Buffer, vulkan::Allocation)
+    //The Desc Loaded From Disk Knows Nothing About The Swapchain, MSAA Can Also Change At Runtime
+    fn pipeline_desc_for_targets(pipeline_desc: &GraphicsPipelineDesc, color_format: vk::Format, depth_format: vk::Format, msaa_samples: vk::SampleCountFlags) -> GraphicsPipelineDesc
     {
-        let vertex_buffer_create_info = vk::BufferCreateInfo::builder()
-            .size(buffer_size)
-            .usage(buffer_usage)
-            .sharing_mode(vk::SharingMode::EXCLUSIVE)
-            .queue_family_indices(&[]);
-
-        let vertex_buffer = unsafe{
-            device
-                .create_buffer(&vertex_buffer_create_info, None)
-                .expect("Failed To Create Vertex Buffer!")
-        };
-
-        let allocation_requirement = unsafe{
-            device
-                .get_buffer_memory_requirements(vertex_buffer)
-        };
-
-        let allocation = allocator.allocate(&vulkan::AllocationCreateDesc{
-            name: "Custom Vertex Buffer",
-            requirements: allocation_requirement,
-            location: memory_location,
-            linear: true,
-            allocation_scheme: vulkan::AllocationScheme::GpuAllocatorManaged,
-        }).expect("Failed To Create Buffer Memory Allocation!");
-
-        unsafe{
-            device
-                .bind_buffer_memory(vertex_buffer, allocation.memory(), allocation.offset())
-                .expect("Failed To Bind Memory To Vertex Buffer!")
-        };
+        GraphicsPipelineDesc{
+            color_formats: vec![color_format],
+            depth_format: Some(depth_format),
+            samples: msaa_samples,
+            ..pipeline_desc.clone()
+        }
+    }
 
-        (vertex_buffer, allocation)
+    //Mismatches Are Reported Here Rather Than As Garbage On Screen Or A Validation Error At Draw Time
+    fn create_graphics_pipeline(
+        device: &ash::Device
+        , device_limits: &vk::PhysicalDeviceLimits
+        , render_pass: vk::RenderPass
+        , pipeline_cache: vk::PipelineCache
+        , graphics_pipeline_cache: &mut GraphicsPipelineCache
+        , descriptor_layout_cache: &mut DescriptorLayoutCache
+        , pipeline_desc: &GraphicsPipelineDesc
+        , vertex_shader: &CompiledShader
+        , fragment_shader: &CompiledShader) -> Result<(vk::Pipeline, vk::PipelineLayout, Vec<vk::DescriptorSetLayout>), GraphicsPipelineError> {
+        pipeline_desc.validate_samples(device_limits.framebuffer_color_sample_counts & device_limits.framebuffer_depth_sample_counts)?;
+        let vertex_reflection = vertex_shader.reflect()?;
+        let fragment_reflection = fragment_shader.reflect()?;
+
+        validate_vertex_input(&vertex_reflection, &pipeline_desc.vertex_layout.binding_descriptions(), &pipeline_desc.vertex_layout.attribute_descriptions())?;
+        validate_stage_interface(&vertex_reflection, &fragment_reflection)?;
+        DRAW_CONSTANTS_RANGE.validate(device_limits, &[&vertex_reflection, &fragment_reflection])?;
+
+        let mut pipeline_layout_desc = PipelineLayoutDesc::from_reflections(&[&vertex_reflection, &fragment_reflection])?;
+        pipeline_layout_desc.push_constant_ranges = vec![DRAW_CONSTANTS_RANGE.vk_range()];
+        pipeline_layout_desc.override_descriptor_type(FRAME_UNIFORMS_SET, FRAME_UNIFORMS_BINDING, vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC);
+        let (pipeline_layout, set_layouts) = graphics_pipeline_cache.get_or_create_layout(device, descriptor_layout_cache, &pipeline_layout_desc);
+
+        let graphics_pipeline = graphics_pipeline_cache.get_or_create(device, pipeline_cache, pipeline_desc, pipeline_layout, render_pass, vertex_shader, fragment_shader)?;
+        Ok((graphics_pipeline, pipeline_layout, set_layouts))
     }
 
     pub fn create_shader_module(device: &ash::Device, binarySource: &[u32]) -> vk::ShaderModule {
@@ -567,60 +929,39 @@ impl VulkanApp
         }
     }
 
-    fn upload_vertex_buffer_data_through_tmp_command(
-        &mut self)
-    {
-        let command_buffer = self.onetime_command_group.allocate_command_buffers(&self.device, 1)[0];
-
-        let mut staging_buffer = GPUBuffer::create_gpu_buffer(
-            &self.device
-            , &mut self.memory_allocator
-            , std::mem::size_of_val(&VERTICES_DATA) as u64
-            , vk::BufferUsageFlags::TRANSFER_SRC
-            , gpu_allocator::MemoryLocation::CpuToGpu);
-
-        unsafe{
-            let data_ptr = staging_buffer.allocation.mapped_ptr()
-                .expect("Failed To Get Mapped Memory").as_ptr() as *mut Vertex;
-            data_ptr.copy_from_nonoverlapping(VERTICES_DATA.as_ptr(), VERTICES_DATA.len());
-            //self.device.unmap_memory(staging_buffer.allocation.memory());
-        }
-
-        let command_buffer_begin_info = vk::CommandBufferBeginInfo::builder()
-        .flags(CommandBufferUsageFlags::SIMULTANEOUS_USE);
-
-        unsafe
-        {
-            self.device
-                .begin_command_buffer(command_buffer, &command_buffer_begin_info)
-                .expect("Begin Unload Commandbuffer Failed!");
-
-            GPUBuffer::cmd_copy_buffer(&self.device, command_buffer, &staging_buffer, &self.vertex_buffer);
-
-            self.device
-                .end_command_buffer(command_buffer)
-                .expect("Failed To End Upload Command Buffer");
-        }
-
-        self.onetime_command_group.submit_and_wait(&self.device, self._graphics_queue);
-
-        GPUBuffer::destroy_gpu_buffer(&mut staging_buffer, &self.device, &mut self.memory_allocator);
-    }
-
     fn record_command_buffer(device: &ash::Device
         , command_buffer: vk::CommandBuffer
         , graphics_pipeline: vk::Pipeline
-        , vertex_buffer: &GPUBuffer
+        , pipeline_layout: vk::PipelineLayout
+        , frame_uniforms_set: vk::DescriptorSet
+        , frame_uniforms_offset: u32
+        , draw_constants: &DrawConstants
+        , gpu_profiler: &mut GpuProfiler
+        , upload_manager: &mut UploadManager
+        , vertex_buffer: &TypedGPUBuffer<Vertex>
+        , vertex_buffer_ready: bool
         , framebuffer: vk::Framebuffer
         , render_pass: vk::RenderPass
         , extent: vk::Extent2D)
     {
+        //Resolve Attachment (If Any) Is Not Cleared, But Still Needs A Slot
         let clear_values = [
             vk::ClearValue{
                 color: vk::ClearColorValue{
                     float32: [0.0, 0.0, 0.0, 1.0]
                 }
-            }
+            },
+            vk::ClearValue{
+                depth_stencil: vk::ClearDepthStencilValue{
+                    depth: 1.0,
+                    stencil: 0,
+                }
+            },
+            vk::ClearValue{
+                color: vk::ClearColorValue{
+                    float32: [0.0, 0.0, 0.0, 1.0]
+                }
+            },
         ];
 
         let command_buffer_begin_info = vk::CommandBufferBeginInfo::builder()
@@ -640,25 +981,64 @@ impl VulkanApp
                 .begin_command_buffer(command_buffer, &command_buffer_begin_info)
                 .expect("Begin Command Buffer Failed!");
 
-            device
-                .cmd_begin_render_pass(command_buffer, &render_pass_begin_info, vk::SubpassContents::INLINE);
-
-            device
-                .cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::GRAPHICS, graphics_pipeline);
-
-            let vertex_buffers = [vertex_buffer.buffer];
+            gpu_profiler.cmd_begin_frame(device, command_buffer);
+            gpu_profiler.cmd_begin_scope(device, command_buffer, "Frame");
 
-            let offsets = [0_u64];
+            //Barriers Cannot Go Inside The Render Pass, So Uploads Are Acquired First
+            upload_manager.cmd_acquire_completed_uploads(device, command_buffer);
 
+            gpu_profiler.cmd_begin_scope(device, command_buffer, "Main Pass");
             device
-                .cmd_bind_vertex_buffers(command_buffer, 0_u32, &vertex_buffers, &offsets);
+                .cmd_begin_render_pass(command_buffer, &render_pass_begin_info, vk::SubpassContents::INLINE);
 
-            device
-                .cmd_draw(command_buffer, VERTICES_DATA.len() as u32, 1, 0, 0);
+            if vertex_buffer_ready
+            {
+                device
+                    .cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::GRAPHICS, graphics_pipeline);
+
+                //Viewport And Scissor Are Dynamic State, Pipelines Survive A Resize Unchanged
+                let viewport = vk::Viewport{
+                    x: 0.0,
+                    y: 0.0,
+                    width: extent.width as f32,
+                    height: extent.height as f32,
+                    min_depth: 0.0,
+                    max_depth: 1.0,
+                };
+                let scissor = vk::Rect2D{
+                    offset: vk::Offset2D{x: 0, y: 0},
+                    extent,
+                };
+                device.cmd_set_viewport(command_buffer, 0, std::slice::from_ref(&viewport));
+                device.cmd_set_scissor(command_buffer, 0, std::slice::from_ref(&scissor));
+
+                let vertex_buffers = [vertex_buffer.buffer()];
+
+                let offsets = [0_u64];
+
+                device
+                    .cmd_bind_vertex_buffers(command_buffer, 0_u32, &vertex_buffers, &offsets);
+
+                device
+                    .cmd_bind_descriptor_sets(
+                        command_buffer
+                        , vk::PipelineBindPoint::GRAPHICS
+                        , pipeline_layout
+                        , FRAME_UNIFORMS_SET
+                        , &[frame_uniforms_set]
+                        , &[frame_uniforms_offset]);
+
+                DRAW_CONSTANTS_RANGE.cmd_push(device, command_buffer, pipeline_layout, draw_constants);
+
+                device
+                    .cmd_draw(command_buffer, vertex_buffer.len() as u32, 1, 0, 0);
+            }
 
             device
                 .cmd_end_render_pass(command_buffer);
+            gpu_profiler.cmd_end_scope(device, command_buffer);
 
+            gpu_profiler.cmd_end_scope(device, command_buffer);
             device
                 .end_command_buffer(command_buffer)
                 .expect("Failed To End Command Buffer");
@@ -673,16 +1053,12 @@ impl VulkanApp
             surface: self.surface_context.surface
         };
 
-        //Pipeline -> RenderPass -> Framebuffers -> Imageviews -> Swapchain all need to be recreated
+        //Pipeline -> RenderPass -> Framebuffers -> Render Targets -> Imageviews -> Swapchain all need to be recreated
         unsafe
         {
             //wait idle before destruction
             self.device.device_wait_idle()
             .expect("Device Wait Idle Failed!");
-            //Pipeline
-            self.device.destroy_pipeline(self.graphics_pipeline, None);
-            //Pipeline Layout
-            self.device.destroy_pipeline_layout(self.pipeline_layout, None);
             //Framebuffers
             for &framebuffer in self.swapchain_framebuffers.iter()
             {
@@ -698,8 +1074,15 @@ impl VulkanApp
             //Swapchain
             self.swapchain_context.swapchain_loader.destroy_swapchain(self.swapchain_context.swapchain, None);
         }
+        //Pipeline And Render Targets Are Not Tied To The Swapchain, So Let The Deletion Queue Retire Them, The Layout Stays Cached
+        self.graphics_pipeline_cache.remove(self.graphics_pipeline);
+        self.deletion_queue.defer(DeferredResource::Pipeline(self.graphics_pipeline));
+        if let Some(color_target) = self.msaa_color_target.as_mut()
+        {
+            self.deletion_queue.defer(DeferredResource::from_image(color_target));
+        }
+        self.deletion_queue.defer(DeferredResource::from_image(&mut self.depth_target));
 
-        
         //Swapchain Context
         self.swapchain_context = create_swapchain(
             &self.instance, 
@@ -713,23 +1096,30 @@ impl VulkanApp
             self.swapchain_context.swapchain_format,
             &self.swapchain_context.swapchain_images);
 
+        //Multisample Render Targets
+        (self.msaa_color_target, self.depth_target) = VulkanApp::create_render_targets(
+            &self.device
+            , &mut self.memory_allocator
+            , &self.swapchain_context
+            , self.depth_format
+            , self.msaa_samples);
+
         //Render Pass
-        self.render_pass = VulkanApp::create_render_pass(&self.device, self.swapchain_context.swapchain_format);
+        self.render_pass = VulkanApp::create_render_pass(&self.device, self.swapchain_context.swapchain_format, self.depth_format, self.msaa_samples);
 
         //Swapchain Framebuffers
-        self.swapchain_framebuffers.clear();
-        for &image_view in self.swapchain_image_views.iter()
-        {
-            let frame_buffer = create_framebuffer(
-                &self.device
-                , self.render_pass
-                , &[image_view]
-                , &self.swapchain_context.swapchain_extent);
-                self.swapchain_framebuffers.push(frame_buffer);
-        }
+        self.swapchain_framebuffers = VulkanApp::create_swapchain_framebuffers(
+            &self.device
+            , self.render_pass
+            , &self.swapchain_image_views
+            , self.msaa_color_target.as_ref()
+            , &self.depth_target
+            , &self.swapchain_context.swapchain_extent);
 
         //Graphics Pipeline
-        (self.graphics_pipeline, self.pipeline_layout) = VulkanApp::create_graphics_pipeline(&self.device, &self.swapchain_context, self.render_pass);
+        let pipeline_desc = VulkanApp::pipeline_desc_for_targets(&self.triangle_pipeline_desc, self.swapchain_context.swapchain_format, self.depth_format, self.msaa_samples);
+        (self.graphics_pipeline, self.pipeline_layout, _) = VulkanApp::create_graphics_pipeline(&self.device, &self.device_limits, self.render_pass, self.pipeline_cache.handle(), &mut self.graphics_pipeline_cache, &mut self.descriptor_layout_cache, &pipeline_desc, &self.vertex_shader, &self.fragment_shader)
+            .expect("Last Good Shaders No Longer Build A Pipeline");
 
     }
 }
@@ -744,6 +1134,9 @@ impl Drop for VulkanApp
         self.device.device_wait_idle()
             .expect("Device Wait Idle Failed!");
 
+        //Everything Still Waiting On A Frame Fence Can Go Now
+        self.deletion_queue.flush_all(&self.device, &mut self.memory_allocator);
+
         //destroy sync context
         self.frame_sync_context.destroy_context(&self.device);
 
@@ -753,13 +1146,37 @@ impl Drop for VulkanApp
             cmd_group.destroy_group(&self.device);
         }
         self.onetime_command_group.destroy_group(&self.device);
-        GPUBuffer::destroy_gpu_buffer(&mut self.vertex_buffer, &self.device, &mut self.memory_allocator);
+        self.sampler_cache.destroy_cache(&self.device);
+        self.gpu_profiler.destroy_profiler(&self.device);
+        if let Err(error) = self.pipeline_cache.save(&self.device)
+        {
+            println!("Failed To Save Pipeline Cache {}: {}", self.pipeline_cache.path().display(), error);
+        }
+        self.pipeline_cache.destroy_cache(&self.device);
+        self.descriptor_layout_cache.destroy_cache(&self.device);
+        if let Some(bindless_heap) = self.bindless_heap.as_mut()
+        {
+            if let Some(vertex_buffer_bindless) = self.vertex_buffer_bindless.take()
+            {
+                bindless_heap.release(vertex_buffer_bindless);
+            }
+            bindless_heap.destroy_heap(&self.device);
+        }
+        self.persistent_descriptor_allocator.destroy_allocator(&self.device);
+        self.uniform_ring.destroy_ring_buffer(&self.device, &mut self.memory_allocator);
+        self.upload_manager.destroy_upload_manager(&self.device, &mut self.memory_allocator);
+        self.vertex_buffer.destroy_typed_buffer(&self.device, &mut self.memory_allocator);
+        //Render Targets
+        if let Some(color_target) = self.msaa_color_target.as_mut()
+        {
+            GPUImage::destroy_gpu_image(color_target, &self.device, &mut self.memory_allocator);
+        }
+        GPUImage::destroy_gpu_image(&mut self.depth_target, &self.device, &mut self.memory_allocator);
+        self.memory_allocator.print_leak_report();
         std::mem::ManuallyDrop::drop(&mut self.memory_allocator);
         
-        //Pipeline
-        self.device.destroy_pipeline(self.graphics_pipeline, None);
-        //Pipeline Layout
-        self.device.destroy_pipeline_layout(self.pipeline_layout, None);
+        //Pipelines And Pipeline Layouts, graphics_pipeline And pipeline_layout Included
+        self.graphics_pipeline_cache.destroy_cache(&self.device);
         //Framebuffers
         for &framebuffer in self.swapchain_framebuffers.iter()
         {
@@ -796,4 +1213,25 @@ impl WindowLoopContext for VulkanApp
     fn on_drawframe(&mut self){
         self.draw_frame();
     }
+    fn on_key_pressed(&mut self, key: VirtualKeyCode) {
+        if key == VirtualKeyCode::M
+        {
+            self.cycle_msaa_samples();
+        }
+        else if key == VirtualKeyCode::B
+        {
+            print!("{}", self.memory_report());
+        }
+        else if key == VirtualKeyCode::P
+        {
+            match self.gpu_profiler.latest_profile()
+            {
+                Some(profile) => print!("{}", profile),
+                None => println!("No Frame Profile Available Yet"),
+            }
+        }
+    }
+    fn take_window_title(&mut self) -> Option<String> {
+        self.pending_window_title.take()
+    }
 }
\ No newline at end of file